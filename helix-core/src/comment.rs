@@ -1,9 +1,11 @@
 //! This module contains the functionality toggle comments on lines over the selection
-//! using the comment character defined in the user's `languages.toml`
+//! using the comment character defined in the user's `languages.toml`, as well as
+//! block comments around the selections using the block comment tokens.
 
 use crate::{
-    find_first_non_whitespace_char, Change, Rope, RopeSlice, Selection, Tendril, Transaction,
+    find_first_non_whitespace_char, Change, Range, Rope, RopeSlice, Selection, Tendril, Transaction,
 };
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
 /// Given text, a comment token, and a set of line indices, returns the following:
@@ -94,9 +96,125 @@ pub fn toggle_line_comments(doc: &Rope, selection: &Selection, token: Option<&st
     Transaction::change(doc, changes.into_iter())
 }
 
+/// The tokens starting and ending a block comment, such as `/*` and `*/`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockCommentToken {
+    pub start: String,
+    pub end: String,
+}
+
+impl Default for BlockCommentToken {
+    fn default() -> Self {
+        Self {
+            start: "/*".to_string(),
+            end: "*/".to_string(),
+        }
+    }
+}
+
+/// Given text, block comment tokens, and a selection, returns the following:
+/// - Whether the selections should be considered commented
+///     - If any of the non-blank selections is uncommented, all are considered as such.
+/// - The text to change for each non-blank selection
+///     - The selected text with surrounding whitespace trimmed, as a char range.
+///     - Whether the start token is followed by a space, and the end token preceded by one.
+///       These margins are removed together with the tokens when uncommenting.
+fn find_block_comments(
+    tokens: &BlockCommentToken,
+    text: RopeSlice,
+    selection: &Selection,
+) -> (bool, Vec<(usize, usize, bool, bool)>) {
+    let mut commented = true;
+    let mut to_change = Vec::with_capacity(selection.len());
+    let start_len = tokens.start.chars().count();
+    let end_len = tokens.end.chars().count();
+    let is_space = |pos: usize| text.get_char(pos) == Some(' ');
+
+    for range in selection {
+        let slice = text.slice(range.from()..range.to());
+        let from = match find_first_non_whitespace_char(slice) {
+            Some(pos) => range.from() + pos,
+            // blank selections don't get changed.
+            None => continue,
+        };
+        let mut to = range.to();
+        while to > from && text.char(to - 1).is_whitespace() {
+            to -= 1;
+        }
+
+        let fragment = text.slice(from..to);
+        let is_commented = to - from >= start_len + end_len
+            && fragment.slice(..start_len) == tokens.start.as_str()
+            && fragment.slice(to - from - end_len..) == tokens.end.as_str();
+        if !is_commented {
+            commented = false;
+            to_change.push((from, to, false, false));
+            continue;
+        }
+
+        let (inner_from, inner_to) = (from + start_len, to - end_len);
+        let start_margin = inner_from < inner_to && is_space(inner_from);
+        let end_margin = inner_from + (start_margin as usize) < inner_to && is_space(inner_to - 1);
+        to_change.push((from, to, start_margin, end_margin));
+    }
+
+    (commented, to_change)
+}
+
+/// Toggles block comments around the selections, ignoring whitespace at their edges.
+#[must_use]
+pub fn toggle_block_comments(
+    doc: &Rope,
+    selection: &Selection,
+    tokens: &BlockCommentToken,
+) -> Transaction {
+    let text = doc.slice(..);
+    let (commented, to_change) = find_block_comments(tokens, text, selection);
+
+    let mut changes: Vec<Change> = Vec::with_capacity(to_change.len() * 2);
+    if commented {
+        let start_len = tokens.start.chars().count();
+        let end_len = tokens.end.chars().count();
+        for (from, to, start_margin, end_margin) in to_change {
+            changes.push((from, from + start_len + start_margin as usize, None));
+            changes.push((to - end_len - end_margin as usize, to, None));
+        }
+        return Transaction::change(doc, changes.into_iter());
+    }
+
+    let start = Tendril::from(format!("{} ", tokens.start));
+    let end = Tendril::from(format!(" {}", tokens.end));
+    // commented selections grow to include the inserted tokens
+    let added = start.chars().count() + end.chars().count();
+    let mut to_change = to_change.into_iter().peekable();
+    let mut offs = 0;
+    let ranges = selection
+        .iter()
+        .map(|range| {
+            let mut grown = 0;
+            if let Some(&(from, to, _, _)) = to_change.peek() {
+                if range.from() <= from && to <= range.to() {
+                    changes.push((from, from, Some(start.clone())));
+                    changes.push((to, to, Some(end.clone())));
+                    to_change.next();
+                    grown = added;
+                }
+            }
+            let range = Range::new(offs + range.from(), offs + range.to() + grown)
+                .with_direction(range.direction());
+            offs += grown;
+            range
+        })
+        .collect();
+
+    Transaction::change(doc, changes.into_iter())
+        .with_selection(Selection::new(ranges, selection.primary_index()))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use smallvec::SmallVec;
 
     #[test]
     fn test_find_line_comment() {
@@ -149,4 +267,54 @@ fn test_find_line_comment() {
 
         // TODO: account for uncommenting with uneven comment indentation
     }
+
+    #[test]
+    fn test_toggle_block_comments() {
+        let tokens = BlockCommentToken::default();
+        let mut doc = Rope::from("let a = 1 + 2;\nlet b =   3;");
+        // select `1 + 2` and `  3` with its leading whitespace
+        let mut selection = Selection::new(
+            SmallVec::from_slice(&[Range::new(8, 13), Range::new(23, 26)]),
+            0,
+        );
+
+        let text = doc.slice(..);
+        let res = find_block_comments(&tokens, text, &selection);
+        assert_eq!(
+            res,
+            (false, vec![(8, 13, false, false), (25, 26, false, false)])
+        );
+
+        // comment
+        let transaction = toggle_block_comments(&doc, &selection, &tokens);
+        transaction.apply(&mut doc);
+        selection = transaction.selection().unwrap().clone();
+
+        assert_eq!(doc, "let a = /* 1 + 2 */;\nlet b =   /* 3 */;");
+        assert_eq!(
+            selection.fragments(doc.slice(..)).collect::<Vec<_>>(),
+            ["/* 1 + 2 */", "  /* 3 */"]
+        );
+
+        // uncomment
+        let transaction = toggle_block_comments(&doc, &selection, &tokens);
+        transaction.apply(&mut doc);
+        assert_eq!(doc, "let a = 1 + 2;\nlet b =   3;");
+
+        // 0 margin comments
+        doc = Rope::from("/*1*/");
+        selection = Selection::single(0, doc.len_chars());
+
+        let transaction = toggle_block_comments(&doc, &selection, &tokens);
+        transaction.apply(&mut doc);
+        assert_eq!(doc, "1");
+
+        // empty comments
+        doc = Rope::from("/* */");
+        selection = Selection::single(0, doc.len_chars());
+
+        let transaction = toggle_block_comments(&doc, &selection, &tokens);
+        transaction.apply(&mut doc);
+        assert_eq!(doc, "");
+    }
 }
@@ -0,0 +1,141 @@
+//! Completion sources which work without a language server, such as the words of open
+//! documents and paths on the file system.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{chars::char_is_word, RopeSlice};
+
+/// A source of completion items. Sources are listed in order of their ranking in the
+/// `completion-sources` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CompletionSource {
+    /// Items returned by the document's language server.
+    LanguageServer,
+    /// Snippets configured for the document's language.
+    Snippet,
+    /// Paths relative to the document or the workspace.
+    Path,
+    /// Words from open documents.
+    Buffer,
+    /// The contents of registers.
+    Register,
+}
+
+impl CompletionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LanguageServer => "language-server",
+            Self::Snippet => "snippet",
+            Self::Path => "path",
+            Self::Buffer => "buffer",
+            Self::Register => "register",
+        }
+    }
+}
+
+/// Words shorter than this aren't offered by the buffer source.
+pub const MIN_WORD_LEN: usize = 3;
+
+/// Counts the occurrences of each word of at least `min_len` chars in `text`.
+pub fn count_words(text: RopeSlice, min_len: usize) -> HashMap<String, usize> {
+    let mut words = HashMap::new();
+    let mut word = String::new();
+    let mut len = 0;
+
+    for ch in text.chars().chain(std::iter::once('\n')) {
+        if char_is_word(ch) {
+            word.push(ch);
+            len += 1;
+            continue;
+        }
+
+        if len >= min_len {
+            *words.entry(std::mem::take(&mut word)).or_insert(0) += 1;
+        }
+        word.clear();
+        len = 0;
+    }
+    words
+}
+
+/// Returns the word touching the char position `pos`, usually the word being completed.
+pub fn word_at(text: RopeSlice, pos: usize) -> Option<String> {
+    let mut before = text.chars_at(pos);
+    before.reverse();
+    let start = pos - before.take_while(|&ch| char_is_word(ch)).count();
+    let end = pos
+        + text
+            .chars_at(pos)
+            .take_while(|&ch| char_is_word(ch))
+            .count();
+    (start < end).then(|| text.slice(start..end).into())
+}
+
+/// Returns the path typed right before `cursor`, such as `./src/ma` or `~/.config/`, along
+/// with the char position where it starts. Only text containing a `/` is considered a path.
+pub fn path_before(text: RopeSlice, cursor: usize) -> Option<(usize, String)> {
+    let is_path_char = |ch: char| {
+        !ch.is_whitespace()
+            && !matches!(
+                ch,
+                '"' | '\'' | '`' | '(' | ')' | '[' | ']' | '{' | '}' | '<' | '>' | ',' | ';' | '='
+            )
+    };
+
+    let mut chars = text.chars_at(cursor);
+    chars.reverse();
+    let len = chars.take_while(|ch| is_path_char(*ch)).count();
+    let start = cursor - len;
+
+    let path: String = text.slice(start..cursor).into();
+    if !path.contains('/') || path.contains("://") {
+        return None;
+    }
+
+    Some((start, path))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Rope;
+
+    #[test]
+    fn test_count_words() {
+        let text = Rope::from("let foo = foobar(ba, foo_baz);\nfoo");
+        let mut words: Vec<_> = count_words(text.slice(..), 3).into_iter().collect();
+        words.sort();
+        assert_eq!(
+            words,
+            [
+                ("foo".to_string(), 2),
+                ("foo_baz".to_string(), 1),
+                ("foobar".to_string(), 1),
+                ("let".to_string(), 1)
+            ]
+        );
+
+        assert_eq!(word_at(text.slice(..), 10), Some("foobar".to_string()));
+        assert_eq!(word_at(text.slice(..), 16), Some("foobar".to_string()));
+        assert_eq!(word_at(text.slice(..), 9), None);
+    }
+
+    #[test]
+    fn test_path_before() {
+        let text = Rope::from("include \"./src/ma\" and ~/.config/ or https://example.com/a");
+
+        assert_eq!(
+            path_before(text.slice(..), 17),
+            Some((9, "./src/ma".to_string()))
+        );
+        assert_eq!(
+            path_before(text.slice(..), 33),
+            Some((23, "~/.config/".to_string()))
+        );
+        assert_eq!(path_before(text.slice(..), 7), None);
+        assert_eq!(path_before(text.slice(..), text.len_chars()), None);
+    }
+}
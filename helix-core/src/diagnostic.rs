@@ -35,6 +35,16 @@ pub enum DiagnosticTag {
     Deprecated,
 }
 
+/// What produced a [`Diagnostic`], so that each provider can replace its own diagnostics
+/// of a document without clearing the others.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum DiagnosticProvider {
+    #[default]
+    Lsp,
+    /// A task run by `:make`, identified by its name
+    Task(String),
+}
+
 /// Corresponds to [`lsp_types::Diagnostic`](https://docs.rs/lsp-types/0.94.0/lsp_types/struct.Diagnostic.html)
 #[derive(Debug, Clone)]
 pub struct Diagnostic {
@@ -46,4 +56,5 @@ pub struct Diagnostic {
     pub tags: Vec<DiagnosticTag>,
     pub source: Option<String>,
     pub data: Option<serde_json::Value>,
+    pub provider: DiagnosticProvider,
 }
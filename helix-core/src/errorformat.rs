@@ -0,0 +1,252 @@
+//! Parsing of compiler and tool output into file locations using patterns in the style of
+//! vim's `errorformat`.
+//!
+//! A pattern is matched against a whole line. These directives are supported, any other
+//! text is matched literally:
+//!
+//! - `%f` the file name
+//! - `%l` the line number
+//! - `%c` the column number
+//! - `%m` the message
+//! - `%t` a single character for the severity: `e`rror, `w`arning, `i`nfo, `n`ote or `h`int
+//! - `%*` any text, which is skipped
+//! - `%%` a literal `%`
+//!
+//! Patterns without `%m` take the message from the closest line before the match which
+//! didn't match any pattern, as rustc prints the message above the location.
+
+use regex::Regex;
+
+use crate::diagnostic::Severity;
+
+/// Patterns for the output of common tools such as rustc, gcc, clang and `grep -n`.
+pub const DEFAULT_ERROR_FORMATS: &[&str] = &[
+    "%*--> %f:%l:%c",
+    "%f:%l:%c: %t%*: %m",
+    "%f:%l:%c: %m",
+    "%f:%l: %m",
+    "%f:%l:%m",
+];
+
+#[derive(Debug, Clone)]
+pub struct ErrorFormat {
+    regex: Regex,
+    has_message: bool,
+}
+
+/// A location found in tool output. Lines and columns are 0-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorLocation {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    pub severity: Option<Severity>,
+}
+
+impl ErrorFormat {
+    pub fn new(format: &str) -> Result<Self, regex::Error> {
+        let mut pattern = String::from("^");
+        let mut has_message = false;
+        let mut literal = String::new();
+        let mut chars = format.chars();
+
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                literal.push(ch);
+                continue;
+            }
+
+            let directive = match chars.next() {
+                Some('%') => {
+                    literal.push('%');
+                    continue;
+                }
+                Some(directive) => directive,
+                None => return Err(regex::Error::Syntax("trailing '%' in pattern".into())),
+            };
+            pattern.push_str(&regex::escape(&literal));
+            literal.clear();
+
+            let group = match directive {
+                'f' => r"(?P<file>(?:[A-Za-z]:)?[^\s:][^:]*?)",
+                'l' => r"(?P<line>\d+)",
+                'c' => r"(?P<column>\d+)",
+                'm' => {
+                    has_message = true;
+                    r"(?P<message>.*)"
+                }
+                't' => r"(?P<type>[A-Za-z])",
+                '*' => r".*?",
+                directive => {
+                    return Err(regex::Error::Syntax(format!(
+                        "unknown directive '%{}' in pattern",
+                        directive
+                    )))
+                }
+            };
+            pattern.push_str(group);
+        }
+        pattern.push_str(&regex::escape(&literal));
+        pattern.push('$');
+
+        Ok(Self {
+            regex: Regex::new(&pattern)?,
+            has_message,
+        })
+    }
+
+    fn matches(&self, line: &str) -> Option<ErrorLocation> {
+        let captures = self.regex.captures(line)?;
+        let number = |name| {
+            captures
+                .name(name)
+                .and_then(|number| number.as_str().parse::<usize>().ok())
+                .map_or(0, |number| number.saturating_sub(1))
+        };
+        let message = captures
+            .name("message")
+            .map(|message| message.as_str().trim().to_string())
+            .unwrap_or_default();
+        let severity = captures
+            .name("type")
+            .and_then(|ty| severity_from_char(ty.as_str().chars().next()?))
+            .or_else(|| severity_from_message(&message));
+
+        Some(ErrorLocation {
+            path: captures.name("file")?.as_str().to_string(),
+            line: number("line"),
+            column: number("column"),
+            message,
+            severity,
+        })
+    }
+}
+
+/// Finds the locations in `text` matching any of `formats`, the first matching format
+/// of each line wins.
+pub fn parse(formats: &[ErrorFormat], text: &str) -> Vec<ErrorLocation> {
+    let mut locations = Vec::new();
+    let mut last_unmatched = "";
+
+    for line in text.lines() {
+        let line = line.trim_end();
+        let location = formats
+            .iter()
+            .find_map(|format| Some((format, format.matches(line)?)));
+
+        match location {
+            Some((format, mut location)) => {
+                if !format.has_message {
+                    location.message = last_unmatched.trim().to_string();
+                    if location.severity.is_none() {
+                        location.severity = severity_from_message(&location.message);
+                    }
+                }
+                locations.push(location);
+            }
+            None if !line.trim().is_empty() => last_unmatched = line,
+            None => (),
+        }
+    }
+
+    locations
+}
+
+fn severity_from_char(ch: char) -> Option<Severity> {
+    match ch.to_ascii_lowercase() {
+        'e' => Some(Severity::Error),
+        'w' => Some(Severity::Warning),
+        'i' | 'n' => Some(Severity::Info),
+        'h' => Some(Severity::Hint),
+        _ => None,
+    }
+}
+
+/// Messages like `error[E0308]: mismatched types` start with their severity.
+fn severity_from_message(message: &str) -> Option<Severity> {
+    let word: String = message
+        .chars()
+        .take_while(|ch| ch.is_ascii_alphabetic())
+        .collect();
+    match word.to_ascii_lowercase().as_str() {
+        "error" => Some(Severity::Error),
+        "warning" => Some(Severity::Warning),
+        "info" | "note" => Some(Severity::Info),
+        "hint" | "help" => Some(Severity::Hint),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn default_formats() -> Vec<ErrorFormat> {
+        DEFAULT_ERROR_FORMATS
+            .iter()
+            .map(|format| ErrorFormat::new(format).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_default_formats() {
+        let output = "\
+error[E0308]: mismatched types
+  --> src/main.rs:4:18
+   |
+main.c:12:5: warning: unused variable 'x'
+lib/util.py:7: SyntaxError: invalid syntax
+README.md:3:some text
+";
+        let locations = parse(&default_formats(), output);
+
+        assert_eq!(
+            locations,
+            [
+                ErrorLocation {
+                    path: "src/main.rs".into(),
+                    line: 3,
+                    column: 17,
+                    message: "error[E0308]: mismatched types".into(),
+                    severity: Some(Severity::Error),
+                },
+                ErrorLocation {
+                    path: "main.c".into(),
+                    line: 11,
+                    column: 4,
+                    message: "unused variable 'x'".into(),
+                    severity: Some(Severity::Warning),
+                },
+                ErrorLocation {
+                    path: "lib/util.py".into(),
+                    line: 6,
+                    column: 0,
+                    message: "SyntaxError: invalid syntax".into(),
+                    severity: None,
+                },
+                ErrorLocation {
+                    path: "README.md".into(),
+                    line: 2,
+                    column: 0,
+                    message: "some text".into(),
+                    severity: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_format_literals() {
+        let format = ErrorFormat::new("[%t] %f(%l,%c): 100%% %m").unwrap();
+        let locations = parse(&[format], "[W] foo.cs(1,2): 100% sure");
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0].path, "foo.cs");
+        assert_eq!((locations[0].line, locations[0].column), (0, 1));
+        assert_eq!(locations[0].message, "sure");
+        assert_eq!(locations[0].severity, Some(Severity::Warning));
+
+        assert!(ErrorFormat::new("%f:%x").is_err());
+        assert!(ErrorFormat::new("%f:%").is_err());
+    }
+}
@@ -3,10 +3,12 @@
 pub mod auto_pairs;
 pub mod chars;
 pub mod comment;
+pub mod completion;
 pub mod config;
 pub mod diagnostic;
 pub mod diff;
 pub mod doc_formatter;
+pub mod errorformat;
 pub mod graphemes;
 pub mod history;
 pub mod increment;
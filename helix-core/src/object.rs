@@ -1,4 +1,5 @@
-use crate::{Range, RopeSlice, Selection, Syntax};
+use crate::{movement::Direction, Range, Rope, RopeSlice, Selection, Syntax, Tendril, Transaction};
+use smallvec::SmallVec;
 use tree_sitter::Node;
 
 pub fn expand_selection(syntax: &Syntax, text: RopeSlice, selection: Selection) -> Selection {
@@ -74,3 +75,281 @@ fn select_node_impl<F>(
         }
     })
 }
+
+/// Returns the outermost node spanning exactly the bytes `from..to`, or the smallest node
+/// containing them if there is no exact match.
+fn node_for_byte_range(syntax: &Syntax, from: usize, to: usize) -> Option<Node<'_>> {
+    let mut node = syntax
+        .tree()
+        .root_node()
+        .descendant_for_byte_range(from, to)?;
+    while let Some(parent) = node.parent() {
+        if parent.start_byte() != node.start_byte() || parent.end_byte() != node.end_byte() {
+            break;
+        }
+        node = parent;
+    }
+    Some(node)
+}
+
+/// A single structural edit: the text in `from..to` is replaced with `text`, and the range
+/// `range.0..range.1` (relative to the start of the replacement) is selected afterwards.
+struct NodeEdit {
+    from: usize,
+    to: usize,
+    text: Tendril,
+    range: (usize, usize),
+}
+
+/// Builds a transaction by calling `edit_fn` with the syntax node under each range. Edits that
+/// overlap a previous edit are dropped and their ranges are left in place.
+fn edit_nodes<F>(syntax: &Syntax, doc: &Rope, selection: &Selection, edit_fn: F) -> Transaction
+where
+    F: Fn(RopeSlice, Node) -> Option<NodeEdit>,
+{
+    let text = doc.slice(..);
+    let mut changes = Vec::with_capacity(selection.len());
+    // index of the range and its position in the new document
+    let mut edited = Vec::with_capacity(selection.len());
+    let mut last = 0;
+    let mut delta = 0isize;
+
+    for (i, range) in selection.iter().enumerate() {
+        let from = text.char_to_byte(range.from());
+        let to = text.char_to_byte(range.to());
+
+        let edit = match node_for_byte_range(syntax, from, to).and_then(|node| edit_fn(text, node))
+        {
+            Some(edit) if edit.from >= last => edit,
+            _ => continue,
+        };
+
+        let start = (edit.from as isize + delta) as usize;
+        let new_range = Range::new(start + edit.range.0, start + edit.range.1)
+            .with_direction(range.direction());
+        edited.push((i, new_range));
+
+        delta += edit.text.chars().count() as isize - (edit.to - edit.from) as isize;
+        last = edit.to;
+        changes.push((edit.from, edit.to, Some(edit.text)));
+    }
+
+    let transaction = Transaction::change(doc, changes.into_iter());
+    let ranges = selection
+        .iter()
+        .enumerate()
+        .map(|(i, range)| match edited.iter().find(|(j, _)| i == *j) {
+            Some((_, new_range)) => *new_range,
+            None => range.map(transaction.changes()),
+        })
+        .collect();
+    let selection = Selection::new(ranges, selection.primary_index());
+    transaction.with_selection(selection)
+}
+
+fn node_range(text: RopeSlice, node: Node) -> (usize, usize) {
+    (
+        text.byte_to_char(node.start_byte()),
+        text.byte_to_char(node.end_byte()),
+    )
+}
+
+/// Swaps the node under each selection with its next or previous named sibling, keeping the
+/// selection on the moved node.
+pub fn swap_sibling(
+    syntax: &Syntax,
+    doc: &Rope,
+    selection: &Selection,
+    direction: Direction,
+) -> Transaction {
+    edit_nodes(syntax, doc, selection, |text, node| {
+        let (first, second) = match direction {
+            Direction::Forward => (node, node.next_named_sibling()?),
+            Direction::Backward => (node.prev_named_sibling()?, node),
+        };
+        let (first_start, first_end) = node_range(text, first);
+        let (second_start, second_end) = node_range(text, second);
+
+        let mut swapped = String::new();
+        swapped.extend(text.slice(second_start..second_end).chunks());
+        swapped.extend(text.slice(first_end..second_start).chunks());
+        swapped.extend(text.slice(first_start..first_end).chunks());
+
+        let range = match direction {
+            Direction::Forward => (
+                swapped.chars().count() - (first_end - first_start),
+                swapped.chars().count(),
+            ),
+            Direction::Backward => (0, second_end - second_start),
+        };
+
+        Some(NodeEdit {
+            from: first_start,
+            to: second_end,
+            text: swapped.into(),
+            range,
+        })
+    })
+}
+
+/// Returns true for nodes enclosed in unnamed delimiters, such as argument lists or blocks.
+fn is_delimited(node: Node) -> bool {
+    let count = node.child_count();
+    count >= 2
+        && node.child(0).map_or(false, |child| !child.is_named())
+        && node
+            .child(count - 1)
+            .map_or(false, |child| !child.is_named())
+}
+
+/// Replaces the parent of the node under each selection with the node itself.
+///
+/// A delimited parent that ends its own parent (the arguments of a call or the body of an `if`)
+/// is treated as part of that construct, so the whole construct is replaced instead.
+pub fn raise_node(syntax: &Syntax, doc: &Rope, selection: &Selection) -> Transaction {
+    edit_nodes(syntax, doc, selection, |text, node| {
+        let mut parent = node.parent()?;
+        if is_delimited(parent) && parent.next_sibling().is_none() {
+            parent = parent.parent().unwrap_or(parent);
+        }
+        let (start, end) = node_range(text, node);
+        let (parent_start, parent_end) = node_range(text, parent);
+
+        Some(NodeEdit {
+            from: parent_start,
+            to: parent_end,
+            text: text.slice(start..end).to_string().into(),
+            range: (0, end - start),
+        })
+    })
+}
+
+/// Removes the delimiters of the nearest delimited node enclosing the node under each
+/// selection, keeping its contents and everything around it.
+pub fn splice_node(syntax: &Syntax, doc: &Rope, selection: &Selection) -> Transaction {
+    edit_nodes(syntax, doc, selection, |text, node| {
+        let mut delimited = node;
+        while !is_delimited(delimited) || delimited.named_child_count() == 0 {
+            delimited = delimited.parent()?;
+        }
+        let open = delimited.child(0)?;
+        let close = delimited.child(delimited.child_count() - 1)?;
+
+        let (start, end) = node_range(text, delimited);
+        let inner_start = text.byte_to_char(open.end_byte());
+        let inner_end = text.byte_to_char(close.start_byte());
+
+        Some(NodeEdit {
+            from: start,
+            to: end,
+            text: text.slice(inner_start..inner_end).to_string().into(),
+            range: (0, inner_end - inner_start),
+        })
+    })
+}
+
+/// Wraps each selection in `prefix` and `suffix`, selecting the wrapped text.
+pub fn wrap_selections(
+    doc: &Rope,
+    selection: &Selection,
+    prefix: &str,
+    suffix: &str,
+) -> Transaction {
+    let text = doc.slice(..);
+    let added = prefix.chars().count() + suffix.chars().count();
+    let mut ranges = SmallVec::with_capacity(selection.len());
+    let mut changes = Vec::with_capacity(selection.len());
+
+    for range in selection.iter() {
+        let (from, to) = (range.from(), range.to());
+        let start = from + added * ranges.len();
+        ranges.push(Range::new(start, start + to - from + added).with_direction(range.direction()));
+
+        let wrapped = format!("{}{}{}", prefix, text.slice(from..to), suffix);
+        changes.push((from, to, Some(wrapped.into())));
+    }
+
+    Transaction::change(doc, changes.into_iter())
+        .with_selection(Selection::new(ranges, selection.primary_index()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::syntax::{Configuration, HighlightConfiguration, Loader};
+    use std::sync::Arc;
+
+    fn rust_syntax(doc: &Rope) -> Syntax {
+        let loader = Loader::new(Configuration { language: vec![] });
+        let language = helix_loader::grammar::get_language("rust").unwrap();
+        let config = HighlightConfiguration::new(language, "", "", "").unwrap();
+        Syntax::new(doc, Arc::new(config), Arc::new(loader))
+    }
+
+    fn apply(doc: &mut Rope, transaction: &Transaction) -> Vec<String> {
+        assert!(transaction.apply(doc));
+        transaction
+            .selection()
+            .unwrap()
+            .fragments(doc.slice(..))
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_swap_sibling() {
+        let mut doc = Rope::from("f(a, bcd, e);");
+        let syntax = rust_syntax(&doc);
+
+        let selection = Selection::single(2, 3);
+        let transaction = swap_sibling(&syntax, &doc, &selection, Direction::Forward);
+        assert_eq!(apply(&mut doc, &transaction), ["a"]);
+        assert_eq!(doc, "f(bcd, a, e);");
+
+        let syntax = rust_syntax(&doc);
+        let selection = Selection::single(2, 5);
+        let transaction = swap_sibling(&syntax, &doc, &selection, Direction::Backward);
+        assert!(transaction.changes().is_empty());
+
+        let selection = Selection::single(10, 11);
+        let transaction = swap_sibling(&syntax, &doc, &selection, Direction::Backward);
+        assert_eq!(apply(&mut doc, &transaction), ["e"]);
+        assert_eq!(doc, "f(bcd, e, a);");
+    }
+
+    #[test]
+    fn test_raise_and_splice_node() {
+        let mut doc = Rope::from("f(g(a, b));");
+        let syntax = rust_syntax(&doc);
+
+        let selection = Selection::single(4, 5);
+        let transaction = raise_node(&syntax, &doc, &selection);
+        assert_eq!(apply(&mut doc, &transaction), ["a"]);
+        assert_eq!(doc, "f(a);");
+
+        let mut doc = Rope::from("f(g(a, b));");
+        let transaction = splice_node(&syntax, &doc, &selection);
+        assert_eq!(apply(&mut doc, &transaction), ["a, b"]);
+        assert_eq!(doc, "f(ga, b);");
+
+        let mut doc = Rope::from("let x = (a + b);");
+        let syntax = rust_syntax(&doc);
+        let selection = Selection::single(9, 10);
+        let transaction = splice_node(&syntax, &doc, &selection);
+        assert_eq!(apply(&mut doc, &transaction), ["a + b"]);
+        assert_eq!(doc, "let x = a + b;");
+    }
+
+    #[test]
+    fn test_wrap_selections() {
+        let mut doc = Rope::from("f(a, bcd);");
+        let selection = Selection::new(smallvec::smallvec![Range::new(2, 3), Range::new(7, 5)], 0);
+        let transaction = wrap_selections(&doc, &selection, "Some(", ")");
+        assert_eq!(apply(&mut doc, &transaction), ["Some(a)", "Some(bc)"]);
+        assert_eq!(doc, "f(Some(a), Some(bc)d);");
+        assert_eq!(
+            transaction.selection().unwrap().ranges()[1].direction(),
+            Direction::Backward
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //!
 //! All positioning is done via `char` offsets into the buffer.
 use crate::{
+    chars::char_is_word,
     graphemes::{
         ensure_grapheme_boundary_next, ensure_grapheme_boundary_prev, next_grapheme_boundary,
         prev_grapheme_boundary,
@@ -769,6 +770,74 @@ pub fn split_on_matches(
     Selection::new(result, 0)
 }
 
+/// Finds the next (or previous) occurrence of the primary range's text, wrapping around the
+/// end of the document. Occurrences overlapping an existing range are skipped. If the primary
+/// range covers exactly one word, only whole-word occurrences are considered.
+///
+/// Returns the new range, facing the same direction as the primary range, and whether the
+/// search wrapped around.
+pub fn find_occurrence(
+    text: RopeSlice,
+    selection: &Selection,
+    direction: Direction,
+) -> Option<(Range, bool)> {
+    let primary = selection.primary();
+    let fragment = primary.fragment(text);
+    if fragment.is_empty() {
+        return None;
+    }
+
+    let is_word = fragment.chars().all(char_is_word)
+        && (primary.from() == 0 || !char_is_word(text.char(primary.from() - 1)))
+        && (primary.to() == text.len_chars() || !char_is_word(text.char(primary.to())));
+    let pattern = if is_word {
+        format!("\\b{}\\b", crate::regex::escape(&fragment))
+    } else {
+        crate::regex::escape(&fragment)
+    };
+    let regex = crate::regex::Regex::new(&pattern).ok()?;
+
+    // TODO: can't avoid allocating the whole buffer since Regex can't operate on chunks yet
+    let contents = text.to_string();
+    let matches: Vec<_> = regex
+        .find_iter(&contents)
+        .map(|mat| {
+            let start = text.byte_to_char(mat.start());
+            let end = text.byte_to_char(mat.end());
+            Range::new(start, end).with_direction(primary.direction())
+        })
+        .collect();
+
+    // Split the matches into the ones in search direction and the ones that are only
+    // reachable by wrapping around.
+    let (ahead, behind): (Vec<_>, Vec<_>) = match direction {
+        Direction::Forward => matches
+            .into_iter()
+            .partition(|range| range.from() >= primary.to()),
+        Direction::Backward => {
+            let (behind, ahead) = matches
+                .into_iter()
+                .partition(|range| range.from() >= primary.to());
+            (ahead, behind)
+        }
+    };
+
+    let is_free = |range: &Range| !selection.iter().any(|existing| existing.overlaps(range));
+    match direction {
+        Direction::Forward => ahead
+            .into_iter()
+            .map(|range| (range, false))
+            .chain(behind.into_iter().map(|range| (range, true)))
+            .find(|(range, _)| is_free(range)),
+        Direction::Backward => ahead
+            .into_iter()
+            .rev()
+            .map(|range| (range, false))
+            .chain(behind.into_iter().rev().map(|range| (range, true)))
+            .find(|(range, _)| is_free(range)),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1251,4 +1320,57 @@ fn contains(a: Vec<(usize, usize)>, b: Vec<(usize, usize)>) -> bool {
             vec!((1, 2), (3, 4), (7, 9))
         ));
     }
+
+    #[test]
+    fn test_find_occurrence() {
+        let text = Rope::from("foo bar foobar foo bar foo");
+        let slice = text.slice(..);
+
+        // whole words only, skipping "foobar"
+        let selection = Selection::single(0, 3);
+        assert_eq!(
+            find_occurrence(slice, &selection, Direction::Forward),
+            Some((Range::new(15, 18), false))
+        );
+
+        // already selected occurrences are skipped
+        let selection = selection.push(Range::new(15, 18));
+        assert_eq!(
+            find_occurrence(slice, &selection, Direction::Forward),
+            Some((Range::new(23, 26), false))
+        );
+
+        // backwards search wraps around the start of the document
+        let selection = Selection::single(0, 3);
+        assert_eq!(
+            find_occurrence(slice, &selection, Direction::Backward),
+            Some((Range::new(23, 26), true))
+        );
+
+        // forward search wraps around the end of the document
+        let selection = Selection::single(18, 15);
+        assert_eq!(
+            find_occurrence(slice, &selection, Direction::Forward),
+            Some((Range::new(26, 23), false))
+        );
+        let selection = selection.push(Range::new(26, 23));
+        assert_eq!(
+            find_occurrence(slice, &selection, Direction::Forward),
+            Some((Range::new(3, 0), true))
+        );
+
+        // partial words match anywhere
+        let selection = Selection::single(1, 3);
+        assert_eq!(
+            find_occurrence(slice, &selection, Direction::Forward),
+            Some((Range::new(9, 11), false))
+        );
+
+        // nothing left to select
+        let selection = Selection::new(
+            smallvec![Range::new(0, 3), Range::new(15, 18), Range::new(23, 26)],
+            0,
+        );
+        assert_eq!(find_occurrence(slice, &selection, Direction::Forward), None);
+    }
 }
@@ -1,7 +1,10 @@
 use std::fmt::Display;
 
-use crate::{movement::Direction, search, Range, Selection};
+use crate::{movement::Direction, search, syntax::Syntax, Range, Selection};
+use once_cell::sync::Lazy;
+use regex::Regex;
 use ropey::RopeSlice;
+use tree_sitter::Node;
 
 pub const PAIRS: &[(char, char)] = &[
     ('(', ')'),
@@ -265,6 +268,265 @@ pub fn get_surround_pos(
     Ok(change_pos)
 }
 
+/// Find the start positions of the `n`th pair of multi-character surround strings around
+/// `range`, such as `/*` and `*/`. Pairs of different strings may be nested.
+pub fn find_nth_str_pairs_pos(
+    text: RopeSlice,
+    open: &str,
+    close: &str,
+    range: Range,
+    n: usize,
+) -> Result<(usize, usize)> {
+    if open.is_empty() || close.is_empty() {
+        return Err(Error::PairNotFound);
+    }
+    if range.to() > text.len_chars() {
+        return Err(Error::RangeExceedsText);
+    }
+
+    let open_len = open.chars().count();
+    let close_len = close.chars().count();
+    let matches_at = |pos: usize, s: &str, len: usize| {
+        text.get_slice(pos..pos + len)
+            .map_or(false, |slice| slice == s)
+    };
+    let nested = open != close;
+
+    if !nested {
+        let from = range.from().saturating_sub(open_len - 1);
+        if (from..=range.from()).any(|pos| matches_at(pos, open, open_len)) {
+            return Err(Error::CursorOnAmbiguousPair);
+        }
+    }
+
+    let mut open_pos = None;
+    let (mut depth, mut count) = (0, 0);
+    for pos in (0..=range.from()).rev() {
+        if nested && pos + close_len <= range.from() && matches_at(pos, close, close_len) {
+            depth += 1;
+        } else if matches_at(pos, open, open_len) {
+            if depth == 0 {
+                count += 1;
+                if count == n {
+                    open_pos = Some(pos);
+                    break;
+                }
+            } else {
+                depth -= 1;
+            }
+        }
+    }
+    let open_pos = open_pos.ok_or(Error::PairNotFound)?;
+
+    let start = (open_pos + open_len).max(range.to().saturating_sub(close_len));
+    let (mut depth, mut count) = (0, 0);
+    for pos in start..text.len_chars() {
+        if nested && pos >= range.to() && matches_at(pos, open, open_len) {
+            depth += 1;
+        } else if matches_at(pos, close, close_len) {
+            if depth == 0 {
+                count += 1;
+                if count == n {
+                    return Ok((open_pos, pos));
+                }
+            } else {
+                depth -= 1;
+            }
+        }
+    }
+
+    Err(Error::PairNotFound)
+}
+
+/// Find the char ranges of multi-character surround strings around every cursor. Like
+/// [get_surround_pos], the opening and closing ranges of each pair follow each other
+/// in a flat Vec.
+pub fn get_surround_str_pos(
+    text: RopeSlice,
+    selection: &Selection,
+    open: &str,
+    close: &str,
+    skip: usize,
+) -> Result<Vec<(usize, usize)>> {
+    let (open_len, close_len) = (open.chars().count(), close.chars().count());
+    let mut change_pos: Vec<(usize, usize)> = Vec::new();
+
+    for &range in selection {
+        let (open_pos, close_pos) = find_nth_str_pairs_pos(text, open, close, range, skip)?;
+        let open_range = (open_pos, open_pos + open_len);
+        let close_range = (close_pos, close_pos + close_len);
+        if change_pos
+            .iter()
+            .any(|&(from, to)| from < close_range.1 && open_range.0 < to)
+        {
+            return Err(Error::CursorOverlap);
+        }
+        change_pos.extend_from_slice(&[open_range, close_range]);
+    }
+    Ok(change_pos)
+}
+
+/// Char ranges of the opening and closing tag of an element, along with the ranges of
+/// the tag names inside them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TagPos {
+    pub open: (usize, usize),
+    pub open_name: (usize, usize),
+    pub close: (usize, usize),
+    pub close_name: (usize, usize),
+}
+
+/// Find the tags of the `n`th element enclosing `range` by scanning the text. Self-closing
+/// tags and unclosed tags such as `<br>` are skipped.
+pub fn find_nth_tag_pos(text: RopeSlice, range: Range, n: usize) -> Result<TagPos> {
+    static TAG_REGEX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"<(/?)([A-Za-z][\w:.-]*)[^<>]*?(/?)>").unwrap());
+
+    if range.to() > text.len_chars() {
+        return Err(Error::RangeExceedsText);
+    }
+
+    // TODO: can't avoid allocation since Regex can't operate on chunks yet
+    let string = text.to_string();
+    let from = text.char_to_byte(range.from());
+    let to = text.char_to_byte(range.to());
+
+    // open tags that are not closed yet: (name, tag, name range)
+    let mut stack = Vec::new();
+    // elements enclosing the range, innermost first
+    let mut elements = Vec::new();
+    for captures in TAG_REGEX.captures_iter(&string) {
+        let tag = captures.get(0).unwrap();
+        let name = captures.get(2).unwrap();
+        if !captures[3].is_empty() {
+            // self-closing tag
+            continue;
+        }
+        if captures[1].is_empty() {
+            stack.push((name.as_str(), tag.range(), name.range()));
+        } else if let Some(idx) = stack
+            .iter()
+            .rposition(|(open, _, _)| *open == name.as_str())
+        {
+            // unclosed tags inside this element (`<br>`, `<li>`, ...) are dropped
+            let (_, open, open_name) = stack[idx].clone();
+            stack.truncate(idx);
+            if open.start <= from && to <= tag.end() {
+                elements.push((open, open_name, tag.range(), name.range()));
+            }
+        }
+    }
+
+    let (open, open_name, close, close_name) = elements
+        .get(n.max(1) - 1)
+        .cloned()
+        .ok_or(Error::PairNotFound)?;
+    let to_chars = |range: std::ops::Range<usize>| {
+        (text.byte_to_char(range.start), text.byte_to_char(range.end))
+    };
+    Ok(TagPos {
+        open: to_chars(open),
+        open_name: to_chars(open_name),
+        close: to_chars(close),
+        close_name: to_chars(close_name),
+    })
+}
+
+/// Find the tags of the `n`th element enclosing `range` in the syntax tree. This works
+/// with grammars which represent elements as a node starting with an opening tag and
+/// ending with a closing tag, such as HTML, XML and JSX.
+pub fn find_nth_tag_pos_syntax(
+    syntax: &Syntax,
+    text: RopeSlice,
+    range: Range,
+    n: usize,
+) -> Result<TagPos> {
+    const OPEN_TAGS: &[&str] = &["start_tag", "jsx_opening_element", "STag"];
+    const CLOSE_TAGS: &[&str] = &["end_tag", "jsx_closing_element", "ETag"];
+
+    if range.to() > text.len_chars() {
+        return Err(Error::RangeExceedsText);
+    }
+
+    let from = text.char_to_byte(range.from());
+    let to = text.char_to_byte(range.to());
+    let mut node = syntax
+        .tree()
+        .root_node()
+        .descendant_for_byte_range(from, to);
+
+    let to_chars = |node: Node| {
+        (
+            text.byte_to_char(node.start_byte()),
+            text.byte_to_char(node.end_byte()),
+        )
+    };
+    // the tag name, or an empty range after the `<` or `</` if the tag has no name
+    let name_range = |tag: Node, offset: usize| match tag.named_child(0) {
+        Some(name) => to_chars(name),
+        None => {
+            let pos = text.byte_to_char(tag.start_byte()) + offset;
+            (pos, pos)
+        }
+    };
+
+    let mut count = 0;
+    while let Some(element) = node {
+        node = element.parent();
+
+        if element.child_count() < 2 {
+            continue;
+        }
+        let (open, close) = match (element.child(0), element.child(element.child_count() - 1)) {
+            (Some(open), Some(close))
+                if OPEN_TAGS.contains(&open.kind()) && CLOSE_TAGS.contains(&close.kind()) =>
+            {
+                (open, close)
+            }
+            _ => continue,
+        };
+
+        count += 1;
+        if count == n.max(1) {
+            return Ok(TagPos {
+                open: to_chars(open),
+                open_name: name_range(open, 1),
+                close: to_chars(close),
+                close_name: name_range(close, 2),
+            });
+        }
+    }
+
+    Err(Error::PairNotFound)
+}
+
+/// Find the tags enclosing every cursor. The syntax tree is used if it knows about tags,
+/// falling back to scanning the text otherwise.
+pub fn get_surround_tag_pos(
+    syntax: Option<&Syntax>,
+    text: RopeSlice,
+    selection: &Selection,
+    skip: usize,
+) -> Result<Vec<TagPos>> {
+    let mut tags: Vec<TagPos> = Vec::new();
+
+    for &range in selection {
+        let tag = match syntax {
+            Some(syntax) => find_nth_tag_pos_syntax(syntax, text, range, skip)
+                .or_else(|_| find_nth_tag_pos(text, range, skip))?,
+            None => find_nth_tag_pos(text, range, skip)?,
+        };
+        if tags
+            .iter()
+            .any(|other| other.open.0 < tag.close.1 && tag.open.0 < other.close.1)
+        {
+            return Err(Error::CursorOverlap);
+        }
+        tags.push(tag);
+    }
+    Ok(tags)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -382,6 +644,123 @@ fn test_find_nth_pairs_pos_inside_quote_ambiguous() {
         )
     }
 
+    #[test]
+    fn test_find_nth_str_pairs_pos() {
+        #[rustfmt::skip]
+        let (doc, selection, expectations) =
+            rope_with_selections_and_expectations(
+                "a /* b /* c */ d */ e",
+                "  _    _  ^ _    _   "
+            );
+
+        let range = selection.primary();
+        assert_eq!(
+            find_nth_str_pairs_pos(doc.slice(..), "/*", "*/", range, 1),
+            Ok((expectations[1], expectations[2]))
+        );
+        assert_eq!(
+            find_nth_str_pairs_pos(doc.slice(..), "/*", "*/", range, 2),
+            Ok((expectations[0], expectations[3]))
+        );
+        assert_eq!(
+            find_nth_str_pairs_pos(doc.slice(..), "/*", "*/", range, 3),
+            Err(Error::PairNotFound)
+        );
+
+        let doc = Rope::from("{% if %}");
+        assert_eq!(
+            find_nth_str_pairs_pos(doc.slice(..), "{%", "%}", Range::new(3, 5), 1),
+            Ok((0, 6))
+        );
+        let doc = Rope::from("a ** b ** c");
+        assert_eq!(
+            find_nth_str_pairs_pos(doc.slice(..), "**", "**", Range::point(5), 1),
+            Ok((2, 7))
+        );
+        assert_eq!(
+            find_nth_str_pairs_pos(doc.slice(..), "**", "**", Range::point(3), 1),
+            Err(Error::CursorOnAmbiguousPair)
+        );
+    }
+
+    #[test]
+    fn test_find_nth_tag_pos() {
+        let doc = Rope::from("<div id=\"a\"><p>text<br></p></div>");
+        let range = Range::point(16);
+
+        assert_eq!(
+            find_nth_tag_pos(doc.slice(..), range, 1),
+            Ok(TagPos {
+                open: (12, 15),
+                open_name: (13, 14),
+                close: (23, 27),
+                close_name: (25, 26),
+            })
+        );
+        assert_eq!(
+            find_nth_tag_pos(doc.slice(..), range, 2),
+            Ok(TagPos {
+                open: (0, 12),
+                open_name: (1, 4),
+                close: (27, 33),
+                close_name: (29, 32),
+            })
+        );
+        assert_eq!(
+            find_nth_tag_pos(doc.slice(..), range, 3),
+            Err(Error::PairNotFound)
+        );
+    }
+
+    #[test]
+    fn test_get_surround_tag_pos() {
+        let doc = Rope::from("<p>a</p><p>b</p>");
+        let selection = Selection::new(
+            SmallVec::from_slice(&[Range::point(3), Range::point(11)]),
+            0,
+        );
+        let tags = get_surround_tag_pos(None, doc.slice(..), &selection, 1).unwrap();
+        assert_eq!(tags[0].open, (0, 3));
+        assert_eq!(tags[1].open, (8, 11));
+
+        // the tags of nested elements would be changed out of order
+        let doc = Rope::from("<div><p>a</p>b</div>");
+        let selection = Selection::new(
+            SmallVec::from_slice(&[Range::point(8), Range::point(13)]),
+            0,
+        );
+        assert_eq!(
+            get_surround_tag_pos(None, doc.slice(..), &selection, 1),
+            Err(Error::CursorOverlap)
+        );
+    }
+
+    #[test]
+    fn test_find_nth_tag_pos_syntax() {
+        use crate::syntax::{Configuration, HighlightConfiguration, Loader};
+        use std::sync::Arc;
+
+        let doc = Rope::from("<div id=\"a\"><p>text<br></p></div>");
+        let loader = Loader::new(Configuration { language: vec![] });
+        let language = helix_loader::grammar::get_language("html").unwrap();
+        let config = HighlightConfiguration::new(language, "", "", "").unwrap();
+        let syntax = Syntax::new(&doc, Arc::new(config), Arc::new(loader));
+
+        let range = Range::point(16);
+        assert_eq!(
+            find_nth_tag_pos_syntax(&syntax, doc.slice(..), range, 1),
+            find_nth_tag_pos(doc.slice(..), range, 1)
+        );
+        assert_eq!(
+            find_nth_tag_pos_syntax(&syntax, doc.slice(..), range, 2),
+            find_nth_tag_pos(doc.slice(..), range, 2)
+        );
+        assert_eq!(
+            find_nth_tag_pos_syntax(&syntax, doc.slice(..), range, 3),
+            Err(Error::PairNotFound)
+        );
+    }
+
     // Create a Rope and a matching Selection using a specification language.
     // ^ is a single-point selection.
     // _ is an expected index. These are returned as a Vec<usize> for use in assertions.
@@ -1,6 +1,8 @@
 use crate::{
     auto_pairs::AutoPairs,
     chars::char_is_line_ending,
+    comment::BlockCommentToken,
+    completion::CompletionSource,
     diagnostic::Severity,
     regex::Regex,
     transaction::{ChangeSet, Operation},
@@ -82,6 +84,7 @@ pub struct LanguageConfiguration {
     pub shebangs: Vec<String>, // interpreter(s) associated with language
     pub roots: Vec<String>,        // these indicate project roots <.git, Cargo.toml>
     pub comment_token: Option<String>,
+    pub block_comment_tokens: Option<BlockCommentToken>,
     pub text_width: Option<usize>,
     pub soft_wrap: Option<SoftWrap>,
 
@@ -116,6 +119,8 @@ pub struct LanguageConfiguration {
     pub(crate) indent_query: OnceCell<Option<Query>>,
     #[serde(skip)]
     pub(crate) textobject_query: OnceCell<Option<TextObjectQuery>>,
+    #[serde(skip)]
+    pub(crate) locals_query: OnceCell<Option<Query>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub debugger: Option<DebugAdapterConfig>,
 
@@ -126,8 +131,25 @@ pub struct LanguageConfiguration {
     #[serde(default, skip_serializing, deserialize_with = "deserialize_auto_pairs")]
     pub auto_pairs: Option<AutoPairs>,
 
+    /// Multi-character surround pairs, keyed by the character used to refer to them
+    /// in the surround commands, e.g. `c = ["/*", "*/"]`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub surround_pairs: HashMap<char, (String, String)>,
+
     pub rulers: Option<Vec<u16>>, // if set, override editor's rulers
 
+    /// Completion sources in order of their ranking. Overrides the editor's
+    /// `completion-sources` for this language.
+    pub completion_sources: Option<Vec<CompletionSource>>,
+    /// Snippets offered as completions, keyed by their trigger. Snippets use the LSP
+    /// snippet syntax, e.g. `fn ${1:name}() {\n\t$0\n}`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub snippets: HashMap<String, String>,
+    /// Tasks like `build` or `test` run by `:make`, keyed by their name. Overrides the
+    /// editor's tasks with the same name.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tasks: HashMap<String, TaskConfiguration>,
+
     /// Hardcoded LSP root directories relative to the workspace root, like `examples` or `tools/fuzz`.
     /// Falling back to the current working directory if none are configured.
     pub workspace_lsp_roots: Option<Vec<PathBuf>>,
@@ -208,6 +230,8 @@ fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct LanguageServerConfiguration {
+    /// The server to spawn. May be empty when connecting to a running server over `socket`.
+    #[serde(default)]
     pub command: String,
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -217,6 +241,14 @@ pub struct LanguageServerConfiguration {
     #[serde(default = "default_timeout")]
     pub timeout: u64,
     pub language_id: Option<String>,
+    /// The address of a running server to connect to instead of spawning one: `host:port` for
+    /// TCP or the path of a Unix socket.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub socket: Option<String>,
+    /// The argument telling the spawned server which port to listen on, with `{}` replaced by
+    /// the port, to connect to it over TCP rather than its stdio.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port_arg: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -228,6 +260,17 @@ pub struct FormatterConfiguration {
     pub args: Vec<String>,
 }
 
+/// A shell command run by `:make`, like building or testing the project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct TaskConfiguration {
+    pub command: String,
+    /// Patterns for the locations in the output of the command, see [crate::errorformat].
+    /// Defaults to the editor's `error-formats`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_formats: Option<Vec<String>>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct AdvancedCompletion {
@@ -529,6 +572,13 @@ pub fn textobject_query(&self) -> Option<&TextObjectQuery> {
             .as_ref()
     }
 
+    /// The query of the local definitions and references, which name the variables.
+    pub fn locals_query(&self) -> Option<&Query> {
+        self.locals_query
+            .get_or_init(|| self.load_query("locals.scm"))
+            .as_ref()
+    }
+
     pub fn scope(&self) -> &str {
         &self.scope
     }
@@ -1077,6 +1127,33 @@ pub fn tree(&self) -> &Tree {
         self.layers[self.root].tree()
     }
 
+    /// Returns the language configuration of the innermost layer containing the byte range,
+    /// which is an injected language if the range is inside an injection.
+    pub fn language_config_for_byte_range(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Option<Arc<LanguageConfiguration>> {
+        let layer = self
+            .layers
+            .values()
+            .filter(|layer| {
+                layer
+                    .ranges
+                    .iter()
+                    .any(|range| range.start_byte <= start && end <= range.end_byte)
+            })
+            .max_by_key(|layer| layer.depth)?;
+
+        self.loader
+            .language_configs()
+            .find(|config| match config.highlight_config.get() {
+                Some(Some(highlight_config)) => Arc::ptr_eq(highlight_config, &layer.config),
+                _ => false,
+            })
+            .cloned()
+    }
+
     /// Iterate over the highlighted regions for a given slice of source code.
     pub fn highlight_iter<'a>(
         &'a self,
@@ -2339,6 +2416,29 @@ fn test_textobject_queries() {
         // test("multiple_nodes_grouped", 1..36);
     }
 
+    #[test]
+    fn test_language_config_for_byte_range() {
+        let injections = r#"
+        ((script_element (raw_text) @injection.content)
+         (#set! injection.language "python"))
+        "#;
+        let source = Rope::from_str("<p>text</p><script>x = 1</script>");
+
+        let loader = Loader::new(crate::config::default_syntax_loader());
+        let language = get_language("html").unwrap();
+        let config = HighlightConfiguration::new(language, "", injections, "").unwrap();
+        let syntax = Syntax::new(&source, Arc::new(config), Arc::new(loader));
+
+        let language_id = |byte| {
+            syntax
+                .language_config_for_byte_range(byte, byte)
+                .map(|config| config.language_id.clone())
+        };
+        assert_eq!(language_id(20).as_deref(), Some("python"));
+        // the root layer's configuration isn't known to the loader here
+        assert_eq!(language_id(4), None);
+    }
+
     #[test]
     fn test_parser() {
         let highlight_names: Vec<String> = [
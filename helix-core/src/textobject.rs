@@ -5,11 +5,12 @@
 
 use crate::chars::{categorize_char, char_is_whitespace, CharCategory};
 use crate::graphemes::{next_grapheme_boundary, prev_grapheme_boundary};
+use crate::indent::indent_level_for_line;
 use crate::line_ending::rope_is_line_ending;
 use crate::movement::Direction;
 use crate::surround;
-use crate::syntax::LanguageConfiguration;
-use crate::Range;
+use crate::syntax::{CapturedNode, LanguageConfiguration};
+use crate::{find_first_non_whitespace_char, Range};
 
 fn find_word_boundary(slice: RopeSlice, mut pos: usize, direction: Direction, long: bool) -> usize {
     use CharCategory::{Eol, Whitespace};
@@ -250,6 +251,297 @@ fn textobject_pair_surround_impl(
         .unwrap_or(range)
 }
 
+/// Select the lines sharing the indentation level of the cursor line. Blank lines are
+/// included if they are surrounded by lines of the block. `Around` also selects the less
+/// indented line introducing the block and a closing line such as `}` or `end` after it.
+/// A count selects the block `count - 1` levels further out.
+pub fn textobject_indentation_level(
+    slice: RopeSlice,
+    range: Range,
+    textobject: TextObject,
+    count: usize,
+    tab_width: usize,
+    indent_width: usize,
+) -> Range {
+    let is_blank = |line| find_first_non_whitespace_char(slice.line(line)).is_none();
+    let indent_level = |line| indent_level_for_line(slice.line(line), tab_width, indent_width);
+    let last_line = slice.len_lines() - 1;
+    let prev_non_blank = |line: usize| (0..line).rev().find(|&line| !is_blank(line));
+    let next_non_blank = |line: usize| (line + 1..=last_line).find(|&line| !is_blank(line));
+
+    let cursor_line = range.cursor_line(slice);
+    let mut line = match (cursor_line..=last_line)
+        .chain((0..cursor_line).rev())
+        .find(|&line| !is_blank(line))
+    {
+        Some(line) => line,
+        None => return range,
+    };
+
+    let (mut start, mut end) = (line, line);
+    for i in 0..count.max(1) {
+        if i > 0 {
+            // step out to the line introducing the current block
+            match prev_non_blank(start) {
+                Some(header) => line = header,
+                None => break,
+            }
+        }
+
+        let level = indent_level(line);
+        start = line;
+        while start > 0 && (is_blank(start - 1) || indent_level(start - 1) >= level) {
+            start -= 1;
+        }
+        end = line;
+        while end < last_line && (is_blank(end + 1) || indent_level(end + 1) >= level) {
+            end += 1;
+        }
+        while is_blank(start) {
+            start += 1;
+        }
+        while is_blank(end) {
+            end -= 1;
+        }
+    }
+
+    match textobject {
+        TextObject::Inside => {}
+        TextObject::Around => {
+            if let Some(header) = prev_non_blank(start) {
+                start = header;
+            }
+            if let Some(footer) = next_non_blank(end) {
+                let first_char = find_first_non_whitespace_char(slice.line(footer))
+                    .map(|pos| slice.line(footer).slice(pos..));
+                if first_char.map_or(false, |rest| {
+                    matches!(rest.get_char(0), Some(')' | ']' | '}'))
+                        || rest.chars().take(3).eq("end".chars())
+                }) {
+                    end = footer;
+                }
+            }
+        }
+        TextObject::Movement => unreachable!(),
+    }
+
+    Range::new(slice.line_to_char(start), slice.line_to_char(end + 1))
+}
+
+/// Select the (X)HTML/XML element enclosing the range, by matching opening and closing tags.
+/// `Inside` selects the element's content and `Around` includes the tags themselves.
+pub fn textobject_tag(
+    slice: RopeSlice,
+    range: Range,
+    textobject: TextObject,
+    count: usize,
+) -> Range {
+    let tag = match surround::find_nth_tag_pos(slice, range, count) {
+        Ok(tag) => tag,
+        Err(_) => return range,
+    };
+    let (start, end) = match textobject {
+        TextObject::Inside => (tag.open.1, tag.close.0),
+        TextObject::Around => (tag.open.0, tag.close.1),
+        TextObject::Movement => unreachable!(),
+    };
+
+    Range::new(start, end)
+}
+
+/// Select the number under the cursor, such as `42`, `0xff`, `1_000` or `1.5e-3`. `Around`
+/// also selects a leading sign.
+pub fn textobject_number(slice: RopeSlice, range: Range, textobject: TextObject) -> Range {
+    let is_number_char = |ch: char| ch.is_ascii_alphanumeric() || ch == '_';
+    let char_is = |pos: usize, f: &dyn Fn(char) -> bool| slice.get_char(pos).map_or(false, f);
+    let len = slice.len_chars();
+
+    let pos = range.cursor(slice);
+    if !char_is(pos, &|ch| is_number_char(ch) || ch == '.') {
+        return range;
+    }
+
+    let (mut start, mut end) = (pos, pos);
+    while start > 0 && char_is(start - 1, &is_number_char) {
+        start -= 1;
+    }
+    while end < len && char_is(end, &is_number_char) {
+        end += 1;
+    }
+
+    // integer part before a decimal point
+    if start >= 2
+        && char_is(start - 1, &|ch| ch == '.')
+        && char_is(start - 2, &|ch| ch.is_ascii_digit())
+    {
+        start -= 1;
+        while start > 0 && char_is(start - 1, &is_number_char) {
+            start -= 1;
+        }
+    }
+    // fractional part after a decimal point
+    if char_is(end, &|ch| ch == '.') && char_is(end + 1, &|ch| ch.is_ascii_digit()) {
+        end += 1;
+        while end < len && char_is(end, &is_number_char) {
+            end += 1;
+        }
+    }
+
+    if !char_is(start, &|ch| ch.is_ascii_digit()) {
+        return range;
+    }
+
+    // signed exponent, e.g. `1e-5`
+    let is_hex = slice.slice(start..end).chars().take(2).eq("0x".chars());
+    if !is_hex
+        && char_is(end.saturating_sub(1), &|ch| ch == 'e' || ch == 'E')
+        && char_is(end, &|ch| ch == '-' || ch == '+')
+        && char_is(end + 1, &|ch| ch.is_ascii_digit())
+    {
+        end += 1;
+        while end < len && char_is(end, &is_number_char) {
+            end += 1;
+        }
+    }
+
+    match textobject {
+        TextObject::Inside => {}
+        TextObject::Around => {
+            // only a sign that isn't a binary operator, `-1` but not `a-1`
+            if start > 0
+                && char_is(start - 1, &|ch| ch == '-' || ch == '+')
+                && (start == 1
+                    || !char_is(start - 2, &|ch| {
+                        is_number_char(ch) || matches!(ch, ')' | ']' | '}')
+                    }))
+            {
+                start -= 1;
+            }
+        }
+        TextObject::Movement => unreachable!(),
+    }
+
+    Range::new(start, end)
+}
+
+/// Select the argument under the cursor in the closest enclosing `()`, `[]` or `{}` pair,
+/// using commas as separators. This is used for languages without tree-sitter textobjects.
+/// `Around` also selects the comma and whitespace separating the argument from the next
+/// one, or from the previous one for the last argument.
+pub fn textobject_argument(slice: RopeSlice, range: Range, textobject: TextObject) -> Range {
+    let pos = range.cursor(slice);
+    let is_quote = |i: usize| {
+        let mut chars = slice.chars_at(i);
+        let mut backslashes = 0;
+        while chars.prev() == Some('\\') {
+            backslashes += 1;
+        }
+        slice.char(i) == '"' && backslashes % 2 == 0
+    };
+
+    // find the opening bracket of the argument list, skipping string literals, which are
+    // assumed not to span lines
+    let line_start = slice.line_to_char(slice.char_to_line(pos));
+    let mut in_string = (line_start..pos).filter(|&i| is_quote(i)).count() % 2 == 1;
+    let mut depth = 0usize;
+    let mut open = None;
+    let mut chars = slice.chars_at(pos);
+    let mut i = pos;
+    while let Some(ch) = chars.prev() {
+        i -= 1;
+        if is_quote(i) {
+            in_string = !in_string;
+            continue;
+        }
+        if in_string {
+            continue;
+        }
+        match ch {
+            ')' | ']' | '}' => depth += 1,
+            '(' | '[' | '{' if depth == 0 => {
+                open = Some(i);
+                break;
+            }
+            '(' | '[' | '{' => depth -= 1,
+            _ => {}
+        }
+    }
+    let open = match open {
+        Some(open) => open,
+        None => return range,
+    };
+
+    // find the separators and the closing bracket
+    let mut separators = vec![open];
+    let mut close = None;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, ch) in slice.chars_at(open + 1).enumerate() {
+        let i = open + 1 + i;
+        if in_string {
+            match ch {
+                '\\' => escaped = !escaped,
+                '"' if !escaped => in_string = false,
+                _ => escaped = false,
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth == 0 => {
+                close = Some(i);
+                break;
+            }
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => separators.push(i),
+            _ => {}
+        }
+    }
+    let close = match close {
+        Some(close) if close >= pos => close,
+        _ => return range,
+    };
+    separators.push(close);
+
+    // the cursor on a comma belongs to the argument before it
+    let idx = match separators
+        .windows(2)
+        .position(|w| w[0] < pos && pos <= w[1])
+    {
+        Some(idx) => idx,
+        None => return range,
+    };
+    let (before, after) = (separators[idx], separators[idx + 1]);
+
+    let mut start = before + 1;
+    let mut end = after;
+    while start < end && char_is_whitespace(slice.char(start)) {
+        start += 1;
+    }
+    while end > start && char_is_whitespace(slice.char(end - 1)) {
+        end -= 1;
+    }
+    if start == end {
+        return range;
+    }
+
+    match textobject {
+        TextObject::Inside => {}
+        TextObject::Around if after != close => {
+            end = after + 1;
+            while end < close && char_is_whitespace(slice.char(end)) {
+                end += 1;
+            }
+        }
+        TextObject::Around if before != open => start = before,
+        TextObject::Around => {}
+        TextObject::Movement => unreachable!(),
+    }
+
+    Range::new(start, end)
+}
+
 /// Transform the given range to select text objects based on tree-sitter.
 /// `object_name` is a query capture base name like "function", "class", etc.
 /// `slice_tree` is the tree-sitter node corresponding to given text slice.
@@ -288,6 +580,79 @@ pub fn textobject_treesitter(
     get_range().unwrap_or(range)
 }
 
+/// A class or function enclosing a position, as found by [enclosing_scopes].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scope {
+    /// The char index where the scope starts.
+    pub start: usize,
+    /// The scope's name node, or its first line if the node has no name.
+    pub name: String,
+}
+
+/// Find the classes and functions that enclose `pos`, from the outermost to the innermost,
+/// using the "class.around" and "function.around" textobject captures.
+pub fn enclosing_scopes(
+    slice: RopeSlice,
+    pos: usize,
+    slice_tree: Node,
+    lang_config: &LanguageConfiguration,
+) -> Vec<Scope> {
+    let query = match lang_config.textobject_query() {
+        Some(query) => query,
+        None => return Vec::new(),
+    };
+    let byte_pos = slice.char_to_byte(pos);
+
+    let mut scopes = Vec::new();
+    let mut cursor = QueryCursor::new();
+    // only match the nodes around `pos` rather than all of the document
+    cursor.set_byte_range(byte_pos..byte_pos + 1);
+    for capture_name in ["class.around", "function.around"] {
+        let nodes = match query.capture_nodes(capture_name, slice_tree, slice, &mut cursor) {
+            Some(nodes) => nodes,
+            None => continue,
+        };
+        for node in nodes {
+            let range = node.byte_range();
+            if !range.contains(&byte_pos) || range.end > slice.len_bytes() {
+                continue;
+            }
+            let name = match &node {
+                CapturedNode::Single(node) => node.child_by_field_name("name"),
+                CapturedNode::Grouped(_) => None,
+            };
+            let name = match name {
+                Some(name) => slice
+                    .byte_slice(name.start_byte()..name.end_byte())
+                    .to_string(),
+                None => {
+                    let line = slice.byte_to_line(range.start);
+                    let line_end = slice.line_to_byte(line + 1).min(range.end);
+                    slice
+                        .byte_slice(range.start..line_end)
+                        .to_string()
+                        .trim()
+                        .trim_end_matches(['{', ':'])
+                        .trim_end()
+                        .to_string()
+                }
+            };
+            scopes.push((range, name));
+        }
+    }
+
+    // Outer scopes start earlier, or at the same byte and end later.
+    scopes.sort_by(|(a, _), (b, _)| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+    scopes.dedup_by(|(a, _), (b, _)| a == b);
+    scopes
+        .into_iter()
+        .map(|(range, name)| Scope {
+            start: slice.byte_to_char(range.start),
+            name,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::TextObject::*;
@@ -585,4 +950,127 @@ fn test_textobject_surround() {
             }
         }
     }
+
+    fn assert_textobject<F>(tests: &[(&str, &str)], textobject_fn: F)
+    where
+        F: Fn(RopeSlice, Range) -> Range,
+    {
+        for (before, expected) in tests {
+            let (s, selection) = crate::test::print(before);
+            let text = Rope::from(s.as_str());
+            let selection = selection.transform(|r| textobject_fn(text.slice(..), r));
+            let actual = crate::test::plain(s.as_ref(), &selection);
+            assert_eq!(actual, *expected, "\nbefore: `{:?}`", before);
+        }
+    }
+
+    #[test]
+    fn test_textobject_indentation_level() {
+        let text = "fn a() {\n    let x;\n\n    if x {\n        b#[(|]#);\n    }\n}\n";
+        assert_textobject(
+            &[
+                (
+                    text,
+                    "fn a() {\n    let x;\n\n    if x {\n#[        b();\n|]#    }\n}\n",
+                ),
+                (
+                    "fn a() {\n    le#[t|]# x;\n\n    if x {\n        b();\n    }\n}\n",
+                    "fn a() {\n#[    let x;\n\n    if x {\n        b();\n    }\n|]#}\n",
+                ),
+            ],
+            |text, range| textobject_indentation_level(text, range, Inside, 1, 4, 4),
+        );
+        assert_textobject(
+            &[(
+                text,
+                "fn a() {\n    let x;\n\n#[    if x {\n        b();\n    }\n|]#}\n",
+            )],
+            |text, range| textobject_indentation_level(text, range, Around, 1, 4, 4),
+        );
+        assert_textobject(
+            &[(
+                text,
+                "#[fn a() {\n    let x;\n\n    if x {\n        b();\n    }\n}\n|]#",
+            )],
+            |text, range| textobject_indentation_level(text, range, Around, 2, 4, 4),
+        );
+        assert_textobject(
+            &[("def a():\n    #[b|]#\nc\n", "#[def a():\n    b\n|]#c\n")],
+            |text, range| textobject_indentation_level(text, range, Around, 1, 4, 4),
+        );
+    }
+
+    #[test]
+    fn test_textobject_tag() {
+        let text = "<div class=\"a\">\n  <p>he#[l|]#lo<br></p>\n  <img src=\"b\"/>\n</div>";
+        assert_textobject(
+            &[(
+                text,
+                "<div class=\"a\">\n  <p>#[hello<br>|]#</p>\n  <img src=\"b\"/>\n</div>",
+            )],
+            |text, range| textobject_tag(text, range, Inside, 1),
+        );
+        assert_textobject(
+            &[(
+                text,
+                "<div class=\"a\">\n  #[<p>hello<br></p>|]#\n  <img src=\"b\"/>\n</div>",
+            )],
+            |text, range| textobject_tag(text, range, Around, 1),
+        );
+        assert_textobject(
+            &[(
+                text,
+                "<div class=\"a\">#[\n  <p>hello<br></p>\n  <img src=\"b\"/>\n|]#</div>",
+            )],
+            |text, range| textobject_tag(text, range, Inside, 2),
+        );
+        assert_textobject(&[("#[n|]#o tags", "#[n|]#o tags")], |text, range| {
+            textobject_tag(text, range, Around, 1)
+        });
+    }
+
+    #[test]
+    fn test_textobject_number() {
+        assert_textobject(
+            &[
+                ("x = 4#[2|]#;", "x = #[42|]#;"),
+                ("x = 0x#[f|]#f;", "x = #[0xff|]#;"),
+                ("x = 1_0#[0|]#0.5e-3;", "x = #[1_000.5e-3|]#;"),
+                ("x = 1.#[5|]#;", "x = #[1.5|]#;"),
+                ("x = -#[1|]#;", "x = -#[1|]#;"),
+                ("x#[1|]# = 1;", "x#[1|]# = 1;"),
+                ("x.#[0|]#", "x.#[0|]#"),
+            ],
+            |text, range| textobject_number(text, range, Inside),
+        );
+        assert_textobject(
+            &[
+                ("x = -#[1|]#;", "x = #[-1|]#;"),
+                ("x = a-#[1|]#;", "x = a-#[1|]#;"),
+            ],
+            |text, range| textobject_number(text, range, Around),
+        );
+    }
+
+    #[test]
+    fn test_textobject_argument() {
+        assert_textobject(
+            &[
+                ("f(a, #[b|]#(c, d), e)", "f(a, #[b(c, d)|]#, e)"),
+                ("f(a, b(c, #[d|]#), e)", "f(a, b(c, #[d|]#), e)"),
+                ("f(#[a|]#)", "f(#[a|]#)"),
+                ("f(\"a, #[b|]#\", c)", "f(#[\"a, b\"|]#, c)"),
+                ("no #[a|]#rguments", "no #[a|]#rguments"),
+            ],
+            |text, range| textobject_argument(text, range, Inside),
+        );
+        assert_textobject(
+            &[
+                ("f(#[a|]#, b, c)", "f(#[a, |]#b, c)"),
+                ("f(a, b, #[c|]#)", "f(a, b#[, c|]#)"),
+                ("f( #[a|]# )", "f( #[a|]# )"),
+            ],
+            |text, range| textobject_argument(text, range, Around),
+        );
+    }
 }
@@ -0,0 +1,176 @@
+use helix_core::{
+    config::default_syntax_loader,
+    syntax::Loader,
+    test,
+    textobject::{enclosing_scopes, textobject_argument, textobject_treesitter, TextObject},
+    Rope, Syntax,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use std::{path::PathBuf, sync::Arc};
+
+/// Capture names recognized in `textobjects.scm`. Captures starting with `_` are only used by
+/// predicates.
+const TEXTOBJECTS: &[&str] = &["function", "class", "test", "parameter", "comment", "entry"];
+
+#[derive(Deserialize)]
+struct Fixture {
+    #[serde(rename = "case")]
+    cases: Vec<Case>,
+}
+
+/// `object` (like `function.around`) is selected from the cursors in `input`, which should
+/// result in the selections in `output`. Both use the selection syntax of `helix_core::test`.
+#[derive(Deserialize)]
+struct Case {
+    object: String,
+    input: String,
+    output: String,
+}
+
+fn runtime_dir() -> PathBuf {
+    let mut runtime = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    runtime.push("../runtime");
+    runtime
+}
+
+#[test]
+fn test_textobject_capture_names() {
+    static CAPTURE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"@([\w.-]+)").unwrap());
+
+    let mut queries_dir = runtime_dir();
+    queries_dir.push("queries");
+
+    for entry in std::fs::read_dir(queries_dir).unwrap() {
+        let query_file = entry.unwrap().path().join("textobjects.scm");
+        let query = match std::fs::read_to_string(&query_file) {
+            Ok(query) => query,
+            Err(_) => continue,
+        };
+
+        for line in query.lines() {
+            // strip comments
+            let line = line.split(';').next().unwrap();
+            for captures in CAPTURE_REGEX.captures_iter(line) {
+                let capture = &captures[1];
+                if capture.starts_with('_') {
+                    continue;
+                }
+                let valid = capture.split_once('.').map_or(false, |(object, kind)| {
+                    TEXTOBJECTS.contains(&object)
+                        && matches!(kind, "inside" | "around" | "movement")
+                });
+                assert!(
+                    valid,
+                    "Unknown capture @{} in {}",
+                    capture,
+                    query_file.display()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_treesitter_textobjects() {
+    let mut test_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    test_dir.push("tests/data/textobject");
+
+    // set runtime path so we can find the queries
+    std::env::set_var("HELIX_RUNTIME", runtime_dir().to_str().unwrap());
+    let loader = Arc::new(Loader::new(default_syntax_loader()));
+
+    for entry in std::fs::read_dir(test_dir).unwrap() {
+        let path = entry.unwrap().path();
+        let language = path.file_stem().unwrap().to_str().unwrap();
+        let fixture: Fixture = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+
+        let language_config = loader
+            .language_config_for_language_id(language)
+            .unwrap_or_else(|| panic!("Unknown language {}", language));
+        let highlight_config = language_config.highlight_config(&[]).unwrap();
+        assert!(
+            language_config.textobject_query().is_some(),
+            "Failed to load textobjects.scm for {}",
+            language
+        );
+
+        for case in fixture.cases {
+            let (object, textobject) = match case.object.split_once('.') {
+                Some((object, "inside")) => (object, TextObject::Inside),
+                Some((object, "around")) => (object, TextObject::Around),
+                _ => panic!("Invalid textobject {} for {}", case.object, language),
+            };
+
+            let (text, selection) = test::print(&case.input);
+            let doc = Rope::from(text.as_str());
+            let syntax = Syntax::new(&doc, highlight_config.clone(), loader.clone());
+            let selection = selection.transform(|range| {
+                textobject_treesitter(
+                    doc.slice(..),
+                    range,
+                    textobject,
+                    object,
+                    syntax.tree().root_node(),
+                    &language_config,
+                    1,
+                )
+            });
+
+            assert_eq!(
+                test::plain(text.as_ref(), &selection),
+                case.output,
+                "Wrong {} for {}:\n{}",
+                case.object,
+                language,
+                case.input
+            );
+        }
+    }
+}
+
+#[test]
+fn test_enclosing_scopes() {
+    std::env::set_var("HELIX_RUNTIME", runtime_dir().to_str().unwrap());
+    let loader = Arc::new(Loader::new(default_syntax_loader()));
+    let language_config = loader.language_config_for_language_id("rust").unwrap();
+    let highlight_config = language_config.highlight_config(&[]).unwrap();
+
+    let (text, selection) = test::print(
+        "mod outer {\n    impl Foo {\n        fn bar() {\n            #[|b]#az();\n        }\n    }\n}\n",
+    );
+    let doc = Rope::from(text.as_str());
+    let syntax = Syntax::new(&doc, highlight_config, loader.clone());
+    let scopes = enclosing_scopes(
+        doc.slice(..),
+        selection.primary().cursor(doc.slice(..)),
+        syntax.tree().root_node(),
+        &language_config,
+    );
+
+    let scopes: Vec<_> = scopes
+        .iter()
+        .map(|scope| (doc.char_to_line(scope.start), scope.name.as_str()))
+        .collect();
+    // `impl` blocks have no name, so their first line is used
+    assert_eq!(scopes, [(1, "impl Foo"), (2, "bar")]);
+}
+
+#[test]
+fn test_textobject_argument_strings() {
+    // brackets and commas in string literals don't delimit arguments
+    let cases = [
+        ("f(\"a(\", #[b|]#)", "f(\"a(\", #[b|]#)"),
+        ("f(\"a)\", #[b|]#)", "f(\"a)\", #[b|]#)"),
+        ("f(\"a\\\"(\", #[b|]#)", "f(\"a\\\"(\", #[b|]#)"),
+        ("f(\"(\", \"a#[,|]# b\")", "f(\"(\", #[\"a, b\"|]#)"),
+    ];
+    for (input, output) in cases {
+        let (text, selection) = test::print(input);
+        let doc = Rope::from(text.as_str());
+        let selection = selection
+            .transform(|range| textobject_argument(doc.slice(..), range, TextObject::Inside));
+        assert_eq!(test::plain(text.as_str(), &selection), output, "{}", input);
+    }
+}
@@ -42,6 +42,12 @@ pub struct Client {
     /// Currently active frame for the current thread.
     pub active_frame: Option<usize>,
     pub quirks: DebuggerQuirks,
+    /// The name of the session, such as the name of the debug configuration it was started with.
+    pub name: String,
+    /// The session which started this one with a `startDebugging` request.
+    pub parent_id: Option<usize>,
+    /// The address of the debug adapter, if connected over TCP.
+    socket: Option<SocketAddr>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -58,7 +64,7 @@ pub async fn process(
         args: Vec<&str>,
         port_arg: Option<&str>,
         id: usize,
-    ) -> Result<(Self, UnboundedReceiver<Payload>)> {
+    ) -> Result<(Self, UnboundedReceiver<(usize, Payload)>)> {
         if command.is_empty() {
             return Result::Err(Error::Other(anyhow!("Command not provided")));
         }
@@ -75,7 +81,7 @@ pub fn streams(
         err: Option<Box<dyn AsyncBufRead + Unpin + Send>>,
         id: usize,
         process: Option<Child>,
-    ) -> Result<(Self, UnboundedReceiver<Payload>)> {
+    ) -> Result<(Self, UnboundedReceiver<(usize, Payload)>)> {
         let (server_rx, server_tx) = Transport::start(rx, tx, err, id);
         let (client_tx, client_rx) = unbounded_channel();
 
@@ -92,9 +98,12 @@ pub fn streams(
             thread_id: None,
             active_frame: None,
             quirks: DebuggerQuirks::default(),
+            name: String::new(),
+            parent_id: None,
+            socket: None,
         };
 
-        tokio::spawn(Self::recv(server_rx, client_tx));
+        tokio::spawn(Self::recv(id, server_rx, client_tx));
 
         Ok((client, client_rx))
     }
@@ -102,17 +111,20 @@ pub fn streams(
     pub async fn tcp(
         addr: std::net::SocketAddr,
         id: usize,
-    ) -> Result<(Self, UnboundedReceiver<Payload>)> {
+    ) -> Result<(Self, UnboundedReceiver<(usize, Payload)>)> {
         let stream = TcpStream::connect(addr).await?;
         let (rx, tx) = stream.into_split();
-        Self::streams(Box::new(BufReader::new(rx)), Box::new(tx), None, id, None)
+        let (mut client, client_rx) =
+            Self::streams(Box::new(BufReader::new(rx)), Box::new(tx), None, id, None)?;
+        client.socket = Some(addr);
+        Ok((client, client_rx))
     }
 
     pub fn stdio(
         cmd: &str,
         args: Vec<&str>,
         id: usize,
-    ) -> Result<(Self, UnboundedReceiver<Payload>)> {
+    ) -> Result<(Self, UnboundedReceiver<(usize, Payload)>)> {
         // Resolve path to the binary
         let cmd = which::which(cmd).map_err(|err| anyhow::anyhow!(err))?;
 
@@ -167,7 +179,7 @@ pub async fn tcp_process(
         args: Vec<&str>,
         port_format: &str,
         id: usize,
-    ) -> Result<(Self, UnboundedReceiver<Payload>)> {
+    ) -> Result<(Self, UnboundedReceiver<(usize, Payload)>)> {
         let port = Self::get_port().await.unwrap();
 
         let process = Command::new(cmd)
@@ -183,32 +195,37 @@ pub async fn tcp_process(
         // Wait for adapter to become ready for connection
         time::sleep(time::Duration::from_millis(500)).await;
 
-        let stream = TcpStream::connect(SocketAddr::new(
-            IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
-            port,
-        ))
-        .await?;
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port);
+        let stream = TcpStream::connect(addr).await?;
 
         let (rx, tx) = stream.into_split();
-        Self::streams(
+        let (mut client, client_rx) = Self::streams(
             Box::new(BufReader::new(rx)),
             Box::new(tx),
             None,
             id,
             Some(process),
-        )
+        )?;
+        client.socket = Some(addr);
+        Ok((client, client_rx))
     }
 
-    async fn recv(mut server_rx: UnboundedReceiver<Payload>, client_tx: UnboundedSender<Payload>) {
+    async fn recv(
+        id: usize,
+        mut server_rx: UnboundedReceiver<Payload>,
+        client_tx: UnboundedSender<(usize, Payload)>,
+    ) {
         while let Some(msg) = server_rx.recv().await {
             match msg {
                 Payload::Event(ev) => {
-                    client_tx.send(Payload::Event(ev)).expect("Failed to send");
+                    client_tx
+                        .send((id, Payload::Event(ev)))
+                        .expect("Failed to send");
                 }
                 Payload::Response(_) => unreachable!(),
                 Payload::Request(req) => {
                     client_tx
-                        .send(Payload::Request(req))
+                        .send((id, Payload::Request(req)))
                         .expect("Failed to send");
                 }
             }
@@ -219,6 +236,10 @@ pub fn id(&self) -> usize {
         self.id
     }
 
+    pub fn socket(&self) -> Option<SocketAddr> {
+        self.socket
+    }
+
     pub fn connection_type(&self) -> Option<ConnectionType> {
         self.connection_type
     }
@@ -339,9 +360,10 @@ pub async fn initialize(&mut self, adapter_id: String) -> Result<()> {
             supports_variable_type: Some(true),
             supports_variable_paging: Some(false),
             supports_run_in_terminal_request: Some(true),
-            supports_memory_references: Some(false),
+            supports_memory_references: Some(true),
             supports_progress_reporting: Some(false),
             supports_invalidated_event: Some(false),
+            supports_start_debugging_request: Some(true),
         };
 
         let response = self.request::<requests::Initialize>(args).await?;
@@ -453,29 +475,41 @@ pub async fn variables(&self, variables_reference: usize) -> Result<Vec<Variable
         Ok(response.variables)
     }
 
-    pub fn step_in(&self, thread_id: ThreadId) -> impl Future<Output = Result<Value>> {
+    pub fn step_in(
+        &self,
+        thread_id: ThreadId,
+        granularity: Option<SteppingGranularity>,
+    ) -> impl Future<Output = Result<Value>> {
         let args = requests::StepInArguments {
             thread_id,
             target_id: None,
-            granularity: None,
+            granularity,
         };
 
         self.call::<requests::StepIn>(args)
     }
 
-    pub fn step_out(&self, thread_id: ThreadId) -> impl Future<Output = Result<Value>> {
+    pub fn step_out(
+        &self,
+        thread_id: ThreadId,
+        granularity: Option<SteppingGranularity>,
+    ) -> impl Future<Output = Result<Value>> {
         let args = requests::StepOutArguments {
             thread_id,
-            granularity: None,
+            granularity,
         };
 
         self.call::<requests::StepOut>(args)
     }
 
-    pub fn next(&self, thread_id: ThreadId) -> impl Future<Output = Result<Value>> {
+    pub fn next(
+        &self,
+        thread_id: ThreadId,
+        granularity: Option<SteppingGranularity>,
+    ) -> impl Future<Output = Result<Value>> {
         let args = requests::NextArguments {
             thread_id,
-            granularity: None,
+            granularity,
         };
 
         self.call::<requests::Next>(args)
@@ -502,15 +536,185 @@ pub async fn eval(
         self.request::<requests::Evaluate>(args).await
     }
 
+    /// Evaluates an expression typed in the debug console.
+    pub fn repl(
+        &self,
+        expression: String,
+        frame_id: Option<usize>,
+    ) -> impl Future<Output = Result<Value>> {
+        let args = requests::EvaluateArguments {
+            expression,
+            frame_id,
+            context: Some("repl".to_owned()),
+            format: None,
+        };
+
+        self.call::<requests::Evaluate>(args)
+    }
+
+    pub fn completions(
+        &self,
+        text: String,
+        column: usize,
+        frame_id: Option<usize>,
+    ) -> impl Future<Output = Result<Value>> {
+        let args = requests::CompletionsArguments {
+            frame_id,
+            text,
+            column,
+            line: None,
+        };
+
+        self.call::<requests::Completions>(args)
+    }
+
     pub fn set_exception_breakpoints(
         &self,
         filters: Vec<String>,
     ) -> impl Future<Output = Result<Value>> {
-        let args = requests::SetExceptionBreakpointsArguments { filters };
+        let args = requests::SetExceptionBreakpointsArguments {
+            filters,
+            filter_options: None,
+        };
 
         self.call::<requests::SetExceptionBreakpoints>(args)
     }
 
+    /// Sets the exception filters along with their conditions. Requires the
+    /// `supports_exception_filter_options` capability.
+    pub async fn set_exception_filter_options(
+        &self,
+        filter_options: Vec<ExceptionFilterOptions>,
+    ) -> Result<requests::SetExceptionBreakpointsResponse> {
+        let args = requests::SetExceptionBreakpointsArguments {
+            filters: Vec::new(),
+            filter_options: Some(filter_options),
+        };
+
+        self.request::<requests::SetExceptionBreakpoints>(args)
+            .await
+    }
+
+    pub async fn set_function_breakpoints(
+        &self,
+        breakpoints: Vec<FunctionBreakpoint>,
+    ) -> Result<Vec<Breakpoint>> {
+        let args = requests::SetFunctionBreakpointsArguments { breakpoints };
+
+        let response = self
+            .request::<requests::SetFunctionBreakpoints>(args)
+            .await?;
+        Ok(response.breakpoints)
+    }
+
+    /// Asks whether a data breakpoint can be set on the variable `name` of the container at
+    /// `variables_reference`, or on the expression `name` if there is no container.
+    pub async fn data_breakpoint_info(
+        &self,
+        variables_reference: Option<usize>,
+        name: String,
+        frame_id: Option<usize>,
+    ) -> Result<requests::DataBreakpointInfoResponse> {
+        let args = requests::DataBreakpointInfoArguments {
+            variables_reference,
+            name,
+            frame_id,
+        };
+
+        self.request::<requests::DataBreakpointInfo>(args).await
+    }
+
+    pub async fn set_data_breakpoints(
+        &self,
+        breakpoints: Vec<DataBreakpoint>,
+    ) -> Result<Vec<Breakpoint>> {
+        let args = requests::SetDataBreakpointsArguments { breakpoints };
+
+        let response = self.request::<requests::SetDataBreakpoints>(args).await?;
+        Ok(response.breakpoints)
+    }
+
+    pub async fn goto_targets(&self, file: PathBuf, line: usize) -> Result<Vec<GotoTarget>> {
+        let args = requests::GotoTargetsArguments {
+            source: Source {
+                path: Some(file),
+                ..Default::default()
+            },
+            line,
+            column: None,
+        };
+
+        let response = self.request::<requests::GotoTargets>(args).await?;
+        Ok(response.targets)
+    }
+
+    pub fn goto(
+        &self,
+        thread_id: ThreadId,
+        target_id: usize,
+    ) -> impl Future<Output = Result<Value>> {
+        let args = requests::GotoArguments {
+            thread_id,
+            target_id,
+        };
+
+        self.call::<requests::Goto>(args)
+    }
+
+    pub fn step_back(
+        &self,
+        thread_id: ThreadId,
+        granularity: Option<SteppingGranularity>,
+    ) -> impl Future<Output = Result<Value>> {
+        let args = requests::StepBackArguments {
+            thread_id,
+            granularity,
+        };
+
+        self.call::<requests::StepBack>(args)
+    }
+
+    pub fn reverse_continue(&self, thread_id: ThreadId) -> impl Future<Output = Result<Value>> {
+        let args = requests::ReverseContinueArguments { thread_id };
+
+        self.call::<requests::ReverseContinue>(args)
+    }
+
+    /// Disassembles `count` instructions starting `instruction_offset` instructions away from
+    /// `memory_reference`.
+    pub async fn disassemble(
+        &self,
+        memory_reference: String,
+        instruction_offset: isize,
+        instruction_count: usize,
+    ) -> Result<Vec<DisassembledInstruction>> {
+        let args = requests::DisassembleArguments {
+            memory_reference,
+            offset: None,
+            instruction_offset: Some(instruction_offset),
+            instruction_count,
+            resolve_symbols: Some(true),
+        };
+
+        let response = self.request::<requests::Disassemble>(args).await?;
+        Ok(response.instructions)
+    }
+
+    pub async fn read_memory(
+        &self,
+        memory_reference: String,
+        offset: isize,
+        count: usize,
+    ) -> Result<requests::ReadMemoryResponse> {
+        let args = requests::ReadMemoryArguments {
+            memory_reference,
+            offset: Some(offset),
+            count,
+        };
+
+        self.request::<requests::ReadMemory>(args).await
+    }
+
     pub fn current_stack_frame(&self) -> Option<&StackFrame> {
         self.stack_frames
             .get(&self.thread_id?)?
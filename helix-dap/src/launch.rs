@@ -0,0 +1,322 @@
+//! Debug configurations of a workspace, in the format of VS Code's `launch.json`.
+
+use std::{collections::HashMap, path::Path};
+
+use anyhow::Context;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The files the configurations are read from, relative to the workspace root.
+pub const LAUNCH_FILES: [&str; 2] = [".helix/launch.json", ".vscode/launch.json"];
+
+/// Attributes which are interpreted by VS Code itself rather than the debug adapter.
+const EDITOR_ATTRIBUTES: [&str; 6] = [
+    "preLaunchTask",
+    "postDebugTask",
+    "presentation",
+    "internalConsoleOptions",
+    "debugServer",
+    "serverReadyAction",
+];
+
+/// The debug adapters of `languages.toml` which take the arguments of VS Code's debug types.
+const ADAPTER_TYPES: [(&str, &str); 4] = [
+    ("lldb", "lldb-vscode"),
+    ("coreclr", "netcoredbg"),
+    ("node", "node-debug2"),
+    ("pwa-node", "node-debug2"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LaunchConfiguration {
+    pub name: String,
+    /// The debug adapter, either the name of an adapter in `languages.toml` or a VS Code debug
+    /// type, see [`LaunchConfiguration::adapter`].
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// Either `launch` or `attach`.
+    pub request: String,
+    /// The other attributes, which are the arguments of the request.
+    #[serde(flatten)]
+    pub args: serde_json::Map<String, Value>,
+}
+
+impl LaunchConfiguration {
+    /// The name of the debug adapter in `languages.toml` which starts the configuration.
+    pub fn adapter(&self) -> &str {
+        ADAPTER_TYPES
+            .iter()
+            .find(|(ty, _)| *ty == self.ty)
+            .map_or(&self.ty, |(_, adapter)| adapter)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LaunchFile {
+    #[serde(default)]
+    configurations: Vec<LaunchConfiguration>,
+}
+
+/// Reads the configurations from the launch files of the workspace at `root`. The files which
+/// don't exist are skipped.
+pub fn load(root: &Path) -> anyhow::Result<Vec<LaunchConfiguration>> {
+    let mut configurations = Vec::new();
+    for file in LAUNCH_FILES {
+        let path = root.join(file);
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()))
+            }
+        };
+        configurations
+            .extend(parse(&text).with_context(|| format!("failed to parse {}", path.display()))?);
+    }
+    Ok(configurations)
+}
+
+/// Parses the configurations of a launch file, which may contain comments and trailing commas.
+pub fn parse(text: &str) -> anyhow::Result<Vec<LaunchConfiguration>> {
+    let file: LaunchFile = serde_json::from_str(&strip_jsonc(text))?;
+    Ok(file
+        .configurations
+        .into_iter()
+        .map(|mut configuration| {
+            for attribute in EDITOR_ATTRIBUTES {
+                configuration.args.remove(attribute);
+            }
+            configuration
+        })
+        .collect())
+}
+
+/// Removes the comments and trailing commas JSON doesn't allow.
+fn strip_jsonc(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(ch) = chars.next() {
+        if in_string {
+            stripped.push(ch);
+            match ch {
+                '\\' => stripped.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (ch, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                stripped.push(ch);
+            }
+            ('/', Some('/')) => {
+                for ch in chars.by_ref() {
+                    if ch == '\n' {
+                        stripped.push(ch);
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = None;
+                for ch in chars.by_ref() {
+                    if prev == Some('*') && ch == '/' {
+                        break;
+                    }
+                    prev = Some(ch);
+                }
+            }
+            _ => stripped.push(ch),
+        }
+    }
+
+    // with the comments gone, a comma is trailing if the next non-whitespace is a bracket
+    let mut result = String::with_capacity(stripped.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, ch) in stripped.char_indices() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if ch == '"' {
+            in_string = true;
+        } else if ch == ',' {
+            let next = stripped[i + 1..].chars().find(|ch| !ch.is_whitespace());
+            if matches!(next, Some('}' | ']')) {
+                continue;
+            }
+        }
+        result.push(ch);
+    }
+    result
+}
+
+/// The values of the `${...}` variables of launch files.
+pub fn variables(
+    root: &Path,
+    file: Option<&Path>,
+    line: usize,
+    selected_text: &str,
+) -> HashMap<String, String> {
+    let path_string = |path: &Path| path.to_string_lossy().into_owned();
+    let mut variables = HashMap::new();
+    variables.insert("workspaceFolder".to_owned(), path_string(root));
+    if let Some(name) = root.file_name() {
+        variables.insert(
+            "workspaceFolderBasename".to_owned(),
+            name.to_string_lossy().into_owned(),
+        );
+    }
+    if let Some(file) = file {
+        variables.insert("file".to_owned(), path_string(file));
+        if let Ok(relative) = file.strip_prefix(root) {
+            variables.insert("relativeFile".to_owned(), path_string(relative));
+        }
+        if let Some(dir) = file.parent() {
+            variables.insert("fileDirname".to_owned(), path_string(dir));
+        }
+        if let Some(name) = file.file_name() {
+            variables.insert(
+                "fileBasename".to_owned(),
+                name.to_string_lossy().into_owned(),
+            );
+        }
+        if let Some(stem) = file.file_stem() {
+            variables.insert(
+                "fileBasenameNoExtension".to_owned(),
+                stem.to_string_lossy().into_owned(),
+            );
+        }
+        let extension = file
+            .extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default();
+        variables.insert("fileExtname".to_owned(), extension);
+    }
+    variables.insert("lineNumber".to_owned(), (line + 1).to_string());
+    variables.insert("selectedText".to_owned(), selected_text.to_owned());
+    if let Ok(cwd) = std::env::current_dir() {
+        variables.insert("cwd".to_owned(), path_string(&cwd));
+    }
+    let home = helix_core::path::expand_tilde(Path::new("~"));
+    if home != Path::new("~") {
+        variables.insert("userHome".to_owned(), path_string(&home));
+    }
+    variables.insert(
+        "pathSeparator".to_owned(),
+        std::path::MAIN_SEPARATOR.to_string(),
+    );
+    variables
+}
+
+/// Replaces the `${name}` and `${env:NAME}` variables in the strings of `value`. Unknown
+/// variables are left as they are.
+pub fn substitute(value: &mut Value, variables: &HashMap<String, String>) {
+    match value {
+        Value::String(string) => *string = substitute_str(string, variables),
+        Value::Array(values) => {
+            for value in values {
+                substitute(value, variables);
+            }
+        }
+        Value::Object(map) => {
+            for value in map.values_mut() {
+                substitute(value, variables);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn substitute_str(text: &str, variables: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = match after.find('}') {
+            Some(end) => end,
+            None => {
+                result.push_str(&rest[start..]);
+                return result;
+            }
+        };
+        let name = &after[..end];
+        let value = match name.strip_prefix("env:") {
+            Some(var) => Some(std::env::var(var).unwrap_or_default()),
+            None => variables.get(name).cloned(),
+        };
+        match value {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(&rest[start..start + end + 3]),
+        }
+        rest = &after[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse() {
+        let text = r#"{
+            // comments are allowed
+            "version": "0.2.0",
+            "configurations": [
+                {
+                    "type": "lldb",
+                    "request": "launch",
+                    "name": "Debug /* not a comment */",
+                    "program": "${workspaceFolder}/target/debug/app", // trailing
+                    "args": ["a,]", "b",],
+                    "preLaunchTask": "build",
+                },
+            ]
+        }"#;
+        let configurations = parse(text).unwrap();
+        assert_eq!(configurations.len(), 1);
+        let configuration = &configurations[0];
+        assert_eq!(configuration.name, "Debug /* not a comment */");
+        assert_eq!(configuration.ty, "lldb");
+        assert_eq!(configuration.adapter(), "lldb-vscode");
+        assert_eq!(configuration.request, "launch");
+        assert_eq!(
+            Value::Object(configuration.args.clone()),
+            json!({
+                "program": "${workspaceFolder}/target/debug/app",
+                "args": ["a,]", "b"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_substitute() {
+        let root = Path::new("/work/app");
+        let variables = variables(root, Some(&root.join("src/main.rs")), 9, "x");
+        let mut value = json!({
+            "program": "${workspaceFolder}/target/${fileBasenameNoExtension}",
+            "args": ["${relativeFile}:${lineNumber}", "${selectedText}", "${unknown}", "${"],
+            "stopOnEntry": true,
+        });
+        substitute(&mut value, &variables);
+        assert_eq!(
+            value,
+            json!({
+                "program": "/work/app/target/main",
+                "args": ["src/main.rs:10", "x", "${unknown}", "${"],
+                "stopOnEntry": true,
+            })
+        );
+    }
+}
@@ -1,9 +1,12 @@
 mod client;
+pub mod launch;
+mod registry;
 mod transport;
 mod types;
 
 pub use client::{Client, ConnectionType};
 pub use events::Event;
+pub use registry::Registry;
 pub use transport::{Payload, Response, Transport};
 pub use types::*;
 
@@ -0,0 +1,140 @@
+//! The debug sessions, each with the client of its own debug adapter.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+};
+
+use anyhow::anyhow;
+use futures_util::stream::SelectAll;
+use helix_core::syntax::DebugAdapterConfig;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use crate::{Client, Payload, Result};
+
+#[derive(Debug, Default)]
+pub struct Registry {
+    clients: BTreeMap<usize, Client>,
+    /// The debug adapter each session was started with, to start its child sessions.
+    configs: HashMap<usize, DebugAdapterConfig>,
+    counter: usize,
+    active: Option<usize>,
+    /// The events and reverse requests of all sessions, by session id.
+    pub incoming: SelectAll<UnboundedReceiverStream<(usize, Payload)>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts and initializes a debug adapter, either spawning it or connecting to `socket`,
+    /// and makes its session the active one.
+    pub async fn start_client(
+        &mut self,
+        socket: Option<SocketAddr>,
+        config: &DebugAdapterConfig,
+    ) -> Result<usize> {
+        self.counter += 1;
+        let id = self.counter;
+        let (mut client, incoming) = match socket {
+            Some(socket) => Client::tcp(socket, id).await?,
+            None => {
+                Client::process(
+                    &config.transport,
+                    &config.command,
+                    config.args.iter().map(|arg| arg.as_str()).collect(),
+                    config.port_arg.as_deref(),
+                    id,
+                )
+                .await?
+            }
+        };
+        client.initialize(config.name.clone()).await?;
+        client.quirks = config.quirks.clone();
+
+        self.incoming.push(UnboundedReceiverStream::new(incoming));
+        self.clients.insert(id, client);
+        self.configs.insert(id, config.clone());
+        self.active = Some(id);
+        Ok(id)
+    }
+
+    /// Starts a session requested by the debug adapter of `parent_id` with `startDebugging`.
+    /// Debug adapters listening on a socket get another connection, the others are started again.
+    pub async fn start_child(&mut self, parent_id: usize) -> Result<usize> {
+        let parent = self
+            .get(parent_id)
+            .ok_or_else(|| anyhow!("Debug session {} is not running", parent_id))?;
+        let socket = parent.socket();
+        let name = parent.name.clone();
+        let config = self.configs[&parent_id].clone();
+
+        let id = self.start_client(socket, &config).await?;
+        let client = self.clients.get_mut(&id).unwrap();
+        client.parent_id = Some(parent_id);
+        client.name = name;
+        Ok(id)
+    }
+
+    /// Removes a session that ended. If it was the active one, its parent or the latest session
+    /// started becomes active.
+    pub fn remove(&mut self, id: usize) -> Option<Client> {
+        let client = self.clients.remove(&id)?;
+        self.configs.remove(&id);
+        if self.active == Some(id) {
+            self.active = client
+                .parent_id
+                .filter(|parent_id| self.clients.contains_key(parent_id))
+                .or_else(|| self.clients.keys().next_back().copied());
+        }
+        Some(client)
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Client> {
+        self.clients.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut Client> {
+        self.clients.get_mut(&id)
+    }
+
+    pub fn active_id(&self) -> Option<usize> {
+        self.active
+    }
+
+    pub fn active(&self) -> Option<&Client> {
+        self.clients.get(&self.active?)
+    }
+
+    pub fn active_mut(&mut self) -> Option<&mut Client> {
+        self.clients.get_mut(&self.active?)
+    }
+
+    /// Makes the session with `id` the one the debugging commands act on. Returns `false` if
+    /// there is no such session.
+    pub fn set_active(&mut self, id: usize) -> bool {
+        let exists = self.clients.contains_key(&id);
+        if exists {
+            self.active = Some(id);
+        }
+        exists
+    }
+
+    /// The sessions, in the order they were started.
+    pub fn iter(&self) -> impl Iterator<Item = &Client> {
+        self.clients.values()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Client> {
+        self.clients.values_mut()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+}
@@ -1,10 +1,11 @@
 use crate::{
     find_lsp_workspace, jsonrpc,
-    transport::{Payload, Transport},
+    transport::{Payload, ServerReader, ServerWriter, Transport},
     Call, Error, OffsetEncoding, Result,
 };
 
-use helix_core::{find_workspace, path, ChangeSet, Rope};
+use futures_util::{future, FutureExt};
+use helix_core::{find_workspace, path, syntax::LanguageServerConfiguration, ChangeSet, Rope};
 use helix_loader::{self, VERSION_AND_GIT_HASH};
 use lsp::{
     notification::DidChangeWorkspaceFolders, DidChangeWorkspaceFoldersParams, OneOf,
@@ -15,21 +16,32 @@
 use serde::Deserialize;
 use serde_json::Value;
 use std::future::Future;
-use std::process::Stdio;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::process::{ExitStatus, Stdio};
 use std::sync::{
-    atomic::{AtomicU64, Ordering},
+    atomic::{AtomicBool, AtomicU64, Ordering},
     Arc,
 };
-use std::{collections::HashMap, path::PathBuf};
+use std::time::Duration;
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+};
 use tokio::{
     io::{BufReader, BufWriter},
+    net::TcpStream,
     process::{Child, Command},
     sync::{
         mpsc::{channel, UnboundedReceiver, UnboundedSender},
-        Notify, OnceCell,
+        Mutex as AsyncMutex, Notify, OnceCell,
     },
 };
 
+/// How often connecting to a server spawned to listen on a port is tried, and how long to wait
+/// in between, as the server takes a moment to start listening.
+const CONNECT_ATTEMPTS: usize = 50;
+const CONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
 fn workspace_for_uri(uri: lsp::Url) -> WorkspaceFolder {
     lsp::WorkspaceFolder {
         name: uri
@@ -41,10 +53,92 @@ fn workspace_for_uri(uri: lsp::Url) -> WorkspaceFolder {
     }
 }
 
+/// Spawns the server of `ls_config`, talking over its stdio, or over TCP when `port_args` tell it
+/// which port to listen on.
+fn spawn_server(ls_config: &LanguageServerConfiguration, port_args: Option<&str>) -> Result<Child> {
+    // Resolve path to the binary
+    let cmd = which::which(&ls_config.command).map_err(|err| anyhow::anyhow!(err))?;
+    let stdio = || match port_args {
+        Some(_) => Stdio::null(),
+        None => Stdio::piped(),
+    };
+
+    let process = Command::new(cmd)
+        .envs(&ls_config.environment)
+        .args(&ls_config.args)
+        .args(port_args.into_iter().flat_map(|args| args.split(' ')))
+        .stdin(stdio())
+        .stdout(stdio())
+        .stderr(Stdio::piped())
+        // make sure the process is reaped on drop
+        .kill_on_drop(true)
+        .spawn()?;
+    Ok(process)
+}
+
+/// Connects to a running server at `socket`, which is the path of a Unix socket if it contains
+/// a `/`, and a TCP address otherwise.
+async fn connect(socket: String) -> Result<(ServerReader, ServerWriter)> {
+    #[cfg(unix)]
+    if socket.contains('/') {
+        let (rx, tx) = tokio::net::UnixStream::connect(&socket).await?.into_split();
+        return Ok((Box::new(BufReader::new(rx)), Box::new(BufWriter::new(tx))));
+    }
+
+    let (rx, tx) = TcpStream::connect(&socket).await?.into_split();
+    Ok((Box::new(BufReader::new(rx)), Box::new(BufWriter::new(tx))))
+}
+
+/// Connects to a server spawned to listen on `port`, giving it some time to start listening.
+async fn connect_to_port(port: u16) -> Result<(ServerReader, ServerWriter)> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let mut attempts = 1;
+    let stream = loop {
+        match TcpStream::connect(addr).await {
+            Ok(stream) => break stream,
+            Err(_) if attempts < CONNECT_ATTEMPTS => {
+                attempts += 1;
+                tokio::time::sleep(CONNECT_RETRY_INTERVAL).await;
+            }
+            Err(err) => return Err(err.into()),
+        }
+    };
+    let (rx, tx) = stream.into_split();
+    Ok((Box::new(BufReader::new(rx)), Box::new(BufWriter::new(tx))))
+}
+
+fn free_port() -> Result<u16> {
+    let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Remembers the result id of the diagnostic report of `uri`, forgetting the previous one if the
+/// report has none.
+fn record_result_id(
+    result_ids: &mut HashMap<lsp::Url, String>,
+    uri: lsp::Url,
+    result_id: Option<&String>,
+) {
+    match result_id {
+        Some(result_id) => result_ids.insert(uri, result_id.clone()),
+        None => result_ids.remove(&uri),
+    };
+}
+
 #[derive(Debug)]
 pub struct Client {
     id: usize,
-    _process: Child,
+    /// The name of the server's executable.
+    name: String,
+    /// The server process, unless connecting to a running server.
+    process: AsyncMutex<Option<Child>>,
+    /// Whether the client connected to a running server through its `socket`. The server isn't
+    /// owned by the client, so it's disconnected from rather than shut down.
+    connected: bool,
+    /// The last lines the server wrote to stderr, to tell why it crashed.
+    stderr_tail: Arc<Mutex<VecDeque<String>>>,
+    /// Whether the server was told to exit, so that it exiting isn't a crash.
+    exiting: AtomicBool,
     server_tx: UnboundedSender<Payload>,
     request_counter: AtomicU64,
     pub(crate) capabilities: OnceCell<lsp::ServerCapabilities>,
@@ -52,6 +146,12 @@ pub struct Client {
     root_path: std::path::PathBuf,
     root_uri: Option<lsp::Url>,
     workspace_folders: Mutex<Vec<lsp::WorkspaceFolder>>,
+    /// The result ids of the last diagnostic reports pulled for each document, sent with the
+    /// next pull so that the server can answer that nothing changed.
+    diagnostic_result_ids: Arc<Mutex<HashMap<lsp::Url, String>>>,
+    /// Whether a pull of the workspace diagnostics is waiting for its answer, which servers may
+    /// hold back until the diagnostics change.
+    workspace_diagnostic_pending: Arc<AtomicBool>,
     initialize_notify: Arc<Notify>,
     /// workspace folders added while the server is still initializing
     req_timeout: u64,
@@ -166,41 +266,52 @@ fn add_workspace_folder(
         tokio::spawn(self.did_change_workspace(vec![workspace_for_uri(root_uri)], Vec::new()));
     }
 
+    /// Starts a language server, or connects to a running one, and returns the client without
+    /// waiting for the server to be connected.
     #[allow(clippy::type_complexity)]
-    #[allow(clippy::too_many_arguments)]
     pub fn start(
-        cmd: &str,
-        args: &[String],
+        ls_config: &LanguageServerConfiguration,
         config: Option<Value>,
-        server_environment: HashMap<String, String>,
         root_markers: &[String],
         manual_roots: &[PathBuf],
         id: usize,
-        req_timeout: u64,
         doc_path: Option<&std::path::PathBuf>,
     ) -> Result<(Self, UnboundedReceiver<(usize, Call)>, Arc<Notify>)> {
-        // Resolve path to the binary
-        let cmd = which::which(cmd).map_err(|err| anyhow::anyhow!(err))?;
-
-        let process = Command::new(cmd)
-            .envs(server_environment)
-            .args(args)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            // make sure the process is reaped on drop
-            .kill_on_drop(true)
-            .spawn();
-
-        let mut process = process?;
-
-        // TODO: do we need bufreader/writer here? or do we use async wrappers on unblock?
-        let writer = BufWriter::new(process.stdin.take().expect("Failed to open stdin"));
-        let reader = BufReader::new(process.stdout.take().expect("Failed to open stdout"));
-        let stderr = BufReader::new(process.stderr.take().expect("Failed to open stderr"));
+        let (mut process, connection) = match (&ls_config.socket, &ls_config.port_arg) {
+            (Some(_), Some(_)) => {
+                return Err(anyhow::anyhow!(
+                    "a language server can't have both a `socket` and a `port-arg`"
+                )
+                .into())
+            }
+            (Some(socket), None) => (None, connect(socket.clone()).boxed()),
+            (None, Some(port_arg)) => {
+                let port = free_port()?;
+                let port_args = port_arg.replace("{}", &port.to_string());
+                let process = spawn_server(ls_config, Some(&port_args))?;
+                (Some(process), connect_to_port(port).boxed())
+            }
+            (None, None) => {
+                let mut process = spawn_server(ls_config, None)?;
+                // TODO: do we need bufreader/writer here? or do we use async wrappers on unblock?
+                let writer = BufWriter::new(process.stdin.take().expect("Failed to open stdin"));
+                let reader = BufReader::new(process.stdout.take().expect("Failed to open stdout"));
+                let streams: (ServerReader, ServerWriter) = (Box::new(reader), Box::new(writer));
+                (Some(process), future::ready(Ok(streams)).boxed())
+            }
+        };
+        let stderr = process
+            .as_mut()
+            .and_then(|process| process.stderr.take())
+            .map(|stderr| Box::new(BufReader::new(stderr)) as ServerReader);
+        let name = match &ls_config.socket {
+            Some(socket) if ls_config.command.is_empty() => socket.clone(),
+            _ => ls_config.command.clone(),
+        };
 
+        let stderr_tail = Arc::new(Mutex::new(VecDeque::new()));
         let (server_rx, server_tx, initialize_notify) =
-            Transport::start(reader, writer, stderr, id);
+            Transport::start(connection, stderr, stderr_tail.clone(), id);
         let (workspace, workspace_is_cwd) = find_workspace();
         let workspace = path::get_normalized_path(&workspace);
         let root = find_lsp_workspace(
@@ -225,15 +336,21 @@ pub fn start(
 
         let client = Self {
             id,
-            _process: process,
+            name,
+            connected: process.is_none(),
+            process: AsyncMutex::new(process),
+            stderr_tail,
+            exiting: AtomicBool::new(false),
             server_tx,
             request_counter: AtomicU64::new(0),
             capabilities: OnceCell::new(),
             config,
-            req_timeout,
+            req_timeout: ls_config.timeout,
             root_path,
             root_uri,
             workspace_folders: Mutex::new(workspace_folders),
+            diagnostic_result_ids: Arc::new(Mutex::new(HashMap::new())),
+            workspace_diagnostic_pending: Arc::new(AtomicBool::new(false)),
             initialize_notify: initialize_notify.clone(),
         };
 
@@ -244,6 +361,26 @@ pub fn id(&self) -> usize {
         self.id
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the server was told to exit, as opposed to exiting on its own.
+    pub fn is_exiting(&self) -> bool {
+        self.exiting.load(Ordering::Relaxed)
+    }
+
+    /// Waits for the server process to exit, returning its exit status. Returns `None` right
+    /// away for servers the client connected to.
+    pub async fn wait_for_exit(&self) -> Option<ExitStatus> {
+        self.process.lock().await.as_mut()?.wait().await.ok()
+    }
+
+    /// The last lines the server wrote to stderr, oldest first.
+    pub fn stderr_tail(&self) -> Vec<String> {
+        self.stderr_tail.lock().iter().cloned().collect()
+    }
+
     fn next_request_id(&self) -> jsonrpc::Id {
         let id = self.request_counter.fetch_add(1, Ordering::Relaxed);
         jsonrpc::Id::Num(id)
@@ -445,6 +582,9 @@ pub(crate) async fn initialize(&self, enable_snippets: bool) -> Result<lsp::Init
                     inlay_hint: Some(lsp::InlayHintWorkspaceClientCapabilities {
                         refresh_support: Some(false),
                     }),
+                    diagnostic: Some(lsp::DiagnosticWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
+                    }),
                     workspace_edit: Some(lsp::WorkspaceEditClientCapabilities {
                         document_changes: Some(true),
                         resource_operations: Some(vec![
@@ -532,6 +672,10 @@ pub(crate) async fn initialize(&self, enable_snippets: bool) -> Result<lsp::Init
                         dynamic_registration: Some(false),
                         resolve_support: None,
                     }),
+                    diagnostic: Some(lsp::DiagnosticClientCapabilities {
+                        dynamic_registration: Some(false),
+                        related_document_support: Some(true),
+                    }),
                     ..Default::default()
                 }),
                 window: Some(lsp::WindowClientCapabilities {
@@ -559,12 +703,24 @@ pub(crate) async fn initialize(&self, enable_snippets: bool) -> Result<lsp::Init
         self.request::<lsp::request::Initialize>(params).await
     }
 
+    /// Asks the server to shut down. A server the client connected to is left running, as
+    /// other clients may use it.
     pub async fn shutdown(&self) -> Result<()> {
+        self.exiting.store(true, Ordering::Relaxed);
+        if self.connected {
+            return Ok(());
+        }
         self.request::<lsp::request::Shutdown>(()).await
     }
 
-    pub fn exit(&self) -> impl Future<Output = Result<()>> {
-        self.notify::<lsp::notification::Exit>(())
+    /// Tells the server to exit, unless the client connected to it. The connection is closed
+    /// once the client is dropped.
+    pub async fn exit(&self) -> Result<()> {
+        self.exiting.store(true, Ordering::Relaxed);
+        if self.connected {
+            return Ok(());
+        }
+        self.notify::<lsp::notification::Exit>(()).await
     }
 
     /// Tries to shut down the language server but returns
@@ -907,6 +1063,87 @@ pub fn text_document_range_inlay_hints(
         Some(self.call::<lsp::request::InlayHintRequest>(params))
     }
 
+    fn diagnostic_options(&self) -> Option<&lsp::DiagnosticOptions> {
+        match self.capabilities().diagnostic_provider.as_ref()? {
+            lsp::DiagnosticServerCapabilities::Options(options) => Some(options),
+            lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+                Some(&options.diagnostic_options)
+            }
+        }
+    }
+
+    /// Pulls the diagnostics of a document, sending the result id of the last report so that the
+    /// server can answer that nothing changed.
+    pub fn text_document_diagnostic(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+    ) -> Option<impl Future<Output = Result<lsp::DocumentDiagnosticReportResult>>> {
+        let options = self.diagnostic_options()?;
+
+        let params = lsp::DocumentDiagnosticParams {
+            previous_result_id: self
+                .diagnostic_result_ids
+                .lock()
+                .get(&text_document.uri)
+                .cloned(),
+            text_document,
+            identifier: options.identifier.clone(),
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        let call = self.call::<lsp::request::DocumentDiagnosticRequest>(params);
+        Some(async move { Ok(serde_json::from_value(call.await?)?) })
+    }
+
+    /// Pulls the diagnostics of the whole workspace, sending the result ids of the last reports.
+    /// Returns `None` if the server only reports the diagnostics of open documents, or if the
+    /// previous pull wasn't answered yet.
+    pub fn workspace_diagnostic(
+        &self,
+    ) -> Option<impl Future<Output = Result<lsp::WorkspaceDiagnosticReportResult>>> {
+        let options = self
+            .diagnostic_options()
+            .filter(|options| options.workspace_diagnostics)?;
+        if self
+            .workspace_diagnostic_pending
+            .swap(true, Ordering::Relaxed)
+        {
+            return None;
+        }
+
+        let previous_result_ids = self
+            .diagnostic_result_ids
+            .lock()
+            .iter()
+            .map(|(uri, value)| lsp::PreviousResultId {
+                uri: uri.clone(),
+                value: value.clone(),
+            })
+            .collect();
+        let params = lsp::WorkspaceDiagnosticParams {
+            identifier: options.identifier.clone(),
+            previous_result_ids,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        let pending = self.workspace_diagnostic_pending.clone();
+        let call = self.call::<lsp::request::WorkspaceDiagnosticRequest>(params);
+        Some(async move {
+            let response = call.await;
+            pending.store(false, Ordering::Relaxed);
+            Ok(serde_json::from_value(response?)?)
+        })
+    }
+
+    /// Remembers the result id of a pulled diagnostic report of `uri` once it was applied, to be
+    /// sent with the next pull. Reports which were dropped must not be recorded, or the server
+    /// could answer that their diagnostics are unchanged.
+    pub fn record_diagnostic_result_id(&self, uri: lsp::Url, result_id: Option<&String>) {
+        record_result_id(&mut self.diagnostic_result_ids.lock(), uri, result_id);
+    }
+
     pub fn text_document_hover(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -1340,3 +1577,104 @@ pub fn command(&self, command: lsp::Command) -> Option<impl Future<Output = Resu
         Some(self.call::<lsp::request::ExecuteCommand>(params))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    #[tokio::test]
+    async fn connects_over_tcp() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let socket = listener.local_addr().unwrap().to_string();
+        let ls_config: LanguageServerConfiguration =
+            serde_json::from_value(serde_json::json!({ "socket": socket })).unwrap();
+
+        let (client, _incoming, _initialize_notify) =
+            Client::start(&ls_config, None, &[], &[], 0, None).unwrap();
+        assert_eq!(client.name(), socket);
+        let client = Arc::new(client);
+        tokio::spawn({
+            let client = client.clone();
+            async move { client.initialize(false).await }
+        });
+
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let received = read_until(&mut stream, r#""method":"initialize""#).await;
+        assert!(received.starts_with("Content-Length: "));
+    }
+
+    #[tokio::test]
+    async fn leaves_running_servers_running() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let socket = listener.local_addr().unwrap().to_string();
+        let ls_config: LanguageServerConfiguration =
+            serde_json::from_value(serde_json::json!({ "socket": socket })).unwrap();
+
+        let (client, _incoming, initialize_notify) =
+            Client::start(&ls_config, None, &[], &[], 0, None).unwrap();
+        let client = Arc::new(client);
+        let initialize = tokio::spawn({
+            let client = client.clone();
+            async move { client.initialize(false).await }
+        });
+
+        let (mut stream, _) = listener.accept().await.unwrap();
+        read_until(&mut stream, r#""method":"initialize""#).await;
+        let response = r#"{"jsonrpc":"2.0","id":0,"result":{"capabilities":{}}}"#;
+        let response = format!("Content-Length: {}\r\n\r\n{}", response.len(), response);
+        stream.write_all(response.as_bytes()).await.unwrap();
+        initialize.await.unwrap().unwrap();
+        initialize_notify.notify_one();
+
+        client.force_shutdown().await.unwrap();
+        assert!(client.is_exiting());
+        drop(client);
+
+        // the connection is closed without asking the server to shut down or exit
+        let received = read_until(&mut stream, "").await;
+        assert!(!received.contains(r#""method":"shutdown""#), "{}", received);
+        assert!(!received.contains(r#""method":"exit""#), "{}", received);
+    }
+
+    /// Reads from `stream` until the received text contains `pattern`, or else until the client
+    /// closes the connection.
+    async fn read_until(stream: &mut tokio::net::TcpStream, pattern: &str) -> String {
+        let mut received = String::new();
+        loop {
+            let mut buffer = [0; 1024];
+            let len = stream.read(&mut buffer).await.unwrap();
+            if len == 0 {
+                assert!(pattern.is_empty(), "the client closed the connection");
+                return received;
+            }
+            received.push_str(std::str::from_utf8(&buffer[..len]).unwrap());
+            if !pattern.is_empty() && received.contains(pattern) {
+                return received;
+            }
+        }
+    }
+
+    #[test]
+    fn records_diagnostic_result_ids() {
+        let main = lsp::Url::parse("file:///src/main.c").unwrap();
+        let header = lsp::Url::parse("file:///src/main.h").unwrap();
+        let mut result_ids = HashMap::new();
+
+        record_result_id(&mut result_ids, main.clone(), Some(&"1".to_string()));
+        record_result_id(&mut result_ids, header.clone(), Some(&"2".to_string()));
+        assert_eq!(result_ids.get(&main).unwrap(), "1");
+        assert_eq!(result_ids.get(&header).unwrap(), "2");
+
+        record_result_id(&mut result_ids, main.clone(), Some(&"3".to_string()));
+        assert_eq!(result_ids.get(&main).unwrap(), "3");
+
+        // A full report without a result id can't be answered with an unchanged report.
+        record_result_id(&mut result_ids, header.clone(), None);
+        assert_eq!(result_ids.get(&main).unwrap(), "3");
+        assert!(!result_ids.contains_key(&header));
+    }
+}
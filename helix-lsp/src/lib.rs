@@ -17,12 +17,13 @@
 use tokio::sync::mpsc::UnboundedReceiver;
 
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, VecDeque},
     path::{Path, PathBuf},
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
     },
+    time::{Duration, Instant},
 };
 
 use thiserror::Error;
@@ -546,6 +547,7 @@ pub enum MethodCall {
     WorkspaceFolders,
     WorkspaceConfiguration(lsp::ConfigurationParams),
     RegisterCapability(lsp::RegistrationParams),
+    WorkspaceDiagnosticRefresh,
 }
 
 impl MethodCall {
@@ -569,6 +571,7 @@ pub fn parse(method: &str, params: jsonrpc::Params) -> Result<MethodCall> {
                 let params: lsp::RegistrationParams = params.parse()?;
                 Self::RegisterCapability(params)
             }
+            lsp::request::WorkspaceDiagnosticRefresh::METHOD => Self::WorkspaceDiagnosticRefresh,
             _ => {
                 return Err(Error::Unhandled);
             }
@@ -622,9 +625,39 @@ pub fn parse(method: &str, params: jsonrpc::Params) -> Result<Notification> {
     }
 }
 
+/// The most times the servers of a language are restarted after crashing within
+/// [CRASH_WINDOW], after which they are left stopped.
+const MAX_CRASHES: usize = 5;
+const CRASH_WINDOW: Duration = Duration::from_secs(180);
+
+/// Records a crash at `now` in the recent crashes of a language, and returns how long to wait
+/// before restarting its server: one second after the first crash, doubling with each further
+/// crash within [CRASH_WINDOW]. Returns `None` if the server crashed too often to restart it.
+fn restart_delay(crashes: &mut VecDeque<Instant>, now: Instant) -> Option<Duration> {
+    while crashes
+        .front()
+        .map_or(false, |&crash| now.duration_since(crash) > CRASH_WINDOW)
+    {
+        crashes.pop_front();
+    }
+    crashes.push_back(now);
+    (crashes.len() <= MAX_CRASHES).then(|| Duration::from_secs(1 << (crashes.len() - 1)))
+}
+
+/// A language server which exited without being told to.
+#[derive(Debug)]
+pub struct Crash {
+    pub client: Arc<Client>,
+    /// How long to wait before restarting the server, or `None` if it crashed too often.
+    pub restart_delay: Option<Duration>,
+}
+
 #[derive(Debug)]
 pub struct Registry {
     inner: HashMap<LanguageId, Vec<(usize, Arc<Client>)>>,
+    /// The times the servers of each language crashed recently. The servers of a language which
+    /// crashed too often are left stopped until they are restarted or stopped on purpose.
+    crashes: HashMap<LanguageId, VecDeque<Instant>>,
 
     counter: AtomicUsize,
     pub incoming: SelectAll<UnboundedReceiverStream<(usize, Call)>>,
@@ -640,6 +673,7 @@ impl Registry {
     pub fn new() -> Self {
         Self {
             inner: HashMap::new(),
+            crashes: HashMap::new(),
             counter: AtomicUsize::new(0),
             incoming: SelectAll::new(),
         }
@@ -653,6 +687,33 @@ pub fn get_by_id(&self, id: usize) -> Option<&Client> {
             .map(|(_, client)| client.as_ref())
     }
 
+    /// Checks whether the server of the client with `id`, which exited, crashed. The server
+    /// crashed if the client is still registered and the server wasn't told to exit.
+    pub fn crashed(&mut self, id: usize) -> Option<Crash> {
+        let (language, client) = self.inner.iter().find_map(|(language, clients)| {
+            let (_, client) = clients.iter().find(|(client_id, _)| *client_id == id)?;
+            Some((language, client))
+        })?;
+        if client.is_exiting() {
+            return None;
+        }
+
+        let client = client.clone();
+        let crashes = self.crashes.entry(language.clone()).or_default();
+        let restart_delay = restart_delay(crashes, Instant::now());
+        Some(Crash {
+            client,
+            restart_delay,
+        })
+    }
+
+    /// Whether the servers of the language `scope` crashed too often to be started again.
+    fn gave_up(&self, scope: &str) -> bool {
+        self.crashes
+            .get(scope)
+            .map_or(false, |crashes| crashes.len() > MAX_CRASHES)
+    }
+
     pub fn remove_by_id(&mut self, id: usize) {
         self.inner.retain(|_, clients| {
             clients.retain(|&(client_id, _)| client_id != id);
@@ -673,6 +734,7 @@ pub fn restart(
         };
 
         let scope = language_config.scope.clone();
+        self.crashes.remove(&scope);
 
         match self.inner.entry(scope) {
             Entry::Vacant(_) => Ok(None),
@@ -705,6 +767,7 @@ pub fn restart(
 
     pub fn stop(&mut self, language_config: &LanguageConfiguration) {
         let scope = language_config.scope.clone();
+        self.crashes.remove(&scope);
 
         if let Some(clients) = self.inner.remove(&scope) {
             for (_, client) in clients {
@@ -726,6 +789,9 @@ pub fn get(
             Some(config) => config,
             None => return Ok(None),
         };
+        if self.gave_up(&language_config.scope) {
+            return Ok(None);
+        }
 
         let clients = self.inner.entry(language_config.scope.clone()).or_default();
         // check if we already have a client for this documents root that we can reuse
@@ -846,14 +912,11 @@ fn start_client(
     enable_snippets: bool,
 ) -> Result<NewClientResult> {
     let (client, incoming, initialize_notify) = Client::start(
-        &ls_config.command,
-        &ls_config.args,
+        ls_config,
         config.config.clone(),
-        ls_config.environment.clone(),
         &config.roots,
         config.workspace_lsp_roots.as_deref().unwrap_or(root_dirs),
         id,
-        ls_config.timeout,
         doc_path,
     )?;
 
@@ -950,8 +1013,52 @@ pub fn find_lsp_workspace(
 
 #[cfg(test)]
 mod tests {
-    use super::{lsp, util::*, OffsetEncoding};
+    use super::{lsp, restart_delay, util::*, OffsetEncoding, Registry, CRASH_WINDOW, MAX_CRASHES};
     use helix_core::Rope;
+    use std::{
+        collections::VecDeque,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn backs_off_restarting_crashed_servers() {
+        let mut crashes = VecDeque::new();
+        let start = Instant::now();
+        let delays: Vec<_> = (0..MAX_CRASHES + 1)
+            .map(|i| restart_delay(&mut crashes, start + Duration::from_secs(i as u64)))
+            .collect();
+        assert_eq!(
+            delays,
+            [1, 2, 4, 8, 16]
+                .into_iter()
+                .map(|secs| Some(Duration::from_secs(secs)))
+                .chain([None])
+                .collect::<Vec<_>>()
+        );
+
+        // the crashes are forgotten after a while
+        let later = start + CRASH_WINDOW + Duration::from_secs(MAX_CRASHES as u64 + 1);
+        assert_eq!(
+            restart_delay(&mut crashes, later),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn gives_up_on_servers_crashing_too_often() {
+        let mut registry = Registry::new();
+        let scope = "source.rust";
+        let crashes = registry.crashes.entry(scope.to_string()).or_default();
+        let start = Instant::now();
+        for i in 0..MAX_CRASHES {
+            restart_delay(crashes, start + Duration::from_secs(i as u64));
+        }
+        assert!(!registry.gave_up(scope));
+
+        let crashes = registry.crashes.get_mut(scope).unwrap();
+        restart_delay(crashes, start + Duration::from_secs(MAX_CRASHES as u64));
+        assert!(registry.gave_up(scope));
+    }
 
     #[test]
     fn converts_lsp_pos_to_pos() {
@@ -1,19 +1,26 @@
 use crate::{jsonrpc, Error, Result};
 use anyhow::Context;
 use log::{error, info};
+use parking_lot::Mutex as SyncMutex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
 use std::sync::Arc;
 use tokio::{
-    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, BufWriter},
-    process::{ChildStderr, ChildStdin, ChildStdout},
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt},
     sync::{
         mpsc::{unbounded_channel, Sender, UnboundedReceiver, UnboundedSender},
         Mutex, Notify,
     },
 };
 
+/// The number of lines of the server's stderr kept to report a crash.
+const STDERR_TAIL_LINES: usize = 5;
+
+pub type ServerReader = Box<dyn AsyncBufRead + Unpin + Send>;
+pub type ServerWriter = Box<dyn AsyncWrite + Unpin + Send>;
+
 #[derive(Debug)]
 pub enum Payload {
     Request {
@@ -42,10 +49,13 @@ pub struct Transport {
 }
 
 impl Transport {
+    /// Starts talking to a server over the streams `connection` resolves to, such as its stdio
+    /// or a socket. Messages to the server are queued until it is connected. If connecting fails,
+    /// the transport closes as if the server exited.
     pub fn start(
-        server_stdout: BufReader<ChildStdout>,
-        server_stdin: BufWriter<ChildStdin>,
-        server_stderr: BufReader<ChildStderr>,
+        connection: impl Future<Output = Result<(ServerReader, ServerWriter)>> + Send + 'static,
+        server_stderr: Option<ServerReader>,
+        stderr_tail: Arc<SyncMutex<VecDeque<String>>>,
         id: usize,
     ) -> (
         UnboundedReceiver<(usize, jsonrpc::Call)>,
@@ -63,19 +73,39 @@ pub fn start(
 
         let transport = Arc::new(transport);
 
-        tokio::spawn(Self::recv(
-            transport.clone(),
-            server_stdout,
-            client_tx.clone(),
-        ));
-        tokio::spawn(Self::err(transport.clone(), server_stderr));
-        tokio::spawn(Self::send(
-            transport,
-            server_stdin,
-            client_tx,
-            client_rx,
-            notify.clone(),
-        ));
+        if let Some(server_stderr) = server_stderr {
+            tokio::spawn(Self::err(
+                transport.clone(),
+                server_stderr,
+                stderr_tail.clone(),
+            ));
+        }
+        let initialize_notify = notify.clone();
+        tokio::spawn(async move {
+            let (server_stdout, server_stdin) = match connection.await {
+                Ok(streams) => streams,
+                Err(err) => {
+                    error!("failed to connect to language server: {}", err);
+                    stderr_tail
+                        .lock()
+                        .push_back(format!("failed to connect: {}", err));
+                    transport.close(&client_tx).await;
+                    return;
+                }
+            };
+            tokio::spawn(Self::recv(
+                transport.clone(),
+                server_stdout,
+                client_tx.clone(),
+            ));
+            tokio::spawn(Self::send(
+                transport,
+                server_stdin,
+                client_tx,
+                client_rx,
+                initialize_notify,
+            ));
+        });
 
         (rx, tx, notify)
     }
@@ -147,7 +177,7 @@ async fn recv_server_error(
 
     async fn send_payload_to_server(
         &self,
-        server_stdin: &mut BufWriter<ChildStdin>,
+        server_stdin: &mut ServerWriter,
         payload: Payload,
     ) -> Result<()> {
         //TODO: reuse string
@@ -167,7 +197,7 @@ async fn send_payload_to_server(
 
     async fn send_string_to_server(
         &self,
-        server_stdin: &mut BufWriter<ChildStdin>,
+        server_stdin: &mut ServerWriter,
         request: String,
     ) -> Result<()> {
         info!("-> {}", request);
@@ -233,9 +263,37 @@ async fn process_request_response(&self, output: jsonrpc::Output) -> Result<()>
         Ok(())
     }
 
+    /// Fails the outstanding requests and tells the client that the server exited.
+    async fn close(&self, client_tx: &UnboundedSender<(usize, jsonrpc::Call)>) {
+        // Close any outstanding requests.
+        for (id, tx) in self.pending_requests.lock().await.drain() {
+            match tx.send(Err(Error::StreamClosed)).await {
+                Ok(_) => (),
+                Err(_) => {
+                    error!("Could not close request on a closed channel (id={:?})", id)
+                }
+            }
+        }
+
+        // Hack: inject a terminated notification so we trigger code that needs to happen after exit
+        use lsp_types::notification::Notification as _;
+        let notification =
+            ServerMessage::Call(jsonrpc::Call::Notification(jsonrpc::Notification {
+                jsonrpc: None,
+                method: lsp_types::notification::Exit::METHOD.to_string(),
+                params: jsonrpc::Params::None,
+            }));
+        match self.process_server_message(client_tx, notification).await {
+            Ok(_) => {}
+            Err(err) => {
+                error!("err: <- {:?}", err);
+            }
+        }
+    }
+
     async fn recv(
         transport: Arc<Self>,
-        mut server_stdout: BufReader<ChildStdout>,
+        mut server_stdout: ServerReader,
         client_tx: UnboundedSender<(usize, jsonrpc::Call)>,
     ) {
         let mut recv_buffer = String::new();
@@ -251,33 +309,7 @@ async fn recv(
                     };
                 }
                 Err(Error::StreamClosed) => {
-                    // Close any outstanding requests.
-                    for (id, tx) in transport.pending_requests.lock().await.drain() {
-                        match tx.send(Err(Error::StreamClosed)).await {
-                            Ok(_) => (),
-                            Err(_) => {
-                                error!("Could not close request on a closed channel (id={:?})", id)
-                            }
-                        }
-                    }
-
-                    // Hack: inject a terminated notification so we trigger code that needs to happen after exit
-                    use lsp_types::notification::Notification as _;
-                    let notification =
-                        ServerMessage::Call(jsonrpc::Call::Notification(jsonrpc::Notification {
-                            jsonrpc: None,
-                            method: lsp_types::notification::Exit::METHOD.to_string(),
-                            params: jsonrpc::Params::None,
-                        }));
-                    match transport
-                        .process_server_message(&client_tx, notification)
-                        .await
-                    {
-                        Ok(_) => {}
-                        Err(err) => {
-                            error!("err: <- {:?}", err);
-                        }
-                    }
+                    transport.close(&client_tx).await;
                     break;
                 }
                 Err(err) => {
@@ -288,11 +320,25 @@ async fn recv(
         }
     }
 
-    async fn err(_transport: Arc<Self>, mut server_stderr: BufReader<ChildStderr>) {
+    async fn err(
+        _transport: Arc<Self>,
+        mut server_stderr: ServerReader,
+        stderr_tail: Arc<SyncMutex<VecDeque<String>>>,
+    ) {
         let mut recv_buffer = String::new();
         loop {
             match Self::recv_server_error(&mut server_stderr, &mut recv_buffer).await {
-                Ok(_) => {}
+                Ok(_) => {
+                    let line = recv_buffer.trim_end();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    let mut tail = stderr_tail.lock();
+                    if tail.len() == STDERR_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line.to_owned());
+                }
                 Err(err) => {
                     error!("err: <- {:?}", err);
                     break;
@@ -303,7 +349,7 @@ async fn err(_transport: Arc<Self>, mut server_stderr: BufReader<ChildStderr>) {
 
     async fn send(
         transport: Arc<Self>,
-        mut server_stdin: BufWriter<ChildStdin>,
+        mut server_stdin: ServerWriter,
         client_tx: UnboundedSender<(usize, jsonrpc::Call)>,
         mut client_rx: UnboundedReceiver<Payload>,
         initialize_notify: Arc<Notify>,
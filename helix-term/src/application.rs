@@ -1,11 +1,9 @@
 use arc_swap::{access::Map, ArcSwap};
 use futures_util::Stream;
 use helix_core::{
-    diagnostic::{DiagnosticTag, NumberOrString},
-    path::get_relative_path,
-    pos_at_coords, syntax, Selection,
+    diagnostic::DiagnosticProvider, path::get_relative_path, pos_at_coords, syntax, Selection,
 };
-use helix_lsp::{lsp, util::lsp_pos_to_pos, LspProgressMap};
+use helix_lsp::{lsp, LspProgressMap};
 use helix_view::{
     align_view,
     document::DocumentSavedEventResult,
@@ -20,16 +18,17 @@
 
 use crate::{
     args::Args,
-    commands::apply_workspace_edit,
+    commands::{apply_workspace_edit, pull_diagnostics_for_document, pull_workspace_diagnostics},
     compositor::{Compositor, Event},
     config::Config,
-    job::Jobs,
+    job::{Callback, Jobs},
     keymap::Keymaps,
     ui::{self, overlay::overlaid},
 };
 
 use log::{debug, error, warn};
 use std::{
+    fmt::Write,
     io::{stdin, stdout},
     path::Path,
     sync::Arc,
@@ -152,6 +151,14 @@ pub fn new(
             })),
         );
 
+        // integration tests shouldn't depend on or change the state of the user's workspaces
+        #[cfg(not(feature = "integration"))]
+        {
+            editor.workspace_state =
+                helix_view::workspace::WorkspaceState::load(&helix_loader::find_workspace().0);
+            editor.breakpoints = editor.workspace_state.breakpoints.clone();
+        }
+
         let keys = Box::new(Map::new(Arc::clone(&config), |config: &Config| {
             &config.keys
         }));
@@ -168,7 +175,7 @@ pub fn new(
             if first.is_dir() {
                 std::env::set_current_dir(first).context("set current dir")?;
                 editor.new_file(Action::VerticalSplit);
-                let picker = ui::file_picker(".".into(), &config.load().editor);
+                let picker = ui::file_picker(".".into(), &editor);
                 compositor.push(Box::new(overlaid(picker)));
             } else {
                 let nr_of_files = args.files.len();
@@ -330,6 +337,10 @@ pub async fn event_loop_until_idle<S>(&mut self, input_stream: &mut S) -> bool
                     self.jobs.handle_callback(&mut self.editor, &mut self.compositor, callback);
                     self.render().await;
                 }
+                Some(callback) = self.jobs.callbacks.recv() => {
+                    self.jobs.handle_callback(&mut self.editor, &mut self.compositor, Ok(Some(callback)));
+                    self.render().await;
+                }
                 event = self.editor.wait_event() => {
                     let _idle_handled = self.handle_editor_event(event).await;
 
@@ -562,6 +573,13 @@ pub fn handle_document_write(&mut self, doc_save_event: DocumentSavedEventResult
             let _ = self.editor.refresh_language_server(id);
         }
 
+        // servers may only check the document once it's saved
+        let doc = doc_mut!(self.editor, &doc_save_event.doc_id);
+        pull_diagnostics_for_document(doc, &mut self.jobs);
+        if let Some(language_server) = doc.language_server() {
+            pull_workspace_diagnostics(language_server, &mut self.jobs);
+        }
+
         // TODO: fix being overwritten by lsp
         self.editor.set_status(format!(
             "'{}' written, {}L {}B",
@@ -571,6 +589,19 @@ pub fn handle_document_write(&mut self, doc_save_event: DocumentSavedEventResult
         ));
     }
 
+    /// Pulls the diagnostics of the documents a language server is attached to, and those of
+    /// the whole workspace.
+    fn pull_language_server_diagnostics(&mut self, server_id: usize) {
+        for doc in self.editor.documents.values_mut() {
+            if doc.language_server().map(|server| server.id()) == Some(server_id) {
+                pull_diagnostics_for_document(doc, &mut self.jobs);
+            }
+        }
+        if let Some(language_server) = self.editor.language_servers.get_by_id(server_id) {
+            pull_workspace_diagnostics(language_server, &mut self.jobs);
+        }
+    }
+
     #[inline(always)]
     pub async fn handle_editor_event(&mut self, event: EditorEvent) -> bool {
         log::debug!("received editor event: {:?}", event);
@@ -594,8 +625,8 @@ pub async fn handle_editor_event(&mut self, event: EditorEvent) -> bool {
                     self.last_render = Instant::now();
                 }
             }
-            EditorEvent::DebuggerEvent(payload) => {
-                let needs_render = self.editor.handle_debugger_message(payload).await;
+            EditorEvent::DebuggerEvent((id, payload)) => {
+                let needs_render = self.editor.handle_debugger_message(id, payload).await;
                 if needs_render {
                     self.render().await;
                 }
@@ -650,6 +681,60 @@ pub async fn handle_terminal_events(
         }
     }
 
+    /// Reports a crashed language server with its exit status and last stderr lines, and
+    /// restarts it for the documents it was attached to after the backoff delay. Reattaching
+    /// the documents sends the server their current text and version.
+    fn restart_crashed_language_server(
+        &mut self,
+        crash: helix_lsp::Crash,
+        doc_ids: Vec<helix_view::DocumentId>,
+    ) {
+        let helix_lsp::Crash {
+            client,
+            restart_delay,
+        } = crash;
+        let status_sender = self.jobs.callback_sender();
+
+        self.jobs.callback(async move {
+            // The server's stdout closes right before it exits.
+            let status = tokio::time::timeout(Duration::from_secs(1), client.wait_for_exit())
+                .await
+                .ok()
+                .flatten()
+                .map_or_else(
+                    || "unknown exit status".to_owned(),
+                    |status| status.to_string(),
+                );
+            let mut message = format!("Language server `{}` crashed ({})", client.name(), status);
+            match restart_delay {
+                Some(delay) => write!(message, ", restarting in {}s", delay.as_secs()).unwrap(),
+                None => message.push_str(", too many crashes to restart it"),
+            }
+            let stderr = client.stderr_tail();
+            log::error!("{}. Last stderr lines:\n{}", message, stderr.join("\n"));
+            if !stderr.is_empty() {
+                write!(message, ": {}", stderr.join(" | ")).unwrap();
+            }
+            let report = Callback::Editor(Box::new(move |editor: &mut Editor| {
+                editor.set_error(message)
+            }));
+            let delay = match restart_delay {
+                Some(delay) => delay,
+                None => return Ok(report),
+            };
+            let _ = status_sender.send(report);
+
+            tokio::time::sleep(delay).await;
+            let call = Callback::Editor(Box::new(move |editor: &mut Editor| {
+                for doc_id in doc_ids {
+                    // documents closed in the meantime are skipped
+                    editor.refresh_language_server(doc_id);
+                }
+            }));
+            Ok(call)
+        });
+    }
+
     pub async fn handle_language_server_message(
         &mut self,
         call: helix_lsp::Call,
@@ -709,141 +794,16 @@ pub async fn handle_language_server_message(
                                 language_id,
                             ));
                         }
-                    }
-                    Notification::PublishDiagnostics(mut params) => {
-                        let path = match params.uri.to_file_path() {
-                            Ok(path) => path,
-                            Err(_) => {
-                                log::error!("Unsupported file URI: {}", params.uri);
-                                return;
-                            }
-                        };
-                        let doc = self.editor.document_by_path_mut(&path).filter(|doc| {
-                            if let Some(version) = params.version {
-                                if version != doc.version() {
-                                    log::info!("Version ({version}) is out of date for {path:?} (expected ({}), dropping PublishDiagnostic notification", doc.version());
-                                    return false;
-                                }
-                            }
-
-                            true
-                        });
-
-                        if let Some(doc) = doc {
-                            let lang_conf = doc.language_config();
-                            let text = doc.text();
-
-                            let diagnostics = params
-                                .diagnostics
-                                .iter()
-                                .filter_map(|diagnostic| {
-                                    use helix_core::diagnostic::{Diagnostic, Range, Severity::*};
-                                    use lsp::DiagnosticSeverity;
-
-                                    let language_server = if let Some(language_server) = doc.language_server() {
-                                        language_server
-                                    } else {
-                                        log::warn!("Discarding diagnostic because language server is not initialized: {:?}", diagnostic);
-                                        return None;
-                                    };
-
-                                    // TODO: convert inside server
-                                    let start = if let Some(start) = lsp_pos_to_pos(
-                                        text,
-                                        diagnostic.range.start,
-                                        language_server.offset_encoding(),
-                                    ) {
-                                        start
-                                    } else {
-                                        log::warn!("lsp position out of bounds - {:?}", diagnostic);
-                                        return None;
-                                    };
-
-                                    let end = if let Some(end) = lsp_pos_to_pos(
-                                        text,
-                                        diagnostic.range.end,
-                                        language_server.offset_encoding(),
-                                    ) {
-                                        end
-                                    } else {
-                                        log::warn!("lsp position out of bounds - {:?}", diagnostic);
-                                        return None;
-                                    };
-
-                                    let severity =
-                                        diagnostic.severity.map(|severity| match severity {
-                                            DiagnosticSeverity::ERROR => Error,
-                                            DiagnosticSeverity::WARNING => Warning,
-                                            DiagnosticSeverity::INFORMATION => Info,
-                                            DiagnosticSeverity::HINT => Hint,
-                                            severity => unreachable!(
-                                                "unrecognized diagnostic severity: {:?}",
-                                                severity
-                                            ),
-                                        });
-
-                                    if let Some(lang_conf) = lang_conf {
-                                        if let Some(severity) = severity {
-                                            if severity < lang_conf.diagnostic_severity {
-                                                return None;
-                                            }
-                                        }
-                                    };
-
-                                    let code = match diagnostic.code.clone() {
-                                        Some(x) => match x {
-                                            lsp::NumberOrString::Number(x) => {
-                                                Some(NumberOrString::Number(x))
-                                            }
-                                            lsp::NumberOrString::String(x) => {
-                                                Some(NumberOrString::String(x))
-                                            }
-                                        },
-                                        None => None,
-                                    };
-
-                                    let tags = if let Some(ref tags) = diagnostic.tags {
-                                        let new_tags = tags.iter().filter_map(|tag| {
-                                            match *tag {
-                                                lsp::DiagnosticTag::DEPRECATED => Some(DiagnosticTag::Deprecated),
-                                                lsp::DiagnosticTag::UNNECESSARY => Some(DiagnosticTag::Unnecessary),
-                                                _ => None
-                                            }
-                                        }).collect();
-
-                                        new_tags
-                                    } else {
-                                        Vec::new()
-                                    };
-
-                                    Some(Diagnostic {
-                                        range: Range { start, end },
-                                        line: diagnostic.range.start.line as usize,
-                                        message: diagnostic.message.clone(),
-                                        severity,
-                                        code,
-                                        tags,
-                                        source: diagnostic.source.clone(),
-                                        data: diagnostic.data.clone(),
-                                    })
-                                })
-                                .collect();
-
-                            doc.set_diagnostics(diagnostics);
-                        }
 
-                        // Sort diagnostics first by severity and then by line numbers.
-                        // Note: The `lsp::DiagnosticSeverity` enum is already defined in decreasing order
-                        params
-                            .diagnostics
-                            .sort_unstable_by_key(|d| (d.severity, d.range.start));
-
-                        // Insert the original lsp::Diagnostics here because we may have no open document
-                        // for diagnosic message and so we can't calculate the exact position.
-                        // When using them later in the diagnostics picker, we calculate them on-demand.
-                        self.editor
-                            .diagnostics
-                            .insert(params.uri, params.diagnostics);
+                        // pull the diagnostics of servers which don't publish them on their own
+                        self.pull_language_server_diagnostics(server_id);
+                    }
+                    Notification::PublishDiagnostics(params) => {
+                        self.editor.handle_lsp_diagnostics(
+                            params.uri,
+                            params.version,
+                            params.diagnostics,
+                        );
                     }
                     Notification::ShowMessage(params) => {
                         log::warn!("unhandled window/showMessage: {:?}", params);
@@ -882,6 +842,7 @@ pub async fn handle_language_server_message(
                                     self.lsp_progress.end_progress(server_id, &token);
                                     if !self.lsp_progress.is_progressing(server_id) {
                                         editor_view.spinners_mut().get_or_create(server_id).stop();
+                                        editor_view.spinners_mut().set_message(server_id, None);
                                     }
                                     self.editor.clear_status();
 
@@ -925,9 +886,13 @@ pub async fn handle_language_server_message(
                             self.lsp_progress.end_progress(server_id, &token);
                             if !self.lsp_progress.is_progressing(server_id) {
                                 editor_view.spinners_mut().get_or_create(server_id).stop();
+                                editor_view.spinners_mut().set_message(server_id, None);
                             }
                         } else {
                             self.lsp_progress.update(server_id, token, work);
+                            editor_view
+                                .spinners_mut()
+                                .set_message(server_id, Some(status.clone()));
                         }
 
                         if self.config.load().editor.lsp.display_messages {
@@ -938,9 +903,11 @@ pub async fn handle_language_server_message(
                         // do nothing
                     }
                     Notification::Exit => {
-                        self.editor.set_status("Language server exited");
+                        let crash = self.editor.language_servers.crashed(server_id);
 
-                        // Clear any diagnostics for documents with this server open.
+                        // Clear any diagnostics for documents with this server open, and detach
+                        // the server from them.
+                        let mut doc_ids = Vec::new();
                         let urls: Vec<_> = self
                             .editor
                             .documents_mut()
@@ -948,7 +915,9 @@ pub async fn handle_language_server_message(
                                 if doc.language_server().map(|server| server.id())
                                     == Some(server_id)
                                 {
-                                    doc.set_diagnostics(Vec::new());
+                                    doc.replace_diagnostics(&DiagnosticProvider::Lsp, Vec::new());
+                                    doc.set_language_server(None);
+                                    doc_ids.push(doc.id());
                                     doc.url()
                                 } else {
                                     None
@@ -962,6 +931,15 @@ pub async fn handle_language_server_message(
 
                         // Remove the language server from the registry.
                         self.editor.language_servers.remove_by_id(server_id);
+
+                        match crash {
+                            Some(crash) => self.restart_crashed_language_server(crash, doc_ids),
+                            None => {
+                                // The documents stay detached, as the server was stopped or
+                                // replaced on purpose.
+                                self.editor.set_status("Language server exited");
+                            }
+                        }
                     }
                 }
             }
@@ -1056,6 +1034,10 @@ pub async fn handle_language_server_message(
                             .collect();
                         Ok(json!(result))
                     }
+                    Ok(MethodCall::WorkspaceDiagnosticRefresh) => {
+                        self.pull_language_server_diagnostics(server_id);
+                        Ok(serde_json::Value::Null)
+                    }
                     Ok(MethodCall::RegisterCapability(_params)) => {
                         log::warn!("Ignoring a client/registerCapability request because dynamic capability registration is not enabled. Please report this upstream to the language server");
                         // Language Servers based on the `vscode-languageserver-node` library often send
@@ -1148,6 +1130,13 @@ pub async fn close(&mut self) -> Vec<anyhow::Error> {
             errs.push(err);
         }
 
+        self.editor.sync_breakpoints();
+        let breakpoints = &self.editor.breakpoints;
+        self.editor.workspace_state.set_breakpoints(breakpoints);
+        if let Err(err) = self.editor.workspace_state.save() {
+            log::error!("Error saving workspace state: {}", err);
+        }
+
         if self.editor.close_language_servers(None).await.is_err() {
             log::error!("Timed out waiting for language servers to shutdown");
             errs.push(anyhow::format_err!(
@@ -37,6 +37,7 @@
     info::Info,
     input::KeyEvent,
     keyboard::KeyCode,
+    quickfix::Quickfix,
     tree,
     view::View,
     Document, DocumentId, Editor, ViewId,
@@ -50,7 +51,7 @@
 use crate::{
     args,
     compositor::{self, Component, Compositor},
-    filter_picker_entry,
+    ctrl, filter_picker_entry,
     job::Callback,
     keymap::ReverseKeymap,
     ui::{
@@ -276,6 +277,10 @@ pub fn doc(&self) -> &str {
         extend_search_prev, "Add previous search match to selection",
         search_selection, "Use current selection as search pattern",
         make_search_word_bounded, "Modify current search to make it word bounded",
+        select_next_occurrence, "Add next occurrence of primary selection to selections",
+        select_prev_occurrence, "Add previous occurrence of primary selection to selections",
+        skip_next_occurrence, "Skip primary selection and select its next occurrence",
+        skip_prev_occurrence, "Skip primary selection and select its previous occurrence",
         global_search, "Global search in workspace folder",
         extend_line, "Select current line, if already selected, extend to another line based on the anchor",
         extend_line_below, "Select current line, if already selected, extend to next line",
@@ -339,6 +344,10 @@ pub fn doc(&self) -> &str {
         goto_prev_change, "Goto previous change",
         goto_first_change, "Goto first change",
         goto_last_change, "Goto last change",
+        goto_next_quickfix, "Goto next quickfix entry",
+        goto_prev_quickfix, "Goto previous quickfix entry",
+        goto_first_quickfix, "Goto first quickfix entry",
+        goto_last_quickfix, "Goto last quickfix entry",
         goto_line_start, "Goto line start",
         goto_line_end, "Goto line end",
         goto_next_buffer, "Goto next buffer",
@@ -390,6 +399,7 @@ pub fn doc(&self) -> &str {
         completion, "Invoke completion popup",
         hover, "Show docs for item under cursor",
         toggle_comments, "Comment/uncomment selections",
+        toggle_block_comments, "Block comment/uncomment selections",
         rotate_selections_forward, "Rotate selections forward",
         rotate_selections_backward, "Rotate selections backward",
         rotate_selection_contents_forward, "Rotate selection contents forward",
@@ -398,6 +408,11 @@ pub fn doc(&self) -> &str {
         shrink_selection, "Shrink selection to previously expanded syntax node",
         select_next_sibling, "Select next sibling in syntax tree",
         select_prev_sibling, "Select previous sibling in syntax tree",
+        swap_next_sibling, "Swap syntax node with next sibling",
+        swap_prev_sibling, "Swap syntax node with previous sibling",
+        raise_node, "Replace parent syntax node with selected node",
+        splice_node, "Remove delimiters around syntax node",
+        wrap_selections, "Wrap selections using a template",
         jump_forward, "Jump forward on jumplist",
         jump_backward, "Jump backward on jumplist",
         save_selection, "Save current selection to jumplist",
@@ -452,14 +467,25 @@ pub fn doc(&self) -> &str {
         dap_step_in, "Step in",
         dap_step_out, "Step out",
         dap_next, "Step to next",
+        dap_next_instruction, "Step to next instruction",
+        dap_step_in_instruction, "Step in by instruction",
+        dap_step_back, "Step back",
+        dap_reverse_continue, "Continue program execution backwards",
+        dap_run_to_cursor, "Continue program execution until the cursor line",
+        dap_goto_cursor, "Jump program execution to the cursor line",
         dap_variables, "List variables",
+        dap_panels, "Show and focus or hide the debugger panels",
+        dap_repl, "Evaluate an expression in the debug console",
+        dap_disassemble, "Show the disassembly around the instruction pointer",
         dap_terminate, "End debug session",
         dap_edit_condition, "Edit breakpoint condition on current line",
         dap_edit_log, "Edit breakpoint log message on current line",
         dap_switch_thread, "Switch current thread",
         dap_switch_stack_frame, "Switch stack frame",
+        dap_switch_session, "Switch debug session",
         dap_enable_exceptions, "Enable exception breakpoints",
         dap_disable_exceptions, "Disable exception breakpoints",
+        dap_exception_filters, "Enable or disable an exception filter",
         shell_pipe, "Pipe selections through shell command",
         shell_pipe_to, "Pipe selections into shell command ignoring output",
         shell_insert_output, "Insert shell command output before selections",
@@ -762,22 +788,22 @@ fn goto_buffer(editor: &mut Editor, direction: Direction) {
 
     let id = match direction {
         Direction::Forward => {
-            let iter = editor.documents.keys();
-            let mut iter = iter.skip_while(|id| *id != &current);
+            let iter = editor.buffers().map(|doc| doc.id());
+            let mut iter = iter.skip_while(|id| *id != current);
             iter.next(); // skip current item
-            iter.next().or_else(|| editor.documents.keys().next())
+            iter.next()
+                .or_else(|| editor.buffers().map(|doc| doc.id()).next())
         }
         Direction::Backward => {
-            let iter = editor.documents.keys();
-            let mut iter = iter.rev().skip_while(|id| *id != &current);
+            let iter = editor.buffers().map(|doc| doc.id());
+            let mut iter = iter.rev().skip_while(|id| *id != current);
             iter.next(); // skip current item
-            iter.next().or_else(|| editor.documents.keys().rev().next())
+            iter.next()
+                .or_else(|| editor.buffers().map(|doc| doc.id()).rev().next())
         }
     }
     .unwrap();
 
-    let id = *id;
-
     editor.switch(id, Action::Replace);
 }
 
@@ -1152,14 +1178,53 @@ fn goto_file_impl(cx: &mut Context, action: Action) {
     }
     for sel in paths {
         let p = sel.trim();
-        if !p.is_empty() {
-            if let Err(e) = cx.editor.open(&PathBuf::from(p), action) {
-                cx.editor.set_error(format!("Open file failed: {:?}", e));
-            }
+        if p.is_empty() {
+            continue;
+        }
+        let (p, position) = split_file_location(p);
+        if let Err(e) = cx.editor.open(&PathBuf::from(p), action) {
+            cx.editor.set_error(format!("Open file failed: {:?}", e));
+            continue;
+        }
+        if let Some(position) = position {
+            let (view, doc) = current!(cx.editor);
+            let pos = pos_at_coords(doc.text().slice(..), position, true);
+            doc.set_selection(view.id, Selection::point(pos));
+            align_view(doc, view, Align::Center);
         }
     }
 }
 
+/// Splits a trailing `:line` or `:line:column` as printed by compilers off `location`,
+/// unless a file with the whole name exists.
+fn split_file_location(location: &str) -> (&str, Option<Position>) {
+    if Path::new(location).exists() {
+        return (location, None);
+    }
+
+    let mut path = location.trim_end_matches(':');
+    let mut numbers = Vec::new();
+    while numbers.len() < 2 {
+        match path.rsplit_once(':') {
+            Some((rest, number)) => match number.parse::<usize>() {
+                Ok(number) => {
+                    numbers.push(number.saturating_sub(1));
+                    path = rest;
+                }
+                Err(_) => break,
+            },
+            None => break,
+        }
+    }
+
+    match numbers[..] {
+        [] => (location, None),
+        [line] => (path, Some(Position::new(line, 0))),
+        [column, line] => (path, Some(Position::new(line, column))),
+        _ => unreachable!(),
+    }
+}
+
 fn extend_word_impl<F>(cx: &mut Context, extend_fn: F)
 where
     F: Fn(RopeSlice, Range, usize) -> Range,
@@ -1969,6 +2034,55 @@ fn make_search_word_bounded(cx: &mut Context) {
     cx.editor.set_status(msg);
 }
 
+fn select_occurrence_impl(cx: &mut Context, direction: Direction, skip: bool) {
+    let count = cx.count();
+    let scrolloff = cx.editor.config().scrolloff;
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let mut selection = doc.selection(view.id).clone();
+    let mut wrapped = false;
+
+    for _ in 0..count {
+        match selection::find_occurrence(text, &selection, direction) {
+            Some((range, did_wrap)) => {
+                wrapped |= did_wrap;
+                let index = selection.primary_index();
+                selection = if skip {
+                    selection.replace(index, range)
+                } else {
+                    selection.push(range)
+                };
+            }
+            None => {
+                cx.editor.set_error("No more occurrences");
+                return;
+            }
+        }
+    }
+
+    doc.set_selection(view.id, selection);
+    view.ensure_cursor_in_view_center(doc, scrolloff);
+    if wrapped {
+        cx.editor.set_status("Wrapped around document");
+    }
+}
+
+fn select_next_occurrence(cx: &mut Context) {
+    select_occurrence_impl(cx, Direction::Forward, false);
+}
+
+fn select_prev_occurrence(cx: &mut Context) {
+    select_occurrence_impl(cx, Direction::Backward, false);
+}
+
+fn skip_next_occurrence(cx: &mut Context) {
+    select_occurrence_impl(cx, Direction::Forward, true);
+}
+
+fn skip_prev_occurrence(cx: &mut Context) {
+    select_occurrence_impl(cx, Direction::Backward, true);
+}
+
 fn global_search(cx: &mut Context) {
     #[derive(Debug)]
     struct FileResult {
@@ -2118,8 +2232,8 @@ fn format(&self, current_path: &Self::Data) -> Row {
                     all_matches,
                     current_path,
                     move |cx, FileResult { path, line_num }, action| {
-                        match cx.editor.open(path, action) {
-                            Ok(_) => {}
+                        let doc_id = match cx.editor.open(path, action) {
+                            Ok(doc_id) => doc_id,
                             Err(e) => {
                                 cx.editor.set_error(format!(
                                     "Failed to open file '{}': {}",
@@ -2128,10 +2242,12 @@ fn format(&self, current_path: &Self::Data) -> Row {
                                 ));
                                 return;
                             }
-                        }
+                        };
 
                         let line_num = *line_num;
-                        let (view, doc) = current!(cx.editor);
+                        // the document is only loaded when several matches are opened at once
+                        let view = view_mut!(cx.editor);
+                        let doc = doc_mut!(cx.editor, &doc_id);
                         let text = doc.text();
                         if line_num >= text.len_lines() {
                             cx.editor.set_error("The line you jumped to does not exist anymore because the file has changed.");
@@ -2141,7 +2257,9 @@ fn format(&self, current_path: &Self::Data) -> Row {
                         let end = text.line_to_char((line_num + 1).min(text.len_lines()));
 
                         doc.set_selection(view.id, Selection::single(start, end));
-                        align_view(doc, view, Align::Center);
+                        if !matches!(action, Action::Load) {
+                            align_view(doc, view, Align::Center);
+                        }
                     },
                     |_editor, FileResult { path, line_num }| {
                         Some((path.clone().into(), Some((*line_num, *line_num))))
@@ -2420,7 +2538,7 @@ fn append_mode(cx: &mut Context) {
 
 fn file_picker(cx: &mut Context) {
     let root = find_workspace().0;
-    let picker = ui::file_picker(root, &cx.editor.config());
+    let picker = ui::file_picker(root, cx.editor).with_id("file_picker");
     cx.push_layer(Box::new(overlaid(picker)));
 }
 
@@ -2437,12 +2555,13 @@ fn file_picker_in_current_buffer_directory(cx: &mut Context) {
         }
     };
 
-    let picker = ui::file_picker(path, &cx.editor.config());
+    let picker =
+        ui::file_picker(path, cx.editor).with_id("file_picker_in_current_buffer_directory");
     cx.push_layer(Box::new(overlaid(picker)));
 }
 fn file_picker_in_current_directory(cx: &mut Context) {
     let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("./"));
-    let picker = ui::file_picker(cwd, &cx.editor.config());
+    let picker = ui::file_picker(cwd, cx.editor).with_id("file_picker_in_current_directory");
     cx.push_layer(Box::new(overlaid(picker)));
 }
 
@@ -2507,7 +2626,18 @@ fn format(&self, _data: &Self::Data) -> Row {
                 .cursor_line(doc.text().slice(..));
             Some((meta.id.into(), Some((line, line))))
         },
-    );
+    )
+    .with_bulk_action(ctrl!('x'), |cx, metas| {
+        let doc_ids: Vec<_> = metas.iter().map(|meta| meta.id).collect();
+        if let Err(err) = buffer_close_by_ids_impl(cx, &doc_ids, false) {
+            cx.editor.set_error(err.to_string());
+        }
+    })
+    .with_boost({
+        let frecency = cx.editor.workspace_state.frecency.clone();
+        move |meta: &BufferMeta| meta.path.as_deref().map_or(0, |path| frecency.score(path))
+    })
+    .with_id("buffer_picker");
     cx.push_layer(Box::new(overlaid(picker)));
 }
 
@@ -2587,9 +2717,12 @@ fn format(&self, _data: &Self::Data) -> Row {
         |cx, meta, action| {
             cx.editor.switch(meta.id, action);
             let config = cx.editor.config();
-            let (view, doc) = current!(cx.editor);
+            let view = view_mut!(cx.editor);
+            let doc = doc_mut!(cx.editor, &meta.id);
             doc.set_selection(view.id, meta.selection.clone());
-            view.ensure_cursor_in_view_center(doc, config.scrolloff);
+            if !matches!(action, Action::Load) {
+                view.ensure_cursor_in_view_center(doc, config.scrolloff);
+            }
         },
         |editor, meta| {
             let doc = &editor.documents.get(&meta.id)?;
@@ -2681,6 +2814,33 @@ fn last_picker(cx: &mut Context) {
     cx.callback = Some(Box::new(|compositor, cx| {
         if let Some(picker) = compositor.last_picker.take() {
             compositor.push(picker);
+        } else if let Some(state) = cx.editor.workspace_state.last_picker.clone() {
+            // The picker is from a previous session: open it again with its command and
+            // apply the saved query and cursor to it.
+            let (command, id) = match state.command.parse::<MappableCommand>() {
+                Ok(command @ MappableCommand::Static { name, .. }) => (command, name),
+                _ => {
+                    cx.editor.set_error("no last picker");
+                    return;
+                }
+            };
+
+            let mut ctx = Context {
+                register: None,
+                count: None,
+                editor: cx.editor,
+                callback: None,
+                on_next_key_callback: None,
+                jobs: cx.jobs,
+            };
+            command.execute(&mut ctx);
+            if let Some(callback) = ctx.callback.take() {
+                callback(compositor, cx);
+            }
+            if let Some(mut picker) = compositor.remove(id) {
+                picker.restore_state(&state, cx.editor);
+                compositor.push(picker);
+            }
         } else {
             cx.editor.set_error("no last picker")
         }
@@ -3023,6 +3183,73 @@ fn goto_prev_diag(cx: &mut Context) {
     doc.set_selection(view.id, selection);
 }
 
+fn goto_next_quickfix(cx: &mut Context) {
+    goto_quickfix_impl(cx, |quickfix, count| quickfix.next(count).is_some());
+}
+
+fn goto_prev_quickfix(cx: &mut Context) {
+    goto_quickfix_impl(cx, |quickfix, count| quickfix.prev(count).is_some());
+}
+
+fn goto_first_quickfix(cx: &mut Context) {
+    goto_quickfix_impl(cx, |quickfix, _| quickfix.select(0).is_some());
+}
+
+fn goto_last_quickfix(cx: &mut Context) {
+    goto_quickfix_impl(cx, |quickfix, _| quickfix.select(usize::MAX).is_some());
+}
+
+fn goto_quickfix_impl(cx: &mut Context, select: impl FnOnce(&mut Quickfix, usize) -> bool) {
+    let count = cx.count();
+    let editor = &mut cx.editor;
+    editor.sync_quickfix();
+
+    if editor.quickfix.is_empty() {
+        editor.set_error("quickfix list is empty");
+        return;
+    }
+    if !select(&mut editor.quickfix, count) {
+        editor.set_status("no more quickfix entries");
+        return;
+    }
+    goto_quickfix_entry(editor);
+}
+
+/// Opens the current quickfix entry and puts the cursor on it.
+pub(crate) fn goto_quickfix_entry(editor: &mut Editor) {
+    let path = match editor.quickfix.current() {
+        Some(entry) => entry.path.clone(),
+        None => return,
+    };
+
+    let (view, doc) = current!(editor);
+    push_jump(view, doc);
+
+    if let Err(err) = editor.open(&path, Action::Replace) {
+        editor.set_error(format!("failed to open {}: {}", path.display(), err));
+        return;
+    }
+    // the positions of the entries are only known once the document is open
+    editor.sync_quickfix();
+
+    let entry = match editor.quickfix.current() {
+        Some(entry) => entry.clone(),
+        None => return,
+    };
+    let (view, doc) = current!(editor);
+    let pos = entry.char_pos(doc.text().slice(..));
+    doc.set_selection(view.id, Selection::point(pos));
+    align_view(doc, view, Align::Center);
+
+    let status = format!(
+        "({}/{}) {}",
+        editor.quickfix.index() + 1,
+        editor.quickfix.entries().len(),
+        entry.text.lines().next().unwrap_or_default()
+    );
+    editor.set_status(status);
+}
+
 fn goto_first_change(cx: &mut Context) {
     goto_first_change_impl(cx, false);
 }
@@ -3187,6 +3414,25 @@ fn language_server_completion(cx: &mut Context, ch: char) {
         }
     }
 
+    // Paths are completed as soon as a `/` is typed
+    fn path_completion(cx: &mut Context, ch: char) {
+        use helix_core::completion::{path_before, CompletionSource};
+
+        if ch != '/' || !cx.editor.config().auto_completion {
+            return;
+        }
+        let (view, doc) = current_ref!(cx.editor);
+        if !doc.completion_sources().contains(&CompletionSource::Path) {
+            return;
+        }
+        let text = doc.text().slice(..);
+        let cursor = doc.selection(view.id).primary().cursor(text);
+        if path_before(text, cursor).is_some() {
+            cx.editor.clear_idle_timer();
+            super::completion(cx);
+        }
+    }
+
     fn signature_help(cx: &mut Context, ch: char) {
         use helix_lsp::lsp;
         // if ch matches signature_help char, trigger
@@ -3255,7 +3501,7 @@ pub fn insert_char(cx: &mut Context, c: char) {
         // TODO: need a post insert hook too for certain triggers (autocomplete, signature help, etc)
         // this could also generically look at Transaction, but it's a bit annoying to look at
         // Operation instead of Change.
-        for hook in &[language_server_completion, signature_help] {
+        for hook in &[language_server_completion, path_completion, signature_help] {
             hook(cx, c);
         }
     }
@@ -4172,24 +4418,40 @@ fn remove_primary_selection(cx: &mut Context) {
 }
 
 pub fn completion(cx: &mut Context) {
+    use helix_core::completion::CompletionSource;
     use helix_lsp::{lsp, util::pos_to_lsp_pos};
 
-    let (view, doc) = current!(cx.editor);
-
-    let language_server = match doc.language_server() {
-        Some(language_server) => language_server,
-        None => return,
-    };
+    let sources = doc!(cx.editor).completion_sources();
+    let builtin_items = ui::builtin_completion_items(cx.editor, &sources);
 
-    let offset_encoding = language_server.offset_encoding();
+    let (view, doc) = current!(cx.editor);
     let text = doc.text().slice(..);
     let cursor = doc.selection(view.id).primary().cursor(text);
 
-    let pos = pos_to_lsp_pos(doc.text(), cursor, offset_encoding);
-
-    let future = match language_server.completion(doc.identifier(), pos, None) {
-        Some(future) => future,
-        None => return,
+    let language_server = doc
+        .language_server()
+        .filter(|_| sources.contains(&CompletionSource::LanguageServer));
+    let offset_encoding = language_server
+        .map(|language_server| language_server.offset_encoding())
+        .unwrap_or_default();
+    let future = language_server.and_then(|language_server| {
+        let pos = pos_to_lsp_pos(doc.text(), cursor, offset_encoding);
+        language_server.completion(doc.identifier(), pos, None)
+    });
+    if future.is_none() && builtin_items.is_empty() {
+        return;
+    }
+    // Without a language server request the built-in items are shown right away. A null
+    // response is only returned for canceled requests, so that their items are dropped.
+    let future = async move {
+        let response = match future {
+            Some(future) => future.await?,
+            None => serde_json::Value::Null,
+        };
+        Ok(match response {
+            serde_json::Value::Null => serde_json::Value::Array(Vec::new()),
+            response => response,
+        })
     };
 
     // setup a channel that allows the request to be canceled
@@ -4252,16 +4514,27 @@ pub fn completion(cx: &mut Context) {
                 return;
             }
 
-            let items = match response {
+            let lsp_items = match response {
                 Some(lsp::CompletionResponse::Array(items)) => items,
                 // TODO: do something with is_incomplete
                 Some(lsp::CompletionResponse::List(lsp::CompletionList {
                     is_incomplete: _is_incomplete,
                     items,
                 })) => items,
-                None => Vec::new(),
+                // the request was canceled
+                None => return,
             };
 
+            // words already offered by the language server aren't repeated
+            let labels: HashSet<_> = lsp_items.iter().map(|item| item.label.clone()).collect();
+            let items: Vec<_> = lsp_items
+                .into_iter()
+                .map(|item| ui::CompletionItem::new(item, CompletionSource::LanguageServer))
+                .chain(builtin_items.into_iter().filter(|item| {
+                    item.source != CompletionSource::Buffer || !labels.contains(&item.item.label)
+                }))
+                .collect();
+
             if items.is_empty() {
                 // editor.set_error("No completion available");
                 return;
@@ -4293,11 +4566,41 @@ pub fn completion(cx: &mut Context) {
 // comments
 fn toggle_comments(cx: &mut Context) {
     let (view, doc) = current!(cx.editor);
-    let token = doc
-        .language_config()
-        .and_then(|lc| lc.comment_token.as_ref())
-        .map(|tc| tc.as_ref());
-    let transaction = comment::toggle_line_comments(doc.text(), doc.selection(view.id), token);
+    let text = doc.text().slice(..);
+    let selection = doc.selection(view.id);
+    let config = doc.language_config_at(selection.primary().cursor(text));
+    let token = config.as_ref().and_then(|lc| lc.comment_token.as_deref());
+    let block_tokens = config
+        .as_ref()
+        .and_then(|lc| lc.block_comment_tokens.as_ref());
+
+    let transaction = match (token, block_tokens) {
+        // languages without line comments get block comments around each line
+        (None, Some(block_tokens)) => {
+            let lines = selection.clone().transform(|range| {
+                let (start, end) = range.line_range(text);
+                Range::new(text.line_to_char(start), line_end_char_index(&text, end))
+            });
+            let transaction = comment::toggle_block_comments(doc.text(), &lines, block_tokens);
+            // keep the selections instead of selecting the commented lines
+            Transaction::from(transaction.changes().clone())
+        }
+        _ => comment::toggle_line_comments(doc.text(), selection, token),
+    };
+
+    doc.apply(&transaction, view.id);
+    exit_select_mode(cx);
+}
+
+fn toggle_block_comments(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let selection = doc.selection(view.id);
+    let tokens = doc
+        .language_config_at(selection.primary().cursor(text))
+        .and_then(|lc| lc.block_comment_tokens.clone())
+        .unwrap_or_default();
+    let transaction = comment::toggle_block_comments(doc.text(), selection, &tokens);
 
     doc.apply(&transaction, view.id);
     exit_select_mode(cx);
@@ -4442,6 +4745,66 @@ fn select_prev_sibling(cx: &mut Context) {
     select_sibling_impl(cx, &|node| Node::prev_sibling(&node))
 }
 
+fn structural_edit_impl<F>(cx: &mut Context, edit_fn: F)
+where
+    F: Fn(&helix_core::Syntax, &Rope, &Selection) -> Transaction,
+{
+    let (view, doc) = current!(cx.editor);
+
+    if let Some(syntax) = doc.syntax() {
+        let transaction = edit_fn(syntax, doc.text(), doc.selection(view.id));
+        doc.apply(&transaction, view.id);
+        exit_select_mode(cx);
+    }
+}
+
+fn swap_next_sibling(cx: &mut Context) {
+    structural_edit_impl(cx, |syntax, text, selection| {
+        object::swap_sibling(syntax, text, selection, Direction::Forward)
+    })
+}
+
+fn swap_prev_sibling(cx: &mut Context) {
+    structural_edit_impl(cx, |syntax, text, selection| {
+        object::swap_sibling(syntax, text, selection, Direction::Backward)
+    })
+}
+
+fn raise_node(cx: &mut Context) {
+    structural_edit_impl(cx, object::raise_node)
+}
+
+fn splice_node(cx: &mut Context) {
+    structural_edit_impl(cx, object::splice_node)
+}
+
+fn wrap_selections(cx: &mut Context) {
+    ui::prompt(
+        cx,
+        "wrap:".into(),
+        None,
+        ui::completers::none,
+        move |cx, input: &str, event: PromptEvent| {
+            if event != PromptEvent::Validate {
+                return;
+            }
+            // `$0` marks where the selected text goes in the template.
+            let (prefix, suffix) = match input.split_once("$0") {
+                Some(parts) => parts,
+                None => {
+                    cx.editor.set_error("Template must contain `$0`");
+                    return;
+                }
+            };
+
+            let (view, doc) = current!(cx.editor);
+            let selection = doc.selection(view.id);
+            let transaction = object::wrap_selections(doc.text(), selection, prefix, suffix);
+            doc.apply(&transaction, view.id);
+        },
+    );
+}
+
 fn match_brackets(cx: &mut Context) {
     let (view, doc) = current!(cx.editor);
 
@@ -4598,14 +4961,15 @@ fn wclose(cx: &mut Context) {
 }
 
 fn wonly(cx: &mut Context) {
+    let focus = cx.editor.tree.focus;
     let views = cx
         .editor
         .tree
-        .views()
-        .map(|(v, focus)| (v.id, focus))
+        .traverse()
+        .map(|(view_id, _)| view_id)
         .collect::<Vec<_>>();
-    for (view_id, focus) in views {
-        if !focus {
+    for view_id in views {
+        if view_id != focus {
             cx.editor.close(view_id);
         }
     }
@@ -4814,11 +5178,33 @@ fn select_textobject(cx: &mut Context, objtype: textobject::TextObject) {
                     match ch {
                         'w' => textobject::textobject_word(text, range, objtype, count, false),
                         'W' => textobject::textobject_word(text, range, objtype, count, true),
-                        't' => textobject_treesitter("class", range),
+                        't' => match textobject_treesitter("class", range) {
+                            // fall back to tags for markup languages without type definitions
+                            new_range if new_range == range => {
+                                textobject::textobject_tag(text, range, objtype, count)
+                            }
+                            new_range => new_range,
+                        },
                         'f' => textobject_treesitter("function", range),
-                        'a' => textobject_treesitter("parameter", range),
+                        'a' => match textobject_treesitter("parameter", range) {
+                            new_range if new_range == range => {
+                                textobject::textobject_argument(text, range, objtype)
+                            }
+                            new_range => new_range,
+                        },
                         'c' => textobject_treesitter("comment", range),
                         'T' => textobject_treesitter("test", range),
+                        'e' => textobject_treesitter("entry", range),
+                        'x' => textobject::textobject_tag(text, range, objtype, count),
+                        'i' => textobject::textobject_indentation_level(
+                            text,
+                            range,
+                            objtype,
+                            count,
+                            doc.tab_width(),
+                            doc.indent_width(),
+                        ),
+                        'n' => textobject::textobject_number(text, range, objtype),
                         'p' => textobject::textobject_paragraph(text, range, objtype, count),
                         'm' => textobject::textobject_pair_surround_closest(
                             text, range, objtype, count,
@@ -4847,11 +5233,15 @@ fn select_textobject(cx: &mut Context, objtype: textobject::TextObject) {
         ("w", "Word"),
         ("W", "WORD"),
         ("p", "Paragraph"),
-        ("t", "Type definition (tree-sitter)"),
+        ("i", "Indentation level"),
+        ("n", "Number"),
+        ("x", "(X)HTML/XML tag"),
+        ("t", "Type definition (tree-sitter) or tag"),
         ("f", "Function (tree-sitter)"),
-        ("a", "Argument/parameter (tree-sitter)"),
+        ("a", "Argument/parameter"),
         ("c", "Comment (tree-sitter)"),
         ("T", "Test (tree-sitter)"),
+        ("e", "Entry/key-value pair (tree-sitter)"),
         ("m", "Closest surrounding pair"),
         (" ", "... or any character acting as a pair"),
     ];
@@ -4859,64 +5249,144 @@ fn select_textobject(cx: &mut Context, objtype: textobject::TextObject) {
     cx.editor.autoinfo = Some(Info::new(title, &help_text));
 }
 
+/// Returns the open and close text for a surround key, preferring multi-character
+/// pairs configured for the document's language.
+fn surround_pair(doc: &Document, ch: char) -> (Tendril, Tendril) {
+    match doc
+        .language_config()
+        .and_then(|config| config.surround_pairs.get(&ch))
+    {
+        Some((open, close)) => (open.as_str().into(), close.as_str().into()),
+        None => {
+            let (open, close) = surround::get_pair(ch);
+            (open.to_string().into(), close.to_string().into())
+        }
+    }
+}
+
+/// Splits tag input such as `div class="a"` into the tag contents and the tag name.
+fn surround_tag(input: &str) -> Option<(&str, &str)> {
+    let input = input.trim();
+    let name = input.split_whitespace().next()?;
+    Some((input, name))
+}
+
 fn surround_add(cx: &mut Context) {
     cx.on_next_key(move |cx, event| {
-        let (view, doc) = current!(cx.editor);
-        // surround_len is the number of new characters being added.
-        let (open, close, surround_len) = match event.char() {
-            Some(ch) => {
-                let (o, c) = surround::get_pair(ch);
-                let mut open = Tendril::new();
-                open.push(o);
-                let mut close = Tendril::new();
-                close.push(c);
-                (open, close, 2)
+        let doc = doc!(cx.editor);
+        let (open, close) = match event.char() {
+            Some('t') => {
+                ui::prompt(
+                    cx,
+                    "tag:".into(),
+                    None,
+                    ui::completers::none,
+                    move |cx, input: &str, event: PromptEvent| {
+                        if event != PromptEvent::Validate {
+                            return;
+                        }
+                        if let Some((tag, name)) = surround_tag(input) {
+                            let open = format!("<{}>", tag).into();
+                            let close = format!("</{}>", name).into();
+                            surround_add_impl(cx.editor, open, close);
+                        }
+                    },
+                );
+                return;
             }
+            Some(ch) => surround_pair(doc, ch),
             None if event.code == KeyCode::Enter => (
                 doc.line_ending.as_str().into(),
                 doc.line_ending.as_str().into(),
-                2 * doc.line_ending.len_chars(),
             ),
             None => return,
         };
+        surround_add_impl(cx.editor, open, close);
+    })
+}
 
-        let selection = doc.selection(view.id);
-        let mut changes = Vec::with_capacity(selection.len() * 2);
-        let mut ranges = SmallVec::with_capacity(selection.len());
-        let mut offs = 0;
+fn surround_add_impl(editor: &mut Editor, open: Tendril, close: Tendril) {
+    let (view, doc) = current!(editor);
+    // surround_len is the number of new characters being added.
+    let surround_len = open.chars().count() + close.chars().count();
+
+    let selection = doc.selection(view.id);
+    let mut changes = Vec::with_capacity(selection.len() * 2);
+    let mut ranges = SmallVec::with_capacity(selection.len());
+    let mut offs = 0;
+
+    for range in selection.iter() {
+        changes.push((range.from(), range.from(), Some(open.clone())));
+        changes.push((range.to(), range.to(), Some(close.clone())));
 
-        for range in selection.iter() {
-            changes.push((range.from(), range.from(), Some(open.clone())));
-            changes.push((range.to(), range.to(), Some(close.clone())));
+        ranges.push(
+            Range::new(offs + range.from(), offs + range.to() + surround_len)
+                .with_direction(range.direction()),
+        );
 
-            ranges.push(
-                Range::new(offs + range.from(), offs + range.to() + surround_len)
-                    .with_direction(range.direction()),
-            );
+        offs += surround_len;
+    }
 
-            offs += surround_len;
+    let transaction = Transaction::change(doc.text(), changes.into_iter())
+        .with_selection(Selection::new(ranges, selection.primary_index()));
+    doc.apply(&transaction, view.id);
+    if editor.mode == Mode::Select {
+        editor.mode = Mode::Normal;
+    }
+}
+
+/// Positions of the surround pairs found around every selection.
+enum SurroundPos {
+    /// Char ranges of the open and close halves of each pair, in a flat Vec.
+    Pairs(Vec<(usize, usize)>),
+    Tags(Vec<surround::TagPos>),
+}
+
+impl SurroundPos {
+    fn find(
+        doc: &Document,
+        selection: &Selection,
+        ch: char,
+        count: usize,
+    ) -> Result<Self, surround::Error> {
+        let text = doc.text().slice(..);
+        let chars = |pos: Vec<usize>| Self::Pairs(pos.into_iter().map(|p| (p, p + 1)).collect());
+        let custom = doc
+            .language_config()
+            .and_then(|config| config.surround_pairs.get(&ch));
+
+        match (ch, custom) {
+            // m selects the closest surround pair
+            ('m', _) => surround::get_surround_pos(text, selection, None, count).map(chars),
+            ('t', _) => {
+                surround::get_surround_tag_pos(doc.syntax(), text, selection, count).map(Self::Tags)
+            }
+            (_, Some((open, close))) => {
+                surround::get_surround_str_pos(text, selection, open, close, count).map(Self::Pairs)
+            }
+            (ch, None) => surround::get_surround_pos(text, selection, Some(ch), count).map(chars),
         }
+    }
 
-        let transaction = Transaction::change(doc.text(), changes.into_iter())
-            .with_selection(Selection::new(ranges, selection.primary_index()));
-        doc.apply(&transaction, view.id);
-        exit_select_mode(cx);
-    })
+    fn ranges(&self) -> Vec<(usize, usize)> {
+        match self {
+            Self::Pairs(ranges) => ranges.clone(),
+            Self::Tags(tags) => tags.iter().flat_map(|tag| [tag.open, tag.close]).collect(),
+        }
+    }
 }
 
 fn surround_replace(cx: &mut Context) {
     let count = cx.count();
     cx.on_next_key(move |cx, event| {
         let surround_ch = match event.char() {
-            Some('m') => None, // m selects the closest surround pair
-            Some(ch) => Some(ch),
+            Some(ch) => ch,
             None => return,
         };
         let (view, doc) = current!(cx.editor);
-        let text = doc.text().slice(..);
         let selection = doc.selection(view.id);
 
-        let change_pos = match surround::get_surround_pos(text, selection, surround_ch, count) {
+        let change_pos = match SurroundPos::find(doc, selection, surround_ch, count) {
             Ok(c) => c,
             Err(err) => {
                 cx.editor.set_error(err.to_string());
@@ -4925,39 +5395,86 @@ fn surround_replace(cx: &mut Context) {
         };
 
         cx.on_next_key(move |cx, event| {
-            let (view, doc) = current!(cx.editor);
-            let to = match event.char() {
-                Some(to) => to,
+            let (open, close) = match event.char() {
+                Some('t') => {
+                    ui::prompt(
+                        cx,
+                        "tag:".into(),
+                        None,
+                        ui::completers::none,
+                        move |cx, input: &str, event: PromptEvent| {
+                            if event != PromptEvent::Validate {
+                                return;
+                            }
+                            let (tag, name) = match surround_tag(input) {
+                                Some(tag) => tag,
+                                None => return,
+                            };
+                            let changes = match &change_pos {
+                                // only rename the tags so that attributes are kept
+                                SurroundPos::Tags(tags) => tags
+                                    .iter()
+                                    .flat_map(|pos| [(pos.open_name, tag), (pos.close_name, name)])
+                                    .map(|(range, text)| (range, text.into()))
+                                    .collect(),
+                                SurroundPos::Pairs(_) => {
+                                    let open = format!("<{}>", tag);
+                                    let close = format!("</{}>", name);
+                                    surround_replace_impl(&change_pos, &open, &close)
+                                }
+                            };
+                            surround_apply(cx.editor, changes);
+                        },
+                    );
+                    return;
+                }
+                Some(to) => surround_pair(doc!(cx.editor), to),
                 None => return,
             };
-            let (open, close) = surround::get_pair(to);
-            let transaction = Transaction::change(
-                doc.text(),
-                change_pos.iter().enumerate().map(|(i, &pos)| {
-                    let mut t = Tendril::new();
-                    t.push(if i % 2 == 0 { open } else { close });
-                    (pos, pos + 1, Some(t))
-                }),
-            );
-            doc.apply(&transaction, view.id);
-            exit_select_mode(cx);
+            let changes = surround_replace_impl(&change_pos, &open, &close);
+            surround_apply(cx.editor, changes);
         });
     })
 }
 
+fn surround_replace_impl(
+    change_pos: &SurroundPos,
+    open: &str,
+    close: &str,
+) -> Vec<((usize, usize), Tendril)> {
+    change_pos
+        .ranges()
+        .into_iter()
+        .enumerate()
+        .map(|(i, range)| (range, if i % 2 == 0 { open } else { close }.into()))
+        .collect()
+}
+
+fn surround_apply(editor: &mut Editor, changes: Vec<((usize, usize), Tendril)>) {
+    let (view, doc) = current!(editor);
+    let transaction = Transaction::change(
+        doc.text(),
+        changes
+            .into_iter()
+            .map(|((from, to), text)| (from, to, Some(text))),
+    );
+    doc.apply(&transaction, view.id);
+    if editor.mode == Mode::Select {
+        editor.mode = Mode::Normal;
+    }
+}
+
 fn surround_delete(cx: &mut Context) {
     let count = cx.count();
     cx.on_next_key(move |cx, event| {
         let surround_ch = match event.char() {
-            Some('m') => None, // m selects the closest surround pair
-            Some(ch) => Some(ch),
+            Some(ch) => ch,
             None => return,
         };
         let (view, doc) = current!(cx.editor);
-        let text = doc.text().slice(..);
         let selection = doc.selection(view.id);
 
-        let change_pos = match surround::get_surround_pos(text, selection, surround_ch, count) {
+        let change_pos = match SurroundPos::find(doc, selection, surround_ch, count) {
             Ok(c) => c,
             Err(err) => {
                 cx.editor.set_error(err.to_string());
@@ -4965,8 +5482,13 @@ fn surround_delete(cx: &mut Context) {
             }
         };
 
-        let transaction =
-            Transaction::change(doc.text(), change_pos.into_iter().map(|p| (p, p + 1, None)));
+        let transaction = Transaction::change(
+            doc.text(),
+            change_pos
+                .ranges()
+                .into_iter()
+                .map(|(from, to)| (from, to, None)),
+        );
         doc.apply(&transaction, view.id);
         exit_select_mode(cx);
     })
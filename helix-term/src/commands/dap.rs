@@ -5,22 +5,32 @@
     ui::{self, overlay::overlaid, FilePicker, Picker, Popup, Prompt, PromptEvent, Text},
 };
 use dap::{StackFrame, Thread, ThreadStates};
-use helix_core::syntax::{DebugArgumentValue, DebugConfigCompletion, DebugTemplate};
-use helix_dap::{self as dap, Client};
+use helix_core::syntax::{
+    DebugAdapterConfig, DebugArgumentValue, DebugConfigCompletion, DebugTemplate,
+};
+use helix_dap::{self as dap, launch, launch::LaunchConfiguration};
 use helix_lsp::block_on;
-use helix_view::editor::Breakpoint;
+use helix_view::{
+    base64,
+    debug_console::REPL_CATEGORY,
+    disassembly::{hex_dump, parse_address, MEMORY_VIEW_LEN},
+    editor::{Action, Breakpoint, DataBreakpoint},
+};
 
 use serde_json::{to_value, Value};
-use tokio_stream::wrappers::UnboundedReceiverStream;
 use tui::{text::Spans, widgets::Row};
 
 use std::collections::HashMap;
 use std::future::Future;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 use anyhow::{anyhow, bail};
 
-use helix_view::handlers::dap::{breakpoints_changed, jump_to_stack_frame, select_thread_id};
+use helix_view::handlers::dap::{
+    breakpoints_changed, data_breakpoints_changed, exception_filters_changed,
+    function_breakpoints_changed, jump_to_stack_frame, refresh_debug_panels, select_thread_id,
+};
 
 impl ui::menu::Item for StackFrame {
     type Data = ();
@@ -30,14 +40,6 @@ fn format(&self, _data: &Self::Data) -> Row {
     }
 }
 
-impl ui::menu::Item for DebugTemplate {
-    type Data = ();
-
-    fn format(&self, _data: &Self::Data) -> Row {
-        self.name.as_str().into()
-    }
-}
-
 impl ui::menu::Item for Thread {
     type Data = ThreadStates;
 
@@ -78,7 +80,7 @@ fn thread_picker(
                 thread_states,
                 move |cx, thread, _action| callback_fn(cx.editor, thread),
                 move |editor, thread| {
-                    let frames = editor.debugger.as_ref()?.stack_frames.get(&thread.id)?;
+                    let frames = editor.debuggers.active()?.stack_frames.get(&thread.id)?;
                     let frame = frames.get(0)?;
                     let path = frame.source.as_ref()?.path.clone()?;
                     let pos = Some((
@@ -142,29 +144,6 @@ pub fn dap_start_impl(
         .and_then(|config| config.debugger.as_ref())
         .ok_or_else(|| anyhow!("No debug adapter available for language"))?;
 
-    let result = match socket {
-        Some(socket) => block_on(Client::tcp(socket, 0)),
-        None => block_on(Client::process(
-            &config.transport,
-            &config.command,
-            config.args.iter().map(|arg| arg.as_str()).collect(),
-            config.port_arg.as_deref(),
-            0,
-        )),
-    };
-
-    let (mut debugger, events) = match result {
-        Ok(r) => r,
-        Err(e) => bail!("Failed to start debug session: {}", e),
-    };
-
-    let request = debugger.initialize(config.name.clone());
-    if let Err(e) = block_on(request) {
-        bail!("Failed to initialize debug adapter: {}", e);
-    }
-
-    debugger.quirks = config.quirks.clone();
-
     // TODO: avoid refetching all of this... pass a config in
     let template = match name {
         Some(name) => config.templates.iter().find(|t| t.name == name),
@@ -223,74 +202,163 @@ pub fn dap_start_impl(
 
     let args = to_value(args).unwrap();
 
+    let config = config.clone();
+    let (name, request) = (template.name.clone(), template.request.clone());
+    start_session(cx, &config, socket, &name, &request, args)
+}
+
+/// Starts the debug session of a configuration of the workspace launch files.
+pub fn dap_start_configuration(
+    cx: &mut compositor::Context,
+    configuration: &LaunchConfiguration,
+) -> Result<(), anyhow::Error> {
+    let adapter = configuration.adapter();
+    let config = cx
+        .editor
+        .syn_loader
+        .language_configs()
+        .filter_map(|config| config.debugger.as_ref())
+        .find(|debugger| debugger.name == adapter)
+        .cloned()
+        .ok_or_else(|| anyhow!("Unknown debug adapter type '{}'", configuration.ty))?;
+
+    let (view, doc) = current_ref!(cx.editor);
+    let text = doc.text().slice(..);
+    let selection = doc.selection(view.id).primary();
+    let variables = launch::variables(
+        &helix_loader::find_workspace().0,
+        doc.path().map(|path| path.as_path()),
+        selection.cursor_line(text),
+        &selection.fragment(text),
+    );
+
+    let mut args = Value::Object(configuration.args.clone());
+    launch::substitute(&mut args, &variables);
+    if let Value::Object(map) = &mut args {
+        if !map.contains_key("cwd") {
+            map.insert("cwd".into(), to_value(std::env::current_dir()?)?);
+        }
+    }
+
+    start_session(
+        cx,
+        &config,
+        None,
+        &configuration.name,
+        &configuration.request,
+        args,
+    )
+}
+
+/// Starts a debug session with the debug adapter, either spawning it or connecting to `socket`,
+/// and sends its `launch` or `attach` request.
+fn start_session(
+    cx: &mut compositor::Context,
+    config: &DebugAdapterConfig,
+    socket: Option<std::net::SocketAddr>,
+    name: &str,
+    request: &str,
+    args: Value,
+) -> Result<(), anyhow::Error> {
+    if !matches!(request, "launch" | "attach") {
+        bail!("Unsupported request '{}'", request);
+    }
+    let id = match block_on(cx.editor.debuggers.start_client(socket, config)) {
+        Ok(id) => id,
+        Err(e) => bail!("Failed to start debug session: {}", e),
+    };
+    let debugger = cx.editor.debuggers.get_mut(id).unwrap();
+    debugger.name = name.to_owned();
+
     let callback = |_editor: &mut Editor, _compositor: &mut Compositor, _response: Value| {
         // if let Err(e) = result {
         //     editor.set_error(format!("Failed {} target: {}", template.request, e));
         // }
     };
 
-    match &template.request[..] {
-        "launch" => {
-            let call = debugger.launch(args);
-            dap_callback(cx.jobs, call, callback);
-        }
-        "attach" => {
-            let call = debugger.attach(args);
-            dap_callback(cx.jobs, call, callback);
-        }
-        request => bail!("Unsupported request '{}'", request),
-    };
+    if request == "launch" {
+        dap_callback(cx.jobs, debugger.launch(args), callback);
+    } else {
+        dap_callback(cx.jobs, debugger.attach(args), callback);
+    }
 
     // TODO: either await "initialized" or buffer commands until event is received
-    cx.editor.debugger = Some(debugger);
-    let stream = UnboundedReceiverStream::new(events);
-    cx.editor.debugger_events.push(stream);
     Ok(())
 }
 
-pub fn dap_launch(cx: &mut Context) {
-    if cx.editor.debugger.is_some() {
-        cx.editor.set_error("Debugger is already running");
-        return;
-    }
+/// A debug configuration of the workspace launch files or a template of the language's adapter.
+enum LaunchTarget {
+    Configuration(LaunchConfiguration),
+    Template(DebugTemplate),
+}
 
-    let doc = doc!(cx.editor);
+impl ui::menu::Item for LaunchTarget {
+    type Data = ();
 
-    let config = match doc
-        .language_config()
-        .and_then(|config| config.debugger.as_ref())
-    {
-        Some(c) => c,
-        None => {
-            cx.editor
-                .set_error("No debug adapter available for language");
-            return;
+    fn format(&self, _data: &Self::Data) -> Row {
+        match self {
+            Self::Configuration(configuration) => {
+                Row::new(vec![configuration.name.as_str(), configuration.ty.as_str()])
+            }
+            Self::Template(template) => Row::new(vec![template.name.as_str(), "template"]),
+        }
+    }
+}
+
+pub fn dap_launch(cx: &mut Context) {
+    let configurations = match launch::load(&helix_loader::find_workspace().0) {
+        Ok(configurations) => configurations,
+        Err(err) => {
+            cx.editor.set_error(format!("{:#}", err));
+            Vec::new()
         }
     };
+    let templates = doc!(cx.editor)
+        .language_config()
+        .and_then(|config| config.debugger.as_ref())
+        .map(|config| config.templates.clone())
+        .unwrap_or_default();
+
+    if configurations.is_empty() && templates.is_empty() {
+        cx.editor
+            .set_error("No debug adapter available for language");
+        return;
+    }
 
-    let templates = config.templates.clone();
+    let targets = configurations
+        .into_iter()
+        .map(LaunchTarget::Configuration)
+        .chain(templates.into_iter().map(LaunchTarget::Template))
+        .collect();
 
     cx.push_layer(Box::new(overlaid(Picker::new(
-        templates,
+        targets,
         (),
-        |cx, template, _action| {
-            let completions = template.completion.clone();
-            let name = template.name.clone();
-            let callback = Box::pin(async move {
-                let call: Callback =
-                    Callback::EditorCompositor(Box::new(move |_editor, compositor| {
-                        let prompt = debug_parameter_prompt(completions, name, Vec::new());
-                        compositor.push(Box::new(prompt));
-                    }));
-                Ok(call)
-            });
-            cx.jobs.callback(callback);
+        |cx, target, _action| match target {
+            LaunchTarget::Configuration(configuration) => {
+                if let Err(err) = dap_start_configuration(cx, configuration) {
+                    cx.editor.set_error(err.to_string());
+                }
+            }
+            LaunchTarget::Template(template) => {
+                let completions = template.completion.clone();
+                let name = template.name.clone();
+                let callback = Box::pin(async move {
+                    let call: Callback =
+                        Callback::EditorCompositor(Box::new(move |_editor, compositor| {
+                            let prompt = debug_parameter_prompt(completions, name, Vec::new());
+                            compositor.push(Box::new(prompt));
+                        }));
+                    Ok(call)
+                });
+                cx.jobs.callback(callback);
+            }
         },
     ))));
 }
 
 pub fn dap_restart(cx: &mut Context) {
-    let debugger = match &cx.editor.debugger {
+    let debugger = match cx.editor.debuggers.active() {
         Some(debugger) => debugger,
         None => {
             cx.editor.set_error("Debugger is not running");
@@ -402,9 +470,6 @@ pub fn dap_toggle_breakpoint(cx: &mut Context) {
 }
 
 pub fn dap_toggle_breakpoint_impl(cx: &mut Context, path: PathBuf, line: usize) {
-    // TODO: need to map breakpoints over edits and update them?
-    // we shouldn't really allow editing while debug is running though
-
     let breakpoints = cx.editor.breakpoints.entry(path.clone()).or_default();
     // TODO: always keep breakpoints sorted and use binary search to determine insertion point
     if let Some(pos) = breakpoints
@@ -419,9 +484,7 @@ pub fn dap_toggle_breakpoint_impl(cx: &mut Context, path: PathBuf, line: usize)
         });
     }
 
-    let debugger = debugger!(cx.editor);
-
-    if let Err(e) = breakpoints_changed(debugger, path, breakpoints) {
+    if let Err(e) = breakpoints_changed(&mut cx.editor.debuggers, path, breakpoints) {
         cx.editor
             .set_error(format!("Failed to set breakpoints: {}", e));
     }
@@ -437,7 +500,7 @@ pub fn dap_continue(cx: &mut Context) {
             cx.jobs,
             request,
             |editor, _compositor, _response: dap::requests::ContinueResponse| {
-                debugger!(editor).resume_application();
+                editor.resume_debuggee();
             },
         );
     } else {
@@ -461,10 +524,10 @@ pub fn dap_step_in(cx: &mut Context) {
     let debugger = debugger!(cx.editor);
 
     if let Some(thread_id) = debugger.thread_id {
-        let request = debugger.step_in(thread_id);
+        let request = debugger.step_in(thread_id, None);
 
         dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
-            debugger!(editor).resume_application();
+            editor.resume_debuggee();
         });
     } else {
         cx.editor
@@ -476,9 +539,9 @@ pub fn dap_step_out(cx: &mut Context) {
     let debugger = debugger!(cx.editor);
 
     if let Some(thread_id) = debugger.thread_id {
-        let request = debugger.step_out(thread_id);
+        let request = debugger.step_out(thread_id, None);
         dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
-            debugger!(editor).resume_application();
+            editor.resume_debuggee();
         });
     } else {
         cx.editor
@@ -490,9 +553,9 @@ pub fn dap_next(cx: &mut Context) {
     let debugger = debugger!(cx.editor);
 
     if let Some(thread_id) = debugger.thread_id {
-        let request = debugger.next(thread_id);
+        let request = debugger.next(thread_id, None);
         dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
-            debugger!(editor).resume_application();
+            editor.resume_debuggee();
         });
     } else {
         cx.editor
@@ -500,6 +563,174 @@ pub fn dap_next(cx: &mut Context) {
     }
 }
 
+pub fn dap_next_instruction(cx: &mut Context) {
+    step_instruction(cx, |debugger, thread_id| {
+        debugger.next(thread_id, Some(dap::SteppingGranularity::Instruction))
+    });
+}
+
+pub fn dap_step_in_instruction(cx: &mut Context) {
+    step_instruction(cx, |debugger, thread_id| {
+        debugger.step_in(thread_id, Some(dap::SteppingGranularity::Instruction))
+    });
+}
+
+fn step_instruction<F>(cx: &mut Context, step: impl FnOnce(&dap::Client, dap::ThreadId) -> F)
+where
+    F: Future<Output = helix_dap::Result<Value>> + 'static + Send,
+{
+    let debugger = debugger!(cx.editor);
+
+    if debugger.capabilities().supports_stepping_granularity != Some(true) {
+        cx.editor
+            .set_error("Debugger does not support stepping by instruction");
+        return;
+    }
+    if let Some(thread_id) = debugger.thread_id {
+        let request = step(debugger, thread_id);
+        dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
+            editor.resume_debuggee();
+        });
+    } else {
+        cx.editor
+            .set_error("Currently active thread is not stopped. Switch the thread.");
+    }
+}
+
+pub fn dap_step_back(cx: &mut Context) {
+    let debugger = debugger!(cx.editor);
+
+    if debugger.capabilities().supports_step_back != Some(true) {
+        cx.editor
+            .set_error("Debugger does not support stepping back");
+        return;
+    }
+    if let Some(thread_id) = debugger.thread_id {
+        let request = debugger.step_back(thread_id, None);
+        dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
+            editor.resume_debuggee();
+        });
+    } else {
+        cx.editor
+            .set_error("Currently active thread is not stopped. Switch the thread.");
+    }
+}
+
+pub fn dap_reverse_continue(cx: &mut Context) {
+    let debugger = debugger!(cx.editor);
+
+    if debugger.capabilities().supports_step_back != Some(true) {
+        cx.editor
+            .set_error("Debugger does not support reverse execution");
+        return;
+    }
+    if let Some(thread_id) = debugger.thread_id {
+        let request = debugger.reverse_continue(thread_id);
+        dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
+            editor.resume_debuggee();
+        });
+    } else {
+        cx.editor
+            .set_error("Currently active thread is not stopped. Switch the thread.");
+    }
+}
+
+/// Continues until the line of the cursor, with a temporary breakpoint there unless there is a
+/// breakpoint already.
+pub fn dap_run_to_cursor(cx: &mut Context) {
+    // the temporary breakpoint would outlive a run which can't be continued
+    let debugger = debugger!(cx.editor);
+    if debugger.thread_id.is_none() {
+        cx.editor
+            .set_error("Currently active thread is not stopped. Switch the thread.");
+        return;
+    }
+
+    let (view, doc) = current!(cx.editor);
+    let path = match doc.path() {
+        Some(path) => path.clone(),
+        None => {
+            cx.editor
+                .set_error("Can't run to the cursor: document has no path");
+            return;
+        }
+    };
+    let line = doc
+        .selection(view.id)
+        .primary()
+        .cursor_line(doc.text().slice(..));
+
+    let breakpoints = cx.editor.breakpoints.entry(path.clone()).or_default();
+    if !breakpoints
+        .iter()
+        .any(|breakpoint| breakpoint.line == line && breakpoint.enabled)
+    {
+        breakpoints.push(Breakpoint {
+            line,
+            temporary: true,
+            ..Default::default()
+        });
+        if let Err(e) = breakpoints_changed(&mut cx.editor.debuggers, path, breakpoints) {
+            cx.editor
+                .set_error(format!("Failed to set breakpoints: {}", e));
+            return;
+        }
+    }
+
+    dap_continue(cx);
+}
+
+/// Moves the execution of the current thread to the line of the cursor, without running the
+/// code in between.
+pub fn dap_goto_cursor(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let path = match doc.path() {
+        Some(path) => path.clone(),
+        None => return,
+    };
+    let line = doc
+        .selection(view.id)
+        .primary()
+        .cursor_line(doc.text().slice(..));
+
+    let debugger = debugger!(cx.editor);
+    if debugger.capabilities().supports_goto_targets_request != Some(true) {
+        cx.editor
+            .set_error("Debugger does not support jumping to a line");
+        return;
+    }
+    let thread_id = match debugger.thread_id {
+        Some(thread_id) => thread_id,
+        None => {
+            cx.editor
+                .set_error("Currently active thread is not stopped. Switch the thread.");
+            return;
+        }
+    };
+
+    // convert from 0-indexing to 1-indexing
+    let target = match block_on(debugger.goto_targets(path, line + 1)) {
+        Ok(targets) => targets.into_iter().next(),
+        Err(e) => {
+            cx.editor
+                .set_error(format!("Failed to get goto targets: {}", e));
+            return;
+        }
+    };
+    let target = match target {
+        Some(target) => target,
+        None => {
+            cx.editor.set_error("Can't jump to this line");
+            return;
+        }
+    };
+
+    let request = debugger.goto(thread_id, target.id);
+    dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
+        editor.resume_debuggee();
+    });
+}
+
 pub fn dap_variables(cx: &mut Context) {
     let debugger = debugger!(cx.editor);
 
@@ -583,49 +814,454 @@ pub fn dap_variables(cx: &mut Context) {
     cx.push_layer(Box::new(popup));
 }
 
-pub fn dap_terminate(cx: &mut Context) {
-    let debugger = debugger!(cx.editor);
+pub fn dap_panels(cx: &mut Context) {
+    let panels = &cx.editor.debug_panels;
+    let show = !(panels.visible && panels.focused);
+    show_debug_panels(cx.editor, show);
+}
 
-    let request = debugger.disconnect(None);
-    dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
-        // editor.set_error(format!("Failed to disconnect: {}", e));
-        editor.debugger = None;
-    });
+/// Shows and focuses the debugger panels, or hides them.
+pub fn show_debug_panels(editor: &mut Editor, show: bool) {
+    let panels = &mut editor.debug_panels;
+    let refresh = show && !panels.visible;
+    panels.visible = show;
+    panels.focused = show;
+    if refresh {
+        block_on(refresh_debug_panels(editor));
+    }
 }
 
-pub fn dap_enable_exceptions(cx: &mut Context) {
+pub fn dap_repl(cx: &mut Context) {
+    open_debug_console(cx.editor);
+    cx.push_layer(Box::new(repl_prompt()));
+}
+
+/// Opens the debug console buffer in a split if it isn't visible yet, keeping the focus on the
+/// current view.
+pub fn open_debug_console(editor: &mut Editor) {
+    let doc_id = match editor.debug_console_doc() {
+        Some(doc_id) => doc_id,
+        None => {
+            let doc_id = editor.new_file(Action::Load);
+            editor.debug_console.doc = Some(doc_id);
+            doc_id
+        }
+    };
+
+    if !editor.tree.views().any(|(view, _)| view.doc == doc_id) {
+        let focus = editor.tree.focus;
+        editor.switch(doc_id, Action::HorizontalSplit);
+        editor.focus(focus);
+    }
+    editor.rebuild_debug_console();
+}
+
+pub fn dap_disassemble(cx: &mut Context) {
     let debugger = debugger!(cx.editor);
+    if debugger.capabilities().supports_disassemble_request != Some(true) {
+        cx.editor
+            .set_error("Debugger does not support disassembling");
+        return;
+    }
 
-    let filters = match &debugger.capabilities().exception_breakpoint_filters {
-        Some(filters) => filters.iter().map(|f| f.filter.clone()).collect(),
+    let doc_id = match cx.editor.disassembly_doc() {
+        Some(doc_id) => doc_id,
+        None => {
+            let doc_id = cx.editor.new_file(Action::Load);
+            cx.editor.disassembly.doc = Some(doc_id);
+            doc_id
+        }
+    };
+    if !cx.editor.tree.views().any(|(view, _)| view.doc == doc_id) {
+        let focus = cx.editor.tree.focus;
+        cx.editor.switch(doc_id, Action::VerticalSplit);
+        cx.editor.focus(focus);
+    }
+    block_on(cx.editor.refresh_disassembly());
+}
+
+/// Opens a hex dump of the debuggee's memory at `memory_reference` in a new split.
+pub fn open_memory_view(editor: &mut Editor, memory_reference: String) {
+    let debugger = match editor.debuggers.active() {
+        Some(debugger) => debugger,
         None => return,
     };
+    if debugger.capabilities().supports_read_memory_request != Some(true) {
+        editor.set_error("Debugger does not support reading memory");
+        return;
+    }
+
+    let response = match block_on(debugger.read_memory(memory_reference, 0, MEMORY_VIEW_LEN)) {
+        Ok(response) => response,
+        Err(err) => {
+            editor.set_error(format!("Failed to read memory: {}", err));
+            return;
+        }
+    };
+    let bytes = match response.data.as_deref().map(base64::decode) {
+        Some(Some(bytes)) => bytes,
+        Some(None) => {
+            editor.set_error("Debugger sent invalid memory data");
+            return;
+        }
+        None => Vec::new(),
+    };
+    let address = match parse_address(&response.address) {
+        Some(address) => address,
+        None => {
+            editor.set_error(format!("Invalid memory address {}", response.address));
+            return;
+        }
+    };
+
+    let text = hex_dump(address, &bytes, response.unreadable_bytes.unwrap_or(0));
+    let doc_id = editor.new_file(Action::HorizontalSplit);
+    let len = doc!(editor, &doc_id).text().len_chars();
+    editor.write_output_buffer(doc_id, (0, len), &text);
+}
+
+/// Completions of the debug console input, by input, fetched from the debugger in the
+/// background so that typing doesn't wait for it.
+type ReplCompletions = Arc<Mutex<HashMap<String, Vec<ui::completers::Completion>>>>;
+
+fn repl_prompt() -> Prompt {
+    let completions = ReplCompletions::default();
+    Prompt::new(
+        "debug> ".into(),
+        Some('>'),
+        {
+            let completions = completions.clone();
+            move |_editor: &Editor, input: &str| {
+                let completions = completions.lock().unwrap();
+                completions.get(input).cloned().unwrap_or_default()
+            }
+        },
+        move |cx, input: &str, event: PromptEvent| match event {
+            PromptEvent::Update => fetch_repl_completions(cx, input, completions.clone()),
+            PromptEvent::Validate if !input.is_empty() => repl_eval(cx, input),
+            _ => (),
+        },
+    )
+}
+
+/// Requests the completions of the debug console input with the `completions` request if the
+/// debugger supports it, and shows them if the input didn't change in the meantime.
+fn fetch_repl_completions(cx: &mut compositor::Context, input: &str, completions: ReplCompletions) {
+    if completions.lock().unwrap().contains_key(input) {
+        return;
+    }
+    let debugger = match cx.editor.debuggers.active() {
+        Some(debugger) if debugger.capabilities().supports_completions_request == Some(true) => {
+            debugger
+        }
+        _ => return,
+    };
+    let frame_id = debugger.current_stack_frame().map(|frame| frame.id);
+    let column = input.encode_utf16().count() + 1;
+    let request = debugger.completions(input.to_owned(), column, frame_id);
+
+    let input = input.to_owned();
+    cx.jobs.callback(async move {
+        let targets = match request.await.and_then(|json| {
+            serde_json::from_value::<dap::requests::CompletionsResponse>(json).map_err(Into::into)
+        }) {
+            Ok(response) => response.targets,
+            Err(err) => {
+                log::warn!("Failed to complete debug console input: {}", err);
+                Vec::new()
+            }
+        };
+        let call = move |editor: &mut Editor, compositor: &mut Compositor| {
+            let items = repl_completion_items(&input, targets);
+            completions.lock().unwrap().insert(input.clone(), items);
+            if let Some(prompt) = compositor.find::<Prompt>() {
+                if prompt.line() == &input {
+                    prompt.recalculate_completion(editor);
+                }
+            }
+        };
+        Ok(Callback::EditorCompositor(Box::new(call)))
+    });
+}
+
+fn repl_completion_items(
+    input: &str,
+    targets: Vec<dap::CompletionItem>,
+) -> Vec<ui::completers::Completion> {
+    // without a start, the item replaces the word before the cursor
+    let word_start = input
+        .char_indices()
+        .rev()
+        .take_while(|(_, ch)| ch.is_alphanumeric() || *ch == '_')
+        .last()
+        .map_or(input.len(), |(i, _)| i);
+
+    targets
+        .into_iter()
+        .map(|item| {
+            let start = match item.start {
+                // columns start at 1
+                Some(start) => utf16_to_byte_offset(input, start.saturating_sub(1)),
+                None => word_start,
+            };
+            let text = item.text.unwrap_or(item.label);
+            (start.., text.into())
+        })
+        .collect()
+}
+
+fn utf16_to_byte_offset(text: &str, offset: usize) -> usize {
+    let mut units = 0;
+    for (i, ch) in text.char_indices() {
+        if units >= offset {
+            return i;
+        }
+        units += ch.len_utf16();
+    }
+    text.len()
+}
+
+/// Evaluates an expression in the REPL context of the debugger, writing it and its result to
+/// the debug console.
+pub fn repl_eval(cx: &mut compositor::Context, expression: &str) {
+    cx.editor.debug_console_output(
+        Some(REPL_CATEGORY.to_owned()),
+        format!("> {}\n", expression),
+    );
+
+    let debugger = match cx.editor.debuggers.active_mut() {
+        Some(debugger) => debugger,
+        None => {
+            cx.editor.debug_console_output(
+                Some(REPL_CATEGORY.to_owned()),
+                "No debugger is running\n".to_owned(),
+            );
+            return;
+        }
+    };
+    let frame_id = debugger.current_stack_frame().map(|frame| frame.id);
+    let request = debugger.repl(expression.to_owned(), frame_id);
+
+    let callback = Box::pin(async move {
+        let result = match request.await {
+            Ok(json) => match serde_json::from_value::<dap::requests::EvaluateResponse>(json) {
+                Ok(response) => response.result,
+                Err(err) => err.to_string(),
+            },
+            Err(err) => err.to_string(),
+        };
+        let call: Callback = Callback::Editor(Box::new(move |editor| {
+            editor.debug_console_output(Some(REPL_CATEGORY.to_owned()), format!("{}\n", result));
+        }));
+        Ok(call)
+    });
+    cx.jobs.callback(callback);
+}
 
-    let request = debugger.set_exception_breakpoints(filters);
+pub fn dap_terminate(cx: &mut Context) {
+    let debugger = debugger!(cx.editor);
 
+    let id = debugger.id();
+    let request = debugger.disconnect(None);
     dap_callback(
         cx.jobs,
         request,
-        |_editor, _compositor, _response: dap::requests::SetExceptionBreakpointsResponse| {
-            // editor.set_error(format!("Failed to set up exception breakpoints: {}", e));
+        move |editor, _compositor, _response: ()| {
+            // editor.set_error(format!("Failed to disconnect: {}", e));
+            editor.remove_debug_session(id);
         },
-    )
+    );
+}
+
+pub fn dap_enable_exceptions(cx: &mut Context) {
+    let debugger = debugger!(cx.editor);
+
+    let filters = match &debugger.capabilities().exception_breakpoint_filters {
+        Some(filters) => filters
+            .iter()
+            .map(|f| dap::ExceptionFilterOptions {
+                filter_id: f.filter.clone(),
+                condition: None,
+            })
+            .collect(),
+        None => return,
+    };
+
+    cx.editor.exception_filters = filters;
+    set_exception_filters(cx.editor);
 }
 
 pub fn dap_disable_exceptions(cx: &mut Context) {
+    cx.editor.exception_filters.clear();
+    set_exception_filters(cx.editor);
+}
+
+fn set_exception_filters(editor: &mut Editor) {
+    let debugger = debugger!(editor);
+    if let Err(e) = exception_filters_changed(debugger, &editor.exception_filters) {
+        editor.set_error(e.to_string());
+    }
+}
+
+impl ui::menu::Item for dap::ExceptionBreakpointsFilter {
+    /// The enabled filters.
+    type Data = Vec<dap::ExceptionFilterOptions>;
+
+    fn format(&self, enabled: &Self::Data) -> Row {
+        match enabled
+            .iter()
+            .find(|filter| filter.filter_id == self.filter)
+        {
+            Some(dap::ExceptionFilterOptions {
+                condition: Some(condition),
+                ..
+            }) => format!("[x] {} if {}", self.label, condition).into(),
+            Some(_) => format!("[x] {}", self.label).into(),
+            None => format!("[ ] {}", self.label).into(),
+        }
+    }
+}
+
+/// Picks an exception filter of the debugger to enable or disable. Enabling a filter which
+/// supports conditions prompts for one.
+pub fn dap_exception_filters(cx: &mut Context) {
     let debugger = debugger!(cx.editor);
 
-    let request = debugger.set_exception_breakpoints(Vec::new());
+    let filters = match &debugger.capabilities().exception_breakpoint_filters {
+        Some(filters) if !filters.is_empty() => filters.clone(),
+        _ => {
+            cx.editor.set_error("Debugger has no exception filters");
+            return;
+        }
+    };
+    let supports_conditions =
+        debugger.capabilities().supports_exception_filter_options == Some(true);
+
+    let picker = Picker::new(
+        filters,
+        cx.editor.exception_filters.clone(),
+        move |cx, filter, _action| {
+            let enabled = &mut cx.editor.exception_filters;
+            if let Some(pos) = enabled.iter().position(|f| f.filter_id == filter.filter) {
+                enabled.remove(pos);
+                set_exception_filters(cx.editor);
+                return;
+            }
 
-    dap_callback(
-        cx.jobs,
-        request,
-        |_editor, _compositor, _response: dap::requests::SetExceptionBreakpointsResponse| {
-            // editor.set_error(format!("Failed to set up exception breakpoints: {}", e));
+            enabled.push(dap::ExceptionFilterOptions {
+                filter_id: filter.filter.clone(),
+                condition: None,
+            });
+            set_exception_filters(cx.editor);
+
+            if supports_conditions && filter.supports_condition == Some(true) {
+                let filter_id = filter.filter.clone();
+                let label = filter
+                    .condition_description
+                    .clone()
+                    .unwrap_or_else(|| "condition".to_owned());
+                let callback = Box::pin(async move {
+                    let call: Callback =
+                        Callback::EditorCompositor(Box::new(move |_editor, compositor| {
+                            let prompt = exception_condition_prompt(label, filter_id);
+                            compositor.push(Box::new(prompt));
+                        }));
+                    Ok(call)
+                });
+                cx.jobs.callback(callback);
+            }
+        },
+    );
+    cx.push_layer(Box::new(overlaid(picker)));
+}
+
+fn exception_condition_prompt(label: String, filter_id: String) -> Prompt {
+    Prompt::new(
+        format!("{}: ", label).into(),
+        None,
+        ui::completers::none,
+        move |cx, input: &str, event: PromptEvent| {
+            if event != PromptEvent::Validate {
+                return;
+            }
+
+            let filter = cx
+                .editor
+                .exception_filters
+                .iter_mut()
+                .find(|filter| filter.filter_id == filter_id);
+            if let Some(filter) = filter {
+                filter.condition = match input {
+                    "" => None,
+                    input => Some(input.to_owned()),
+                };
+                set_exception_filters(cx.editor);
+            }
         },
     )
 }
 
+/// Adds a function breakpoint, or removes it if there is one on the function already.
+pub fn toggle_function_breakpoint(editor: &mut Editor, name: &str) -> anyhow::Result<()> {
+    let breakpoints = &mut editor.function_breakpoints;
+    if let Some(pos) = breakpoints
+        .iter()
+        .position(|breakpoint| breakpoint.name == name)
+    {
+        breakpoints.remove(pos);
+    } else {
+        breakpoints.push(dap::FunctionBreakpoint {
+            name: name.to_owned(),
+            ..Default::default()
+        });
+    }
+
+    match editor.debuggers.active_mut() {
+        Some(debugger) => function_breakpoints_changed(debugger, &editor.function_breakpoints),
+        None => Ok(()),
+    }
+}
+
+/// Adds a data breakpoint on the variable `name` of the container at `variables_reference`, or
+/// on the expression `name`, or removes the one there is already.
+pub fn toggle_data_breakpoint(
+    editor: &mut Editor,
+    variables_reference: Option<usize>,
+    name: String,
+) -> anyhow::Result<()> {
+    let debugger = match editor.debuggers.active_mut() {
+        Some(debugger) => debugger,
+        None => bail!("Data breakpoints can only be set while debugging"),
+    };
+    if debugger.capabilities().supports_data_breakpoints != Some(true) {
+        bail!("Debugger does not support data breakpoints");
+    }
+
+    let frame_id = debugger.current_stack_frame().map(|frame| frame.id);
+    let info = block_on(debugger.data_breakpoint_info(variables_reference, name, frame_id))?;
+    let data_id = match info.data_id {
+        Some(data_id) => data_id,
+        None => bail!("Can't set a data breakpoint: {}", info.description),
+    };
+
+    let session = debugger.id();
+    let breakpoints = &mut editor.data_breakpoints;
+    if let Some(pos) = breakpoints
+        .iter()
+        .position(|breakpoint| breakpoint.session == session && breakpoint.data_id == data_id)
+    {
+        breakpoints.remove(pos);
+    } else {
+        breakpoints.push(DataBreakpoint {
+            session,
+            data_id,
+            description: info.description,
+            access_type: None,
+        });
+    }
+    data_breakpoints_changed(debugger, breakpoints)
+}
+
 // TODO: both edit condition and edit log need to be stable: we might get new breakpoints from the debugger which can change offsets
 pub fn dap_edit_condition(cx: &mut Context) {
     if let Some((pos, breakpoint)) = get_breakpoint_at_current_line(cx.editor) {
@@ -650,9 +1286,9 @@ pub fn dap_edit_condition(cx: &mut Context) {
                             input => Some(input.to_owned()),
                         };
 
-                        let debugger = debugger!(cx.editor);
-
-                        if let Err(e) = breakpoints_changed(debugger, path.clone(), breakpoints) {
+                        if let Err(e) =
+                            breakpoints_changed(&mut cx.editor.debuggers, path.clone(), breakpoints)
+                        {
                             cx.editor
                                 .set_error(format!("Failed to set breakpoints: {}", e));
                         }
@@ -692,8 +1328,9 @@ pub fn dap_edit_log(cx: &mut Context) {
                             input => Some(input.to_owned()),
                         };
 
-                        let debugger = debugger!(cx.editor);
-                        if let Err(e) = breakpoints_changed(debugger, path.clone(), breakpoints) {
+                        if let Err(e) =
+                            breakpoints_changed(&mut cx.editor.debuggers, path.clone(), breakpoints)
+                        {
                             cx.editor
                                 .set_error(format!("Failed to set breakpoints: {}", e));
                         }
@@ -744,6 +1381,9 @@ pub fn dap_switch_stack_frame(cx: &mut Context) {
                 .cloned();
             if let Some(frame) = &frame {
                 jump_to_stack_frame(cx.editor, frame);
+                block_on(refresh_debug_panels(cx.editor));
+                block_on(cx.editor.refresh_debug_values());
+                block_on(cx.editor.refresh_disassembly());
             }
         },
         move |_editor, frame| {
@@ -764,3 +1404,69 @@ pub fn dap_switch_stack_frame(cx: &mut Context) {
     );
     cx.push_layer(Box::new(picker))
 }
+
+/// A debug session in the session switcher.
+struct DebugSession {
+    id: usize,
+    /// The number of the session in the gutter, by the order the sessions were started.
+    number: usize,
+    name: String,
+    /// The name of the session which started this one.
+    parent: Option<String>,
+    stopped: bool,
+}
+
+impl ui::menu::Item for DebugSession {
+    /// The active session.
+    type Data = Option<usize>;
+
+    fn format(&self, active: &Self::Data) -> Row {
+        let mut state = if self.stopped { "stopped" } else { "running" }.to_owned();
+        if *active == Some(self.id) {
+            state.push_str(", active");
+        }
+        let name = match &self.parent {
+            Some(parent) => format!("{} (started by {})", self.name, parent),
+            None => self.name.clone(),
+        };
+        Row::new(vec![self.number.to_string(), name, state])
+    }
+}
+
+pub fn dap_switch_session(cx: &mut Context) {
+    let debuggers = &cx.editor.debuggers;
+    if debuggers.is_empty() {
+        cx.editor.set_error("Debugger is not running");
+        return;
+    }
+
+    let sessions = debuggers
+        .iter()
+        .enumerate()
+        .map(|(i, debugger)| DebugSession {
+            id: debugger.id(),
+            number: i + 1,
+            name: debugger.name.clone(),
+            parent: debugger
+                .parent_id
+                .and_then(|id| debuggers.get(id))
+                .map(|parent| parent.name.clone()),
+            stopped: debugger.thread_id.is_some(),
+        })
+        .collect();
+
+    cx.push_layer(Box::new(overlaid(Picker::new(
+        sessions,
+        debuggers.active_id(),
+        |cx, session, _action| {
+            cx.editor.debuggers.set_active(session.id);
+            if let Some(frame) = cx.editor.current_stack_frame().cloned() {
+                jump_to_stack_frame(cx.editor, &frame);
+            }
+            cx.editor.debug_panels.clear_values();
+            block_on(refresh_debug_panels(cx.editor));
+            block_on(cx.editor.refresh_debug_values());
+            block_on(cx.editor.refresh_disassembly());
+        },
+    ))));
+}
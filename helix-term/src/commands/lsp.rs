@@ -15,7 +15,9 @@
 
 use super::{align_view, push_jump, Align, Context, Editor, Open};
 
-use helix_core::{path, text_annotations::InlineAnnotation, Selection};
+use helix_core::{
+    diagnostic::DiagnosticProvider, path, text_annotations::InlineAnnotation, Selection,
+};
 use helix_view::{
     document::{DocumentInlayHints, DocumentInlayHintsId, Mode},
     editor::Action,
@@ -25,14 +27,22 @@
 
 use crate::{
     compositor::{self, Compositor},
+    job::{Callback, Jobs},
     ui::{
-        self, lsp::SignatureHelp, overlay::overlaid, DynamicPicker, FileLocation, FilePicker,
-        Popup, PromptEvent,
+        self,
+        lsp::SignatureHelp,
+        overlay::{overlaid, Overlay},
+        DynamicPicker, FileLocation, FilePicker, Popup, PromptEvent,
     },
 };
 
 use std::{
-    cmp::Ordering, collections::BTreeMap, fmt::Write, future::Future, path::PathBuf, sync::Arc,
+    cmp::Ordering,
+    collections::{BTreeMap, HashSet},
+    fmt::Write,
+    future::Future,
+    path::PathBuf,
+    sync::Arc,
 };
 
 /// Gets the language server that is attached to a document, and
@@ -177,8 +187,12 @@ fn jump_to_location(
     offset_encoding: OffsetEncoding,
     action: Action,
 ) {
-    let (view, doc) = current!(editor);
-    push_jump(view, doc);
+    // the document is only loaded in the background when several locations are opened at once
+    let load = matches!(action, Action::Load);
+    if !load {
+        let (view, doc) = current!(editor);
+        push_jump(view, doc);
+    }
 
     let path = match location.uri.to_file_path() {
         Ok(path) => path,
@@ -188,15 +202,16 @@ fn jump_to_location(
             return;
         }
     };
-    match editor.open(&path, action) {
-        Ok(_) => (),
+    let doc_id = match editor.open(&path, action) {
+        Ok(doc_id) => doc_id,
         Err(err) => {
             let err = format!("failed to open path: {:?}: {:?}", location.uri, err);
             editor.set_error(err);
             return;
         }
-    }
-    let (view, doc) = current!(editor);
+    };
+    let view = view_mut!(editor);
+    let doc = doc_mut!(editor, &doc_id);
     // TODO: convert inside server
     let new_range =
         if let Some(new_range) = lsp_range_to_range(doc.text(), location.range, offset_encoding) {
@@ -206,7 +221,9 @@ fn jump_to_location(
             return;
         };
     doc.set_selection(view.id, Selection::single(new_range.anchor, new_range.head));
-    align_view(doc, view, Align::Center);
+    if !load {
+        align_view(doc, view, Align::Center);
+    }
 }
 
 fn sym_picker(
@@ -219,9 +236,14 @@ fn sym_picker(
         symbols,
         current_path.clone(),
         move |cx, symbol, action| {
+            // the document is only loaded when several symbols are opened at once
+            let load = matches!(action, Action::Load);
             let (view, doc) = current!(cx.editor);
-            push_jump(view, doc);
+            if !load {
+                push_jump(view, doc);
+            }
 
+            let mut doc_id = doc.id();
             if current_path.as_ref() != Some(&symbol.location.uri) {
                 let uri = &symbol.location.uri;
                 let path = match uri.to_file_path() {
@@ -232,15 +254,19 @@ fn sym_picker(
                         return;
                     }
                 };
-                if let Err(err) = cx.editor.open(&path, action) {
-                    let err = format!("failed to open document: {}: {}", uri, err);
-                    log::error!("{}", err);
-                    cx.editor.set_error(err);
-                    return;
-                }
+                doc_id = match cx.editor.open(&path, action) {
+                    Ok(doc_id) => doc_id,
+                    Err(err) => {
+                        let err = format!("failed to open document: {}: {}", uri, err);
+                        log::error!("{}", err);
+                        cx.editor.set_error(err);
+                        return;
+                    }
+                };
             }
 
-            let (view, doc) = current!(cx.editor);
+            let view = view_mut!(cx.editor);
+            let doc = doc_mut!(cx.editor, &doc_id);
 
             if let Some(range) =
                 lsp_range_to_range(doc.text(), symbol.location.range, offset_encoding)
@@ -248,7 +274,9 @@ fn sym_picker(
                 // we flip the range so that the cursor sits on the start of the symbol
                 // (for example start of the function).
                 doc.set_selection(view.id, Selection::single(range.head, range.anchor));
-                align_view(doc, view, Align::Center);
+                if !load {
+                    align_view(doc, view, Align::Center);
+                }
             }
         },
         move |_editor, symbol| Some(location_to_file_location(&symbol.location)),
@@ -262,16 +290,13 @@ enum DiagnosticsFormat {
     HideSourcePath,
 }
 
-fn diag_picker(
-    cx: &Context,
-    diagnostics: BTreeMap<lsp::Url, Vec<lsp::Diagnostic>>,
-    current_path: Option<lsp::Url>,
-    format: DiagnosticsFormat,
-    offset_encoding: OffsetEncoding,
-) -> FilePicker<PickerDiagnostic> {
-    // TODO: drop current_path comparison and instead use workspace: bool flag?
+/// The id of the workspace diagnostics picker, the name of its command.
+const WORKSPACE_DIAGNOSTICS_PICKER_ID: &str = "workspace_diagnostics_picker";
 
-    // flatten the map to a vec of (url, diag) pairs
+/// Flattens the map to a vec of (url, diag) pairs.
+fn flatten_diagnostics(
+    diagnostics: BTreeMap<lsp::Url, Vec<lsp::Diagnostic>>,
+) -> Vec<PickerDiagnostic> {
     let mut flat_diag = Vec::new();
     for (url, diags) in diagnostics {
         flat_diag.reserve(diags.len());
@@ -282,33 +307,53 @@ fn diag_picker(
             });
         }
     }
+    flat_diag
+}
+
+fn diag_picker(
+    editor: &Editor,
+    diagnostics: BTreeMap<lsp::Url, Vec<lsp::Diagnostic>>,
+    current_path: Option<lsp::Url>,
+    format: DiagnosticsFormat,
+    offset_encoding: OffsetEncoding,
+) -> FilePicker<PickerDiagnostic> {
+    // TODO: drop current_path comparison and instead use workspace: bool flag?
+    let flat_diag = flatten_diagnostics(diagnostics);
 
     let styles = DiagnosticStyles {
-        hint: cx.editor.theme.get("hint"),
-        info: cx.editor.theme.get("info"),
-        warning: cx.editor.theme.get("warning"),
-        error: cx.editor.theme.get("error"),
+        hint: editor.theme.get("hint"),
+        info: editor.theme.get("info"),
+        warning: editor.theme.get("warning"),
+        error: editor.theme.get("error"),
     };
 
     FilePicker::new(
         flat_diag,
         (styles, format),
         move |cx, PickerDiagnostic { url, diag }, action| {
-            if current_path.as_ref() == Some(url) {
+            // the document is only loaded when several diagnostics are opened at once
+            let load = matches!(action, Action::Load);
+            let doc_id = if current_path.as_ref() == Some(url) {
                 let (view, doc) = current!(cx.editor);
-                push_jump(view, doc);
+                if !load {
+                    push_jump(view, doc);
+                }
+                doc.id()
             } else {
                 let path = url.to_file_path().unwrap();
-                cx.editor.open(&path, action).expect("editor.open failed");
-            }
+                cx.editor.open(&path, action).expect("editor.open failed")
+            };
 
-            let (view, doc) = current!(cx.editor);
+            let view = view_mut!(cx.editor);
+            let doc = doc_mut!(cx.editor, &doc_id);
 
             if let Some(range) = lsp_range_to_range(doc.text(), diag.range, offset_encoding) {
                 // we flip the range so that the cursor sits on the start of the symbol
                 // (for example start of the function).
                 doc.set_selection(view.id, Selection::single(range.head, range.anchor));
-                align_view(doc, view, Align::Center);
+                if !load {
+                    align_view(doc, view, Align::Center);
+                }
             }
         },
         move |_editor, PickerDiagnostic { url, diag }| {
@@ -448,7 +493,7 @@ pub fn diagnostics_picker(cx: &mut Context) {
             .cloned()
             .unwrap_or_default();
         let picker = diag_picker(
-            cx,
+            cx.editor,
             [(current_url.clone(), diagnostics)].into(),
             Some(current_url),
             DiagnosticsFormat::HideSourcePath,
@@ -463,14 +508,19 @@ pub fn workspace_diagnostics_picker(cx: &mut Context) {
     let language_server = language_server!(cx.editor, doc);
     let current_url = doc.url();
     let offset_encoding = language_server.offset_encoding();
+
+    // Show the diagnostics known so far, the picker is refreshed once the pull is answered.
+    pull_workspace_diagnostics(language_server, cx.jobs);
+
     let diagnostics = cx.editor.diagnostics.clone();
     let picker = diag_picker(
-        cx,
+        cx.editor,
         diagnostics,
         current_url,
         DiagnosticsFormat::ShowSourcePath,
         offset_encoding,
-    );
+    )
+    .with_id(WORKSPACE_DIAGNOSTICS_PICKER_ID);
     cx.push_layer(Box::new(overlaid(picker)));
 }
 
@@ -554,14 +604,16 @@ pub fn code_action(cx: &mut Context) {
     let future = match language_server.code_actions(
         doc.identifier(),
         range,
-        // Filter and convert overlapping diagnostics
+        // Filter and convert overlapping diagnostics, leaving out those of other providers which
+        // the server doesn't know about
         lsp::CodeActionContext {
             diagnostics: doc
                 .diagnostics()
                 .iter()
                 .filter(|&diag| {
-                    selection_range
-                        .overlaps(&helix_core::Range::new(diag.range.start, diag.range.end))
+                    diag.provider == DiagnosticProvider::Lsp
+                        && selection_range
+                            .overlaps(&helix_core::Range::new(diag.range.start, diag.range.end))
                 })
                 .map(|diag| diagnostic_to_lsp_diagnostic(doc.text(), diag, offset_encoding))
                 .collect(),
@@ -1641,3 +1693,81 @@ fn compute_inlay_hints_for_view(
 
     Some(callback)
 }
+
+/// Pulls the diagnostics of the documents shown in views which changed since they were last
+/// pulled.
+pub fn pull_diagnostics_for_all_views(editor: &mut Editor, jobs: &mut Jobs) {
+    let doc_ids: HashSet<_> = editor.tree.views().map(|(view, _)| view.doc).collect();
+    for doc_id in doc_ids {
+        if let Some(doc) = editor.documents.get_mut(&doc_id) {
+            if doc.pulled_diagnostics_outdated {
+                pull_diagnostics_for_document(doc, jobs);
+            }
+        }
+    }
+}
+
+/// Pulls the diagnostics of a document from its language server, if the server supports pull
+/// diagnostics.
+pub fn pull_diagnostics_for_document(doc: &mut Document, jobs: &mut Jobs) {
+    let (language_server, uri) = match (doc.language_server(), doc.url()) {
+        (Some(language_server), Some(uri)) => (language_server, uri),
+        _ => return,
+    };
+    let server_id = language_server.id();
+    let identifier = lsp::TextDocumentIdentifier::new(uri.clone());
+    let future = match language_server.text_document_diagnostic(identifier) {
+        Some(future) => future,
+        None => return,
+    };
+    doc.pulled_diagnostics_outdated = false;
+
+    let version = doc.version();
+    let callbacks = jobs.callback_sender();
+    jobs.spawn(async move {
+        match future.await {
+            Ok(report) => {
+                let call = move |editor: &mut Editor| {
+                    editor.handle_lsp_document_diagnostic_report(server_id, uri, version, report)
+                };
+                let _ = callbacks.send(Callback::Editor(Box::new(call)));
+            }
+            Err(err) => log::warn!("Failed to pull the diagnostics of {}: {}", uri, err),
+        }
+        Ok(())
+    });
+}
+
+/// Pulls the diagnostics of the whole workspace from a language server, if the server supports
+/// workspace diagnostics and the previous pull was answered. An open workspace diagnostics picker
+/// is refreshed with the result.
+pub fn pull_workspace_diagnostics(language_server: &helix_lsp::Client, jobs: &mut Jobs) {
+    let server_id = language_server.id();
+    let future = match language_server.workspace_diagnostic() {
+        Some(future) => future,
+        None => return,
+    };
+
+    let callbacks = jobs.callback_sender();
+    jobs.spawn(async move {
+        match future.await {
+            Ok(report) => {
+                let call = move |editor: &mut Editor, compositor: &mut Compositor| {
+                    editor.handle_lsp_workspace_diagnostic_report(server_id, report);
+                    // Wrapping of pickers in overlay is done outside the picker code,
+                    // so this is fragile and will break if wrapped in some other widget.
+                    type DiagnosticsPicker = Overlay<FilePicker<PickerDiagnostic>>;
+                    if let Some(overlay) =
+                        compositor.find_id::<DiagnosticsPicker>(WORKSPACE_DIAGNOSTICS_PICKER_ID)
+                    {
+                        let diagnostics = flatten_diagnostics(editor.diagnostics.clone());
+                        overlay.content.set_options(diagnostics);
+                    }
+                };
+                let _ = callbacks.send(Callback::EditorCompositor(Box::new(call)));
+            }
+            Err(err) => log::warn!("Failed to pull workspace diagnostics: {}", err),
+        }
+        Ok(())
+    });
+}
@@ -5,7 +5,9 @@
 
 use super::*;
 
-use helix_core::{encoding, shellwords::Shellwords};
+use helix_core::{
+    diagnostic::DiagnosticProvider, encoding, shellwords::Shellwords, syntax::TaskConfiguration,
+};
 use helix_view::document::DEFAULT_LANGUAGE_NAME;
 use helix_view::editor::{Action, CloseError, ConfigEvent};
 use serde_json::Value;
@@ -115,7 +117,7 @@ fn open(cx: &mut compositor::Context, args: &[Cow<str>], event: PromptEvent) ->
             let callback = async move {
                 let call: job::Callback = job::Callback::EditorCompositor(Box::new(
                     move |editor: &mut Editor, compositor: &mut Compositor| {
-                        let picker = ui::file_picker(path, &editor.config());
+                        let picker = ui::file_picker(path, editor);
                         compositor.push(Box::new(overlaid(picker)));
                     },
                 ));
@@ -135,7 +137,7 @@ fn open(cx: &mut compositor::Context, args: &[Cow<str>], event: PromptEvent) ->
     Ok(())
 }
 
-fn buffer_close_by_ids_impl(
+pub(super) fn buffer_close_by_ids_impl(
     cx: &mut compositor::Context,
     doc_ids: &[DocumentId],
     force: bool,
@@ -238,7 +240,7 @@ fn buffer_gather_others_impl(editor: &mut Editor) -> Vec<DocumentId> {
     editor
         .documents()
         .map(|doc| doc.id())
-        .filter(|doc_id| doc_id != current_document)
+        .filter(|doc_id| doc_id != current_document && !editor.is_pinned(*doc_id))
         .collect()
 }
 
@@ -324,6 +326,58 @@ fn buffer_previous(
     Ok(())
 }
 
+fn buffer_pin(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    ensure!(args.is_empty(), ":buffer-pin takes no arguments");
+
+    let doc_id = doc!(cx.editor).id();
+    if cx.editor.toggle_pin(doc_id) {
+        cx.editor.set_status("Buffer pinned");
+    } else {
+        cx.editor.set_status("Buffer unpinned");
+    }
+    Ok(())
+}
+
+fn buffer_move(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    ensure!(args.len() == 1, ":buffer-move takes one argument");
+
+    let doc_id = doc!(cx.editor).id();
+    let current = cx.editor.buffer_position(doc_id).unwrap_or_default();
+    let arg = args[0].as_ref();
+    let parse = |number: &str| {
+        number
+            .parse::<usize>()
+            .map_err(|_| anyhow!("Invalid buffer position: {}", arg))
+    };
+    // `+n` and `-n` move relative to the current position, a plain number is a position
+    let to = if let Some(offset) = arg.strip_prefix('+') {
+        current + parse(offset)?
+    } else if let Some(offset) = arg.strip_prefix('-') {
+        current.saturating_sub(parse(offset)?)
+    } else {
+        parse(arg)?.saturating_sub(1)
+    };
+    cx.editor.move_buffer(doc_id, to);
+
+    Ok(())
+}
+
 fn write_impl(
     cx: &mut compositor::Context,
     path: Option<&Cow<str>>,
@@ -1426,7 +1480,7 @@ fn lsp_stop(
     for doc in cx.editor.documents_mut() {
         if doc.language_server().map_or(false, |ls| ls.id() == ls_id) {
             doc.set_language_server(None);
-            doc.set_diagnostics(Default::default());
+            doc.replace_diagnostics(&DiagnosticProvider::Lsp, Vec::new());
         }
     }
 
@@ -1540,6 +1594,188 @@ fn hsplit_new(
     Ok(())
 }
 
+fn tab_new(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    ensure!(args.len() <= 1, ":tab-new takes at most one argument");
+
+    let doc_id = match args.first() {
+        Some(path) => cx
+            .editor
+            .open(&PathBuf::from(path.as_ref()), Action::Load)?,
+        None => cx.editor.new_file(Action::Load),
+    };
+    cx.editor.new_tab(doc_id, None);
+
+    Ok(())
+}
+
+fn tab_close(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    ensure!(args.is_empty(), ":tab-close takes no arguments");
+    ensure!(
+        cx.editor.tree.tabs().len() > 1,
+        "Cannot close the last tab page"
+    );
+
+    cx.editor.close_tab(cx.editor.tree.active_tab());
+
+    Ok(())
+}
+
+fn tab_only(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    ensure!(args.is_empty(), ":tab-only takes no arguments");
+
+    let active = cx.editor.tree.tabs()[cx.editor.tree.active_tab()].id;
+    while cx.editor.tree.tabs().len() > 1 {
+        let index = cx
+            .editor
+            .tree
+            .tabs()
+            .iter()
+            .position(|tab| tab.id != active)
+            .unwrap();
+        cx.editor.close_tab(index);
+    }
+
+    Ok(())
+}
+
+fn tab_next(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    ensure!(args.is_empty(), ":tab-next takes no arguments");
+
+    let len = cx.editor.tree.tabs().len();
+    cx.editor.focus_tab((cx.editor.tree.active_tab() + 1) % len);
+
+    Ok(())
+}
+
+fn tab_previous(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    ensure!(args.is_empty(), ":tab-previous takes no arguments");
+
+    let len = cx.editor.tree.tabs().len();
+    cx.editor
+        .focus_tab((cx.editor.tree.active_tab() + len - 1) % len);
+
+    Ok(())
+}
+
+/// Parse a 1-based tab page position into an index into `Tree::tabs`.
+fn tab_position(editor: &Editor, arg: &str) -> anyhow::Result<usize> {
+    let position: usize = arg
+        .parse()
+        .map_err(|_| anyhow!("Invalid tab page number: {}", arg))?;
+    ensure!(
+        (1..=editor.tree.tabs().len()).contains(&position),
+        "No tab page number {}",
+        position
+    );
+    Ok(position - 1)
+}
+
+fn tab_goto(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    ensure!(args.len() == 1, ":tab-goto takes one argument");
+
+    let index = tab_position(cx.editor, &args[0])?;
+    cx.editor.focus_tab(index);
+
+    Ok(())
+}
+
+fn tab_move(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    ensure!(args.len() == 1, ":tab-move takes one argument");
+
+    let active = cx.editor.tree.active_tab();
+    let last = cx.editor.tree.tabs().len() - 1;
+    let arg = args[0].as_ref();
+    // `+n` and `-n` move relative to the current position, a plain number is a position
+    let to = if let Some(offset) = arg.strip_prefix('+') {
+        let offset: usize = offset
+            .parse()
+            .map_err(|_| anyhow!("Invalid tab page offset: {}", arg))?;
+        (active + offset).min(last)
+    } else if let Some(offset) = arg.strip_prefix('-') {
+        let offset: usize = offset
+            .parse()
+            .map_err(|_| anyhow!("Invalid tab page offset: {}", arg))?;
+        active.saturating_sub(offset)
+    } else {
+        tab_position(cx.editor, arg)?
+    };
+    cx.editor.tree.move_tab(active, to);
+
+    Ok(())
+}
+
+fn tab_rename(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let name = (!args.is_empty()).then(|| args.join(" "));
+    let active = cx.editor.tree.active_tab();
+    cx.editor.tree.set_tab_name(active, name);
+
+    Ok(())
+}
+
 fn debug_eval(
     cx: &mut compositor::Context,
     args: &[Cow<str>],
@@ -1549,7 +1785,7 @@ fn debug_eval(
         return Ok(());
     }
 
-    if let Some(debugger) = cx.editor.debugger.as_mut() {
+    if let Some(debugger) = cx.editor.debuggers.active_mut() {
         let (frame, thread_id) = match (debugger.active_frame, debugger.thread_id) {
             (Some(frame), Some(thread_id)) => (frame, thread_id),
             _ => {
@@ -1566,6 +1802,38 @@ fn debug_eval(
     Ok(())
 }
 
+fn debug_function_breakpoint(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+    ensure!(
+        !args.is_empty(),
+        ":debug-function-breakpoint takes a function name"
+    );
+
+    dap::toggle_function_breakpoint(cx.editor, &args.join(" "))
+}
+
+fn debug_data_breakpoint(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+    ensure!(
+        !args.is_empty(),
+        ":debug-data-breakpoint takes an expression"
+    );
+
+    dap::toggle_data_breakpoint(cx.editor, None, args.join(" "))
+}
+
 fn debug_start(
     cx: &mut compositor::Context,
     args: &[Cow<str>],
@@ -1580,6 +1848,21 @@ fn debug_start(
         0 => None,
         _ => Some(args.remove(0)),
     };
+    if let Some(name) = &name {
+        // the configurations of the workspace take precedence over the templates, which are
+        // still available if the launch files are broken
+        match helix_dap::launch::load(&helix_loader::find_workspace().0) {
+            Ok(configurations) => {
+                let configuration = configurations
+                    .into_iter()
+                    .find(|configuration| configuration.name == *name);
+                if let Some(configuration) = configuration {
+                    return dap::dap_start_configuration(cx, &configuration);
+                }
+            }
+            Err(err) => cx.editor.set_error(format!("{:#}", err)),
+        }
+    }
     dap_start_impl(cx, name.as_deref(), None, Some(args))
 }
 
@@ -2113,6 +2396,245 @@ fn run_shell_command(
     Ok(())
 }
 
+fn terminal(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let command = (!args.is_empty()).then(|| args.join(" "));
+    let shell = cx.editor.config().shell.clone();
+    let cwd = std::env::current_dir()?;
+    let callbacks = cx.jobs.callback_sender();
+
+    let callback = async move {
+        let call: job::Callback = Callback::EditorCompositor(Box::new(
+            move |editor: &mut Editor, compositor: &mut Compositor| {
+                let doc_id = editor.new_file(Action::HorizontalSplit);
+                let view = view!(editor);
+                let view_id = view.id;
+                let size = (view.area.width, view.area.height.saturating_sub(1));
+
+                let terminal = ui::terminal::Terminal::spawn(
+                    command.as_deref(),
+                    &shell,
+                    &cwd,
+                    size,
+                    view_id,
+                    doc_id,
+                    callbacks,
+                );
+                match (terminal, compositor.find::<ui::EditorView>()) {
+                    (Ok(terminal), Some(editor_view)) => {
+                        editor_view.terminals.insert(view_id, terminal)
+                    }
+                    (Err(err), _) => {
+                        editor.close(view_id);
+                        let _ = editor.close_document(doc_id, true);
+                        editor.set_error(err.to_string());
+                    }
+                    (Ok(_), None) => (),
+                }
+            },
+        ));
+        Ok(call)
+    };
+    cx.jobs.callback(callback);
+
+    Ok(())
+}
+
+fn make(cx: &mut compositor::Context, args: &[Cow<str>], event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+    ensure!(args.len() <= 1, ":make takes at most one argument");
+
+    let name = args.first().map_or("build", |name| name.as_ref());
+    let tasks = cx.editor.tasks();
+    let task = match tasks.get(name) {
+        Some(task) => task.clone(),
+        None if tasks.is_empty() => bail!("No tasks are configured"),
+        None => bail!(
+            "Unknown task '{}', available tasks: {}",
+            name,
+            tasks
+                .keys()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+
+    run_task(cx, name, task)
+}
+
+/// Runs `task` in the background, streaming its output into the task output buffer. Once
+/// it exits, the locations in the output are shown as diagnostics and in the quickfix list.
+fn run_task(
+    cx: &mut compositor::Context,
+    name: &str,
+    task: TaskConfiguration,
+) -> anyhow::Result<()> {
+    use std::process::Stdio;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::process::Command;
+
+    let config = cx.editor.config();
+    let shell = config.shell.clone();
+    ensure!(!shell.is_empty(), "No shell set");
+    let error_formats = task
+        .error_formats
+        .clone()
+        .unwrap_or_else(|| config.error_formats.clone());
+    drop(config);
+    // report broken patterns now rather than once the task is done
+    for format in &error_formats {
+        helix_core::errorformat::ErrorFormat::new(format)
+            .with_context(|| format!("invalid error format '{}'", format))?;
+    }
+    let cwd = std::env::current_dir()?;
+
+    let mut process = Command::new(&shell[0])
+        .args(&shell[1..])
+        .arg(&task.command)
+        .current_dir(&cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("failed to start task '{}'", name))?;
+    let stdout = process.stdout.take().expect("stdout is piped");
+    let stderr = process.stderr.take().expect("stderr is piped");
+
+    let output_id = open_task_output(cx.editor, &format!("$ {}\n", task.command));
+    // starting a task cancels the previous one, which shares the output buffer
+    let (cancel_sender, mut cancel) = tokio::sync::oneshot::channel::<()>();
+    cx.editor.task_handle = Some(cancel_sender);
+    cx.editor.set_status(format!("Running task '{}'", name));
+
+    let sender = cx.jobs.callback_sender();
+    let name = name.to_string();
+    cx.jobs.spawn(async move {
+        let mut stdout = BufReader::new(stdout).lines();
+        let mut stderr = BufReader::new(stderr).lines();
+        let (mut stdout_done, mut stderr_done) = (false, false);
+        let mut output = String::new();
+
+        while !(stdout_done && stderr_done) {
+            let (line, done) = tokio::select! {
+                line = stdout.next_line(), if !stdout_done => (line?, &mut stdout_done),
+                line = stderr.next_line(), if !stderr_done => (line?, &mut stderr_done),
+                _ = &mut cancel => {
+                    process.kill().await?;
+                    return Ok(());
+                }
+            };
+            let mut line = match line {
+                Some(line) => line,
+                None => {
+                    *done = true;
+                    continue;
+                }
+            };
+            line.push('\n');
+            output.push_str(&line);
+            let _ = sender.send(Callback::Editor(Box::new(move |editor| {
+                append_task_output(editor, output_id, &line);
+            })));
+        }
+
+        let status = process.wait().await?;
+        let entries = helix_view::quickfix::parse_output(&error_formats, &output, &cwd)?;
+        // sent through the same channel as the output so that it's handled last
+        let _ = sender.send(Callback::Editor(Box::new(move |editor| {
+            let result = match status.code() {
+                Some(0) => "succeeded".to_string(),
+                Some(code) => format!("failed with exit code {}", code),
+                None => "was terminated".to_string(),
+            };
+            append_task_output(
+                editor,
+                output_id,
+                &format!("\nTask '{}' {}\n", name, result),
+            );
+
+            let title = format!("make {}", name);
+            let count = entries.len();
+            editor.set_task_diagnostics(&name, entries.clone());
+            if !entries.is_empty() {
+                editor.quickfix.set(title, entries);
+                editor.quickfix.visible = true;
+            } else if editor.quickfix.title == title {
+                editor.quickfix.set(title, Vec::new());
+            }
+
+            let message = format!("Task '{}' {}, {} locations found", name, result, count);
+            if status.success() {
+                editor.set_status(message);
+            } else {
+                editor.set_error(message);
+            }
+        })));
+        Ok(())
+    });
+
+    Ok(())
+}
+
+/// Returns the scratch buffer for task output, starting it over with `header`. The buffer
+/// is opened in a split if it isn't visible yet, keeping the focus on the current view.
+fn open_task_output(editor: &mut Editor, header: &str) -> DocumentId {
+    let doc_id = match editor
+        .task_output
+        .filter(|id| editor.documents.contains_key(id))
+    {
+        Some(doc_id) => {
+            let doc = doc_mut!(editor, &doc_id);
+            let len = doc.text().len_chars();
+            editor.write_output_buffer(doc_id, (0, len), header);
+            doc_id
+        }
+        None => {
+            let doc_id = editor.new_file(Action::Load);
+            editor.write_output_buffer(doc_id, (0, 0), header);
+            editor.task_output = Some(doc_id);
+            doc_id
+        }
+    };
+
+    if !editor.tree.views().any(|(view, _)| view.doc == doc_id) {
+        let focus = editor.tree.focus;
+        editor.switch(doc_id, Action::HorizontalSplit);
+        editor.focus(focus);
+    }
+    doc_id
+}
+
+fn append_task_output(editor: &mut Editor, doc_id: DocumentId, text: &str) {
+    let len = match editor.documents.get(&doc_id) {
+        Some(doc) => doc.text().len_chars(),
+        None => return,
+    };
+    editor.write_output_buffer(doc_id, (len, len), text);
+
+    // follow the output in the views which show it, unless the user is in them
+    let focus = editor.tree.focus;
+    let scrolloff = editor.config().scrolloff;
+    let doc = doc_mut!(editor, &doc_id);
+    let end = doc.text().len_chars();
+    for (view, _) in editor.tree.views_mut() {
+        if view.doc == doc_id && view.id != focus {
+            doc.set_selection(view.id, Selection::point(end));
+            view.ensure_cursor_in_view(doc, scrolloff);
+        }
+    }
+}
+
 fn reset_diff_change(
     cx: &mut compositor::Context,
     args: &[Cow<str>],
@@ -2167,6 +2689,76 @@ fn reset_diff_change(
     Ok(())
 }
 
+fn quickfix(
+    cx: &mut compositor::Context,
+    _args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    cx.editor.quickfix.visible = !cx.editor.quickfix.visible;
+    Ok(())
+}
+
+fn debug_panels(
+    cx: &mut compositor::Context,
+    _args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let show = !cx.editor.debug_panels.visible;
+    dap::show_debug_panels(cx.editor, show);
+    Ok(())
+}
+
+fn debug_console(
+    cx: &mut compositor::Context,
+    args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+    ensure!(args.len() <= 1, ":debug-console takes at most one argument");
+
+    if let Some(category) = args.first() {
+        cx.editor.debug_console.filter = match category.as_ref() {
+            "all" => None,
+            category => Some(category.to_owned()),
+        };
+    }
+    dap::open_debug_console(cx.editor);
+    Ok(())
+}
+
+fn quickfix_from_buffer(
+    cx: &mut compositor::Context,
+    _args: &[Cow<str>],
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let doc = doc!(cx.editor);
+    let title = doc.display_name().into_owned();
+    let output = doc.text().to_string();
+    let cwd = std::env::current_dir()?;
+    let entries =
+        helix_view::quickfix::parse_output(&cx.editor.config().error_formats, &output, &cwd)?;
+    ensure!(!entries.is_empty(), "no locations found in {}", title);
+
+    cx.editor.quickfix.set(title, entries);
+    cx.editor.quickfix.visible = true;
+    goto_quickfix_entry(cx.editor);
+    Ok(())
+}
+
 pub const TYPABLE_COMMAND_LIST: &[TypableCommand] = &[
         TypableCommand {
             name: "quit",
@@ -2206,14 +2798,14 @@ fn reset_diff_change(
         TypableCommand {
             name: "buffer-close-others",
             aliases: &["bco", "bcloseother"],
-            doc: "Close all buffers but the currently focused one.",
+            doc: "Close all buffers but the currently focused and the pinned ones.",
             fun: buffer_close_others,
             signature: CommandSignature::none(),
         },
         TypableCommand {
             name: "buffer-close-others!",
             aliases: &["bco!", "bcloseother!"],
-            doc: "Force close all buffers but the currently focused one.",
+            doc: "Force close all buffers but the currently focused and the pinned ones.",
             fun: force_buffer_close_others,
             signature: CommandSignature::none(),
         },
@@ -2245,6 +2837,20 @@ fn reset_diff_change(
             fun: buffer_previous,
             signature: CommandSignature::none(),
         },
+        TypableCommand {
+            name: "buffer-pin",
+            aliases: &["bpin"],
+            doc: "Pin or unpin the current buffer. Pinned buffers are listed first and kept by :buffer-close-others.",
+            fun: buffer_pin,
+            signature: CommandSignature::none(),
+        },
+        TypableCommand {
+            name: "buffer-move",
+            aliases: &["bmove"],
+            doc: "Move the current buffer to the given position in the bufferline, or by `+n`/`-n` places.",
+            fun: buffer_move,
+            signature: CommandSignature::none(),
+        },
         TypableCommand {
             name: "write",
             aliases: &["w"],
@@ -2533,7 +3139,7 @@ fn reset_diff_change(
         TypableCommand {
             name: "debug-start",
             aliases: &["dbg"],
-            doc: "Start a debug session from a given template with given parameters.",
+            doc: "Start a debug session from a given launch configuration or template with given parameters.",
             fun: debug_start,
             signature: CommandSignature::none(),
         },
@@ -2551,6 +3157,20 @@ fn reset_diff_change(
             fun: debug_eval,
             signature: CommandSignature::none(),
         },
+        TypableCommand {
+            name: "debug-function-breakpoint",
+            aliases: &["dbf"],
+            doc: "Toggle a breakpoint on the function with the given name.",
+            fun: debug_function_breakpoint,
+            signature: CommandSignature::none(),
+        },
+        TypableCommand {
+            name: "debug-data-breakpoint",
+            aliases: &["dbd"],
+            doc: "Toggle a data breakpoint, which stops when the value of the given expression changes.",
+            fun: debug_data_breakpoint,
+            signature: CommandSignature::none(),
+        },
         TypableCommand {
             name: "vsplit",
             aliases: &["vs"],
@@ -2579,6 +3199,62 @@ fn reset_diff_change(
             fun: hsplit_new,
             signature: CommandSignature::none(),
         },
+        TypableCommand {
+            name: "tab-new",
+            aliases: &["tabnew"],
+            doc: "Open a file, or a scratch buffer, in a new tab page.",
+            fun: tab_new,
+            signature: CommandSignature::positional(&[completers::filename]),
+        },
+        TypableCommand {
+            name: "tab-close",
+            aliases: &["tabclose", "tabc"],
+            doc: "Close the current tab page and all of its views.",
+            fun: tab_close,
+            signature: CommandSignature::none(),
+        },
+        TypableCommand {
+            name: "tab-only",
+            aliases: &["tabonly", "tabo"],
+            doc: "Close all tab pages but the current one.",
+            fun: tab_only,
+            signature: CommandSignature::none(),
+        },
+        TypableCommand {
+            name: "tab-next",
+            aliases: &["tabnext", "tabn"],
+            doc: "Switch to the next tab page.",
+            fun: tab_next,
+            signature: CommandSignature::none(),
+        },
+        TypableCommand {
+            name: "tab-previous",
+            aliases: &["tabprevious", "tabp"],
+            doc: "Switch to the previous tab page.",
+            fun: tab_previous,
+            signature: CommandSignature::none(),
+        },
+        TypableCommand {
+            name: "tab-goto",
+            aliases: &["tabgoto"],
+            doc: "Switch to the tab page at the given position, starting from 1.",
+            fun: tab_goto,
+            signature: CommandSignature::none(),
+        },
+        TypableCommand {
+            name: "tab-move",
+            aliases: &["tabmove", "tabm"],
+            doc: "Move the current tab page to the given position, or by `+n`/`-n` places.",
+            fun: tab_move,
+            signature: CommandSignature::none(),
+        },
+        TypableCommand {
+            name: "tab-rename",
+            aliases: &["tabrename"],
+            doc: "Name the current tab page. Without a name the tab is labelled after its focused buffer.",
+            fun: tab_rename,
+            signature: CommandSignature::none(),
+        },
         TypableCommand {
             name: "tutor",
             aliases: &[],
@@ -2713,6 +3389,20 @@ fn reset_diff_change(
             fun: run_shell_command,
             signature: CommandSignature::all(completers::filename)
         },
+        TypableCommand {
+            name: "make",
+            aliases: &["mk"],
+            doc: "Run a task like `build` (the default) or `test` in the background and collect the locations in its output into the quickfix list.",
+            fun: make,
+            signature: CommandSignature::positional(&[completers::task]),
+        },
+        TypableCommand {
+            name: "terminal",
+            aliases: &["term"],
+            doc: "Open a terminal running the given command, or your shell, in a horizontal split. Press `Ctrl-\\ Ctrl-n` to navigate the terminal contents in normal mode and `i` to go back to typing into the terminal.",
+            fun: terminal,
+            signature: CommandSignature::all(completers::filename),
+        },
        TypableCommand {
             name: "reset-diff-change",
             aliases: &["diffget", "diffg"],
@@ -2720,6 +3410,34 @@ fn reset_diff_change(
             fun: reset_diff_change,
             signature: CommandSignature::none(),
         },
+        TypableCommand {
+            name: "quickfix",
+            aliases: &["qf"],
+            doc: "Show or hide the quickfix panel.",
+            fun: quickfix,
+            signature: CommandSignature::none(),
+        },
+        TypableCommand {
+            name: "debug-panels",
+            aliases: &[],
+            doc: "Show or hide the debugger panels with the variables, watches, call stack and breakpoints.",
+            fun: debug_panels,
+            signature: CommandSignature::none(),
+        },
+        TypableCommand {
+            name: "debug-console",
+            aliases: &[],
+            doc: "Open the debug console buffer with the output of the debugger. Shows only the output of the given category, such as `stdout` or `stderr`, if one is given, or all output with `all`.",
+            fun: debug_console,
+            signature: CommandSignature::none(),
+        },
+        TypableCommand {
+            name: "quickfix-from-buffer",
+            aliases: &["qfb"],
+            doc: "Fill the quickfix list with the locations in the current buffer, parsed with the `error-formats` patterns.",
+            fun: quickfix_from_buffer,
+            signature: CommandSignature::none(),
+        },
     ];
 
 pub static TYPABLE_COMMAND_MAP: Lazy<HashMap<&'static str, &'static TypableCommand>> =
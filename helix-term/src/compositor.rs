@@ -16,7 +16,7 @@ pub enum EventResult {
 }
 
 use crate::job::Jobs;
-use helix_view::Editor;
+use helix_view::{workspace::PickerState, Editor};
 
 pub use helix_view::input::Event;
 
@@ -72,6 +72,10 @@ fn type_name(&self) -> &'static str {
     fn id(&self) -> Option<&'static str> {
         None
     }
+
+    /// Applies the query and cursor a picker was closed with in a previous session, for
+    /// components which are pickers.
+    fn restore_state(&mut self, _state: &PickerState, _editor: &Editor) {}
 }
 
 pub struct Compositor {
@@ -184,4 +184,39 @@ fn keys_resolve_to_correct_defaults() {
         let default_keys = Config::default().keys;
         assert_eq!(default_keys, keymap::default());
     }
+
+    #[test]
+    fn parsing_statusline_items() {
+        use helix_view::editor::{StatusLineElement, StatusLineItem};
+
+        let config = Config::load_test(
+            r#"
+            [editor.statusline]
+            left = ["mode", { format = "{?recording:rec @{recording}}" }]
+            right = [{ command = "date +%R", interval = 30 }]
+            "#,
+        );
+        let statusline = config.editor.statusline;
+        assert_eq!(
+            statusline.left[0],
+            StatusLineItem::Element(StatusLineElement::Mode)
+        );
+        assert!(matches!(
+            &statusline.left[1],
+            StatusLineItem::Format { format } if format.source() == "{?recording:rec @{recording}}"
+        ));
+        assert_eq!(
+            statusline.right,
+            [StatusLineItem::Command {
+                command: "date +%R".to_string(),
+                interval: 30
+            }]
+        );
+
+        let load =
+            |config: &str| Config::load(Ok(config.to_owned()), Err(ConfigLoadError::default()));
+        assert!(load("editor.statusline.left = [\"unknown\"]").is_err());
+        assert!(load("editor.statusline.left = [{ format = \"{mode\" }]").is_err());
+        assert!(load("editor.statusline.left = [{ format = \"\", command = \"\" }]").is_err());
+    }
 }
@@ -192,11 +192,13 @@ pub fn languages_all() -> std::io::Result<()> {
     for lang in &syn_loader_conf.language {
         column(&lang.language_id, Color::Reset);
 
-        let lsp = lang
-            .language_server
-            .as_ref()
-            .map(|lsp| lsp.command.to_string());
-        check_binary(lsp);
+        match &lang.language_server {
+            // servers connected to over a socket run outside of Helix
+            Some(lsp) if lsp.command.is_empty() => {
+                column(lsp.socket.as_deref().unwrap_or_default(), Color::Reset)
+            }
+            lsp => check_binary(lsp.as_ref().map(|lsp| lsp.command.to_string())),
+        }
 
         let dap = lang.debugger.as_ref().map(|dap| dap.command.to_string());
         check_binary(dap);
@@ -264,12 +266,17 @@ pub fn language(lang_str: String) -> std::io::Result<()> {
         }
     };
 
-    probe_protocol(
-        "language server",
-        lang.language_server
-            .as_ref()
-            .map(|lsp| lsp.command.to_string()),
-    )?;
+    match &lang.language_server {
+        Some(lsp) if lsp.command.is_empty() => writeln!(
+            stdout,
+            "Configured language server: socket {}",
+            lsp.socket.as_deref().unwrap_or_default().green()
+        )?,
+        lsp => probe_protocol(
+            "language server",
+            lsp.as_ref().map(|lsp| lsp.command.to_string()),
+        )?,
+    }
 
     probe_protocol(
         "debug adapter",
@@ -4,6 +4,7 @@
 
 use futures_util::future::{BoxFuture, Future, FutureExt};
 use futures_util::stream::{FuturesUnordered, StreamExt};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
 pub type EditorCompositorCallback = Box<dyn FnOnce(&mut Editor, &mut Compositor) + Send>;
 pub type EditorCallback = Box<dyn FnOnce(&mut Editor) + Send>;
@@ -21,11 +22,14 @@ pub struct Job {
     pub wait: bool,
 }
 
-#[derive(Default)]
 pub struct Jobs {
     pub futures: FuturesUnordered<JobFuture>,
     /// These are the ones that need to complete before we exit.
     pub wait_futures: FuturesUnordered<JobFuture>,
+    /// Callbacks sent by running jobs, for jobs which update the editor while they run
+    /// rather than once they're done.
+    pub callbacks: UnboundedReceiver<Callback>,
+    callback_sender: UnboundedSender<Callback>,
 }
 
 impl Job {
@@ -51,11 +55,28 @@ pub fn wait_before_exiting(mut self) -> Self {
     }
 }
 
+impl Default for Jobs {
+    fn default() -> Self {
+        let (callback_sender, callbacks) = unbounded_channel();
+        Self {
+            futures: FuturesUnordered::new(),
+            wait_futures: FuturesUnordered::new(),
+            callbacks,
+            callback_sender,
+        }
+    }
+}
+
 impl Jobs {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Returns a sender for callbacks which are run as soon as the event loop receives them.
+    pub fn callback_sender(&self) -> UnboundedSender<Callback> {
+        self.callback_sender.clone()
+    }
+
     pub fn spawn<F: Future<Output = anyhow::Result<()>> + Send + 'static>(&mut self, f: F) {
         self.add(Job::new(f));
     }
@@ -87,6 +87,11 @@ pub fn default() -> HashMap<Mode, Keymap> {
         "A-i" | "A-down" => shrink_selection,
         "A-p" | "A-left" => select_prev_sibling,
         "A-n" | "A-right" => select_next_sibling,
+        "A-P" => swap_prev_sibling,
+        "A-N" => swap_next_sibling,
+        "A-O" => raise_node,
+        "A-I" => splice_node,
+        "A-W" => wrap_selections,
 
         "%" => select_all,
         "x" => extend_line_below,
@@ -112,6 +117,8 @@ pub fn default() -> HashMap<Mode, Keymap> {
             "c" => goto_prev_comment,
             "T" => goto_prev_test,
             "p" => goto_prev_paragraph,
+            "q" => goto_prev_quickfix,
+            "Q" => goto_first_quickfix,
             "space" => add_newline_above,
         },
         "]" => { "Right bracket"
@@ -125,6 +132,8 @@ pub fn default() -> HashMap<Mode, Keymap> {
             "c" => goto_next_comment,
             "T" => goto_next_test,
             "p" => goto_next_paragraph,
+            "q" => goto_next_quickfix,
+            "Q" => goto_last_quickfix,
             "space" => add_newline_below,
         },
 
@@ -158,6 +167,10 @@ pub fn default() -> HashMap<Mode, Keymap> {
 
         "," => keep_primary_selection,
         "A-," => remove_primary_selection,
+        "C-n" => select_next_occurrence,
+        "C-p" => select_prev_occurrence,
+        "C-A-n" => skip_next_occurrence,
+        "C-A-p" => skip_prev_occurrence,
 
         // "q" => record_macro,
         // "Q" => replay_macro,
@@ -221,6 +234,7 @@ pub fn default() -> HashMap<Mode, Keymap> {
             "D" => workspace_diagnostics_picker,
             "a" => code_action,
             "'" => last_picker,
+            "C" => toggle_block_comments,
             "g" => { "Debug (experimental)" sticky=true
                 "l" => dap_launch,
                 "r" => dap_restart,
@@ -230,17 +244,28 @@ pub fn default() -> HashMap<Mode, Keymap> {
                 "i" => dap_step_in,
                 "o" => dap_step_out,
                 "n" => dap_next,
+                "A-n" => dap_next_instruction,
+                "A-i" => dap_step_in_instruction,
+                "N" => dap_step_back,
+                "C" => dap_reverse_continue,
+                "u" => dap_run_to_cursor,
+                "j" => dap_goto_cursor,
                 "v" => dap_variables,
+                "p" => dap_panels,
+                "R" => dap_repl,
+                "d" => dap_disassemble,
                 "t" => dap_terminate,
                 "C-c" => dap_edit_condition,
                 "C-l" => dap_edit_log,
                 "s" => { "Switch"
                     "t" => dap_switch_thread,
                     "f" => dap_switch_stack_frame,
+                    "s" => dap_switch_session,
                     // sl, sb
                 },
                 "e" => dap_enable_exceptions,
                 "E" => dap_disable_exceptions,
+                "x" => dap_exception_filters,
             },
             "w" => { "Window"
                 "C-w" | "w" => rotate_view,
@@ -0,0 +1,186 @@
+use helix_core::{
+    textobject::{self, Scope},
+    unicode::width::UnicodeWidthStr,
+};
+use helix_view::{document::SCRATCH_BUFFER_NAME, graphics::Rect, Document, Editor, View};
+use tui::buffer::Buffer as Surface;
+
+const SEPARATOR: &str = " › ";
+const ELLIPSIS: &str = "…";
+
+/// A part of the breadcrumbs header, with the position a click on it jumps to.
+struct Crumb {
+    area: Rect,
+    text: String,
+    target: Option<usize>,
+}
+
+/// The classes and functions enclosing `pos` in the document, outermost first.
+pub fn scopes(doc: &Document, pos: usize) -> Vec<Scope> {
+    match doc.language_config().zip(doc.syntax()) {
+        Some((lang_config, syntax)) => textobject::enclosing_scopes(
+            doc.text().slice(..),
+            pos,
+            syntax.tree().root_node(),
+            lang_config,
+        ),
+        None => Vec::new(),
+    }
+}
+
+/// Lays out the file name and the scopes enclosing the primary cursor in the view's header.
+/// When they don't fit, the outermost parts are replaced by an ellipsis.
+fn crumbs(doc: &Document, view: &View) -> Vec<Crumb> {
+    if view.header_height() == 0 {
+        return Vec::new();
+    }
+
+    let text = doc.text().slice(..);
+    let cursor = doc.selection(view.id).primary().cursor(text);
+    let name = doc
+        .relative_path()
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|| SCRATCH_BUFFER_NAME.to_string());
+    let mut parts: Vec<_> = std::iter::once((name, None))
+        .chain(
+            scopes(doc, cursor)
+                .into_iter()
+                .map(|scope| (scope.name, Some(scope.start))),
+        )
+        .collect();
+
+    // leave a column of padding on each side
+    let available = view.area.width.saturating_sub(2) as usize;
+    let width = |parts: &[(String, Option<usize>)]| {
+        parts.iter().map(|(text, _)| text.width()).sum::<usize>()
+            + parts.len().saturating_sub(1) * SEPARATOR.width()
+    };
+    let mut truncated = false;
+    while parts.len() > 1 && width(&parts) > available {
+        if !truncated {
+            parts[0] = (ELLIPSIS.to_string(), None);
+            truncated = true;
+        } else if parts.len() > 2 {
+            parts.remove(1);
+        } else {
+            break;
+        }
+    }
+
+    let mut x = view.area.x + 1;
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(i, (text, target))| {
+            if i > 0 {
+                x = x.saturating_add(SEPARATOR.width() as u16);
+            }
+            let width = text.width() as u16;
+            let area = Rect::new(x, view.area.y, width, 1);
+            x = x.saturating_add(width);
+            Crumb { area, text, target }
+        })
+        .collect()
+}
+
+/// Renders the breadcrumbs header of a view.
+pub fn render(editor: &Editor, doc: &Document, view: &View, surface: &mut Surface) {
+    let header_height = view.header_height();
+    if header_height == 0 {
+        return;
+    }
+
+    let theme = &editor.theme;
+    let style = theme
+        .try_get("ui.breadcrumbs")
+        .unwrap_or_else(|| theme.get("ui.statusline.inactive"));
+    let area = Rect::new(view.area.x, view.area.y, view.area.width, header_height);
+    surface.clear_with(area, style);
+
+    let right = area.right().saturating_sub(1);
+    for (i, crumb) in crumbs(doc, view).iter().enumerate() {
+        if i > 0 {
+            let x = crumb.area.x.saturating_sub(SEPARATOR.width() as u16);
+            let width = right.saturating_sub(x) as usize;
+            surface.set_stringn(x, area.y, SEPARATOR, width, style);
+        }
+        let width = right.saturating_sub(crumb.area.x) as usize;
+        surface.set_stringn(crumb.area.x, area.y, &crumb.text, width, style);
+    }
+}
+
+/// The lines pinned at the top of the view: the first lines of the scopes enclosing its first
+/// line, innermost last, leaving the row of the cursor uncovered. Returns each line with the
+/// position a click on it jumps to.
+fn sticky_lines(doc: &Document, view: &View) -> Vec<(usize, usize)> {
+    let text = doc.text().slice(..);
+    let top = text.char_to_line(view.offset.anchor.min(text.len_chars()));
+    if top == 0 {
+        return Vec::new();
+    }
+
+    let mut lines: Vec<(usize, usize)> = Vec::new();
+    for scope in scopes(doc, text.line_to_char(top)) {
+        let line = text.char_to_line(scope.start);
+        if line >= top {
+            continue;
+        }
+        // keep the innermost of the scopes starting on the same line
+        match lines.last_mut() {
+            Some(last) if last.0 == line => *last = (line, scope.start),
+            _ => lines.push((line, scope.start)),
+        }
+    }
+
+    // cover at most a third of the view, and never the cursor
+    let cursor = doc.selection(view.id).primary().cursor(text);
+    let cursor_row = view
+        .screen_coords_at_pos(doc, text, cursor)
+        .map_or(usize::MAX, |pos| pos.row);
+    let max = (view.inner_height() / 3).min(cursor_row);
+    let skip = lines.len().saturating_sub(max);
+    lines.drain(..skip);
+    lines
+}
+
+/// Renders the first lines of the scopes enclosing the top of the view over its first rows.
+pub fn render_sticky(editor: &Editor, doc: &Document, view: &View, surface: &mut Surface) {
+    let theme = &editor.theme;
+    let style = theme
+        .try_get("ui.sticky-scroll")
+        .unwrap_or_else(|| theme.get("ui.cursorline.primary"));
+    let inner = view.inner_area(doc);
+    let tab = " ".repeat(doc.tab_width());
+    let text = doc.text().slice(..);
+
+    for (row, (line, _)) in sticky_lines(doc, view).into_iter().enumerate() {
+        let y = inner.y + row as u16;
+        surface.clear_with(Rect::new(view.area.x, y, view.area.width, 1), style);
+        let content = text.line(line).to_string().replace('\t', &tab);
+        let content = content.trim_end_matches(['\n', '\r']);
+        surface.set_stringn(inner.x, y, content, inner.width as usize, style);
+    }
+}
+
+/// The position a click on the breadcrumbs or sticky lines of a view jumps to.
+pub fn click_target(editor: &Editor, view: &View, row: u16, column: u16) -> Option<usize> {
+    if column < view.area.left() || column >= view.area.right() {
+        return None;
+    }
+    let doc = editor.document(view.doc)?;
+
+    if view.header_height() > 0 && row == view.area.y {
+        return crumbs(doc, view)
+            .into_iter()
+            .find(|crumb| (crumb.area.left()..crumb.area.right()).contains(&column))
+            .and_then(|crumb| crumb.target);
+    }
+
+    if editor.config().sticky_scroll {
+        let inner = view.inner_area(doc);
+        let row = row.checked_sub(inner.y)? as usize;
+        return sticky_lines(doc, view).get(row).map(|&(_, target)| target);
+    }
+
+    None
+}
@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+
+use helix_core::{diagnostic::Severity, unicode::width::UnicodeWidthStr};
+use helix_view::{
+    document::SCRATCH_BUFFER_NAME, graphics::Rect, theme::Style, view, Document, DocumentId, Editor,
+};
+use tui::buffer::Buffer as Surface;
+
+/// Renders the open buffers in bufferline order, scrolled so that the current buffer is visible.
+/// Returns the area of each rendered buffer, for mouse handling.
+pub fn render(editor: &Editor, viewport: Rect, surface: &mut Surface) -> Vec<(Rect, DocumentId)> {
+    let theme = &editor.theme;
+    surface.clear_with(
+        viewport,
+        theme
+            .try_get("ui.bufferline.background")
+            .unwrap_or_else(|| theme.get("ui.statusline")),
+    );
+
+    let bufferline_active = theme
+        .try_get("ui.bufferline.active")
+        .unwrap_or_else(|| theme.get("ui.statusline.active"));
+
+    let bufferline_inactive = theme
+        .try_get("ui.bufferline")
+        .unwrap_or_else(|| theme.get("ui.statusline.inactive"));
+
+    let current_doc = view!(editor).doc;
+    let docs: Vec<_> = editor.buffers().collect();
+    let paths: Vec<_> = docs.iter().map(|doc| doc.relative_path()).collect();
+    let labels: Vec<_> = docs
+        .iter()
+        .zip(buffer_names(&paths))
+        .map(|(doc, name)| {
+            let style = if doc.id() == current_doc {
+                bufferline_active
+            } else {
+                bufferline_inactive
+            };
+            label(editor, doc, name, style)
+        })
+        .collect();
+    let widths: Vec<usize> = labels
+        .iter()
+        .map(|parts| parts.iter().map(|(text, _)| text.width()).sum())
+        .collect();
+
+    // When the buffers don't fit, show as many buffers before the current one as possible and
+    // reserve a column on each side for the overflow markers.
+    let overflows = widths.iter().sum::<usize>() > viewport.width as usize;
+    let mut first = 0;
+    if overflows {
+        let available = viewport.width.saturating_sub(2) as usize;
+        first = docs
+            .iter()
+            .position(|doc| doc.id() == current_doc)
+            .unwrap_or_default();
+        let mut width = widths[first];
+        while first > 0 && width + widths[first - 1] <= available {
+            first -= 1;
+            width += widths[first];
+        }
+    }
+
+    let mut x = viewport.x;
+    let right = if overflows {
+        viewport.right().saturating_sub(1)
+    } else {
+        viewport.right()
+    };
+    if first > 0 {
+        surface.set_string(x, viewport.y, "<", bufferline_inactive);
+        x += 1;
+    }
+
+    let mut areas = Vec::new();
+    let mut hidden = false;
+    for (index, parts) in labels.iter().enumerate().skip(first) {
+        if x >= right {
+            hidden = true;
+            break;
+        }
+        hidden = x as usize + widths[index] > right as usize;
+
+        let start = x;
+        for (text, style) in parts {
+            let width = right.saturating_sub(x) as usize;
+            x = surface.set_stringn(x, viewport.y, text, width, *style).0;
+        }
+        areas.push((Rect::new(start, viewport.y, x - start, 1), docs[index].id()));
+    }
+    if hidden {
+        surface.set_string(right, viewport.y, ">", bufferline_inactive);
+    }
+
+    areas
+}
+
+/// The parts of a buffer's label with their styles: its name, markers for pinned and modified
+/// buffers, and a dot colored by the most severe diagnostic.
+fn label(editor: &Editor, doc: &Document, name: String, style: Style) -> Vec<(String, Style)> {
+    let pin = if editor.is_pinned(doc.id()) {
+        "📌 "
+    } else {
+        ""
+    };
+    let modified = if doc.is_modified() { "[+]" } else { "" };
+    let mut parts = vec![(format!(" {}{}{}", pin, name, modified), style)];
+
+    let severity = doc
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.severity.unwrap_or(Severity::Error))
+        .max();
+    if let Some(severity) = severity {
+        let scope = match severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+            Severity::Hint => "hint",
+        };
+        parts.push((" ●".to_string(), style.patch(editor.theme.get(scope))));
+    }
+
+    parts.push((" ".to_string(), style));
+    parts
+}
+
+/// File names of the buffers, extended with parent directories until they are unique.
+fn buffer_names(paths: &[Option<PathBuf>]) -> Vec<String> {
+    let components: Vec<usize> = paths
+        .iter()
+        .map(|path| path.as_ref().map_or(0, |path| path.components().count()))
+        .collect();
+    let name = |index: usize, depth: usize| match &paths[index] {
+        Some(path) => path_suffix(path, depth),
+        None => SCRATCH_BUFFER_NAME.to_string(),
+    };
+
+    let mut depths = vec![1; paths.len()];
+    loop {
+        let names: Vec<_> = (0..paths.len()).map(|i| name(i, depths[i])).collect();
+        let mut extended = false;
+        for i in 0..paths.len() {
+            let ambiguous =
+                (0..paths.len()).any(|j| j != i && paths[j].is_some() && names[j] == names[i]);
+            if paths[i].is_some() && ambiguous && depths[i] < components[i] {
+                depths[i] += 1;
+                extended = true;
+            }
+        }
+        if !extended {
+            return names;
+        }
+    }
+}
+
+/// The last `depth` components of the path.
+fn path_suffix(path: &Path, depth: usize) -> String {
+    let components: Vec<_> = path.components().collect();
+    components[components.len().saturating_sub(depth)..]
+        .iter()
+        .collect::<PathBuf>()
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_buffer_names() {
+        let paths = [
+            Some(PathBuf::from("src/ui/mod.rs")),
+            Some(PathBuf::from("src/commands/mod.rs")),
+            Some(PathBuf::from("lib.rs")),
+            None,
+            Some(PathBuf::from("a/x/lib.rs")),
+        ];
+        let names = buffer_names(&paths);
+        assert_eq!(names[0], path_suffix(Path::new("ui/mod.rs"), 2));
+        assert_eq!(names[1], path_suffix(Path::new("commands/mod.rs"), 2));
+        // a name can't be extended further than its path
+        assert_eq!(names[2], "lib.rs");
+        assert_eq!(names[3], SCRATCH_BUFFER_NAME);
+        assert_eq!(names[4], path_suffix(Path::new("x/lib.rs"), 2));
+    }
+
+    #[test]
+    fn test_path_suffix() {
+        let path = Path::new("src/ui/mod.rs");
+        assert_eq!(path_suffix(path, 1), "mod.rs");
+        assert_eq!(
+            path_suffix(path, 2),
+            Path::new("ui/mod.rs").to_string_lossy()
+        );
+        assert_eq!(
+            path_suffix(path, 5),
+            Path::new("src/ui/mod.rs").to_string_lossy()
+        );
+    }
+}
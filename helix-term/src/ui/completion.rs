@@ -7,16 +7,41 @@
 };
 use tui::{buffer::Buffer as Surface, text::Span};
 
-use std::{borrow::Cow, sync::Arc};
+use std::{borrow::Cow, collections::HashSet, path::Path, sync::Arc};
 
-use helix_core::{Change, Transaction};
-use helix_view::{graphics::Rect, Document, Editor};
+use helix_core::{
+    completion::{self, CompletionSource},
+    Change, Transaction,
+};
+use helix_view::{graphics::Rect, Document, DocumentId, Editor};
 
 use crate::commands;
 use crate::ui::{menu, Markdown, Menu, Popup, PromptEvent};
 
 use helix_lsp::{lsp, util};
-use lsp::CompletionItem;
+
+/// The maximum number of directory entries offered by the path source.
+const MAX_PATH_ITEMS: usize = 1000;
+
+/// A completion item along with the source it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletionItem {
+    pub item: lsp::CompletionItem,
+    pub source: CompletionSource,
+    /// The range to replace relative to the primary cursor, for items which replace
+    /// more than the word before the cursor.
+    pub edit_offset: Option<(i128, i128)>,
+}
+
+impl CompletionItem {
+    pub fn new(item: lsp::CompletionItem, source: CompletionSource) -> Self {
+        Self {
+            item,
+            source,
+            edit_offset: None,
+        }
+    }
+}
 
 impl menu::Item for CompletionItem {
     type Data = ();
@@ -26,28 +51,23 @@ fn sort_text(&self, data: &Self::Data) -> Cow<str> {
 
     #[inline]
     fn filter_text(&self, _data: &Self::Data) -> Cow<str> {
-        self.filter_text
+        self.item
+            .filter_text
             .as_ref()
-            .unwrap_or(&self.label)
+            .unwrap_or(&self.item.label)
             .as_str()
             .into()
     }
 
     fn format(&self, _data: &Self::Data) -> menu::Row {
-        let deprecated = self.deprecated.unwrap_or_default()
-            || self.tags.as_ref().map_or(false, |tags| {
+        let item = &self.item;
+        let deprecated = item.deprecated.unwrap_or_default()
+            || item.tags.as_ref().map_or(false, |tags| {
                 tags.contains(&lsp::CompletionItemTag::DEPRECATED)
             });
-        menu::Row::new(vec![
-            menu::Cell::from(Span::styled(
-                self.label.as_str(),
-                if deprecated {
-                    Style::default().add_modifier(Modifier::CROSSED_OUT)
-                } else {
-                    Style::default()
-                },
-            )),
-            menu::Cell::from(match self.kind {
+        let kind = match self.source {
+            CompletionSource::Buffer | CompletionSource::Register => self.source.as_str(),
+            _ => match item.kind {
                 Some(lsp::CompletionItemKind::TEXT) => "text",
                 Some(lsp::CompletionItemKind::METHOD) => "method",
                 Some(lsp::CompletionItemKind::FUNCTION) => "function",
@@ -78,7 +98,18 @@ fn format(&self, _data: &Self::Data) -> menu::Row {
                     ""
                 }
                 None => "",
-            }),
+            },
+        };
+        menu::Row::new(vec![
+            menu::Cell::from(Span::styled(
+                item.label.as_str(),
+                if deprecated {
+                    Style::default().add_modifier(Modifier::CROSSED_OUT)
+                } else {
+                    Style::default()
+                },
+            )),
+            menu::Cell::from(kind),
             // self.detail.as_deref().unwrap_or("")
             // self.label_details
             //     .as_ref()
@@ -88,9 +119,174 @@ fn format(&self, _data: &Self::Data) -> menu::Row {
     }
 }
 
+/// Collects completion items for the primary cursor from the built-in `sources`.
+pub fn builtin_completion_items(
+    editor: &Editor,
+    sources: &[CompletionSource],
+) -> Vec<CompletionItem> {
+    let (view, doc) = current_ref!(editor);
+    let text = doc.text().slice(..);
+    let cursor = doc.selection(view.id).primary().cursor(text);
+
+    let mut items = Vec::new();
+    for source in sources {
+        match source {
+            // requested asynchronously by `commands::completion`
+            CompletionSource::LanguageServer => (),
+            CompletionSource::Snippet => items.extend(snippet_items(doc)),
+            CompletionSource::Path => items.extend(path_items(doc, cursor)),
+            CompletionSource::Buffer => items.extend(buffer_items(editor, doc.id(), cursor)),
+            CompletionSource::Register => items.extend(register_items(editor)),
+        }
+    }
+    items
+}
+
+fn snippet_items(doc: &Document) -> Vec<CompletionItem> {
+    let snippets = match doc.language_config() {
+        Some(config) => &config.snippets,
+        None => return Vec::new(),
+    };
+
+    snippets
+        .iter()
+        .map(|(trigger, body)| {
+            let item = lsp::CompletionItem {
+                label: trigger.clone(),
+                kind: Some(lsp::CompletionItemKind::SNIPPET),
+                insert_text: Some(body.clone()),
+                insert_text_format: Some(lsp::InsertTextFormat::SNIPPET),
+                documentation: Some(lsp::Documentation::String(body.clone())),
+                ..Default::default()
+            };
+            CompletionItem::new(item, CompletionSource::Snippet)
+        })
+        .collect()
+}
+
+fn path_items(doc: &Document, cursor: usize) -> Vec<CompletionItem> {
+    let text = doc.text().slice(..);
+    let path = match completion::path_before(text, cursor) {
+        Some((_, path)) => path,
+        None => return Vec::new(),
+    };
+    // `path_before` only returns paths containing a `/`
+    let (dir, typed) = path.rsplit_once('/').unwrap();
+    let dir = helix_core::path::expand_tilde(Path::new(if dir.is_empty() { "/" } else { dir }));
+
+    // relative paths are looked up next to the document first, then in the workspace
+    let dir = if dir.is_absolute() {
+        dir
+    } else {
+        let doc_dir = doc
+            .path()
+            .and_then(|path| path.parent())
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::current_dir().ok());
+        let (workspace, _) = helix_loader::find_workspace();
+        match doc_dir
+            .into_iter()
+            .chain(std::iter::once(workspace))
+            .map(|base| base.join(&dir))
+            .find(|dir| dir.is_dir())
+        {
+            Some(dir) => dir,
+            None => return Vec::new(),
+        }
+    };
+
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let edit_offset = Some((-(typed.chars().count() as i128), 0));
+
+    entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().into_string().ok()?;
+            // hidden files are only offered once a `.` is typed
+            if name.starts_with('.') && !typed.starts_with('.') {
+                return None;
+            }
+            let is_dir = entry.path().is_dir();
+            let item = lsp::CompletionItem {
+                label: if is_dir { format!("{}/", name) } else { name },
+                kind: Some(if is_dir {
+                    lsp::CompletionItemKind::FOLDER
+                } else {
+                    lsp::CompletionItemKind::FILE
+                }),
+                ..Default::default()
+            };
+            Some(CompletionItem {
+                edit_offset,
+                ..CompletionItem::new(item, CompletionSource::Path)
+            })
+        })
+        .take(MAX_PATH_ITEMS)
+        .collect()
+}
+
+fn buffer_items(editor: &Editor, doc_id: DocumentId, cursor: usize) -> Vec<CompletionItem> {
+    let typed = completion::word_at(doc!(editor, &doc_id).text().slice(..), cursor);
+    let mut words = HashSet::new();
+    for doc in editor.documents() {
+        for (word, &count) in doc.words().iter() {
+            // the word being completed isn't offered unless it's also typed elsewhere
+            if doc.id() == doc_id && count == 1 && typed.as_ref() == Some(word) {
+                continue;
+            }
+            if !words.contains(word) {
+                words.insert(word.clone());
+            }
+        }
+    }
+
+    words
+        .into_iter()
+        .map(|word| {
+            let item = lsp::CompletionItem {
+                label: word,
+                kind: Some(lsp::CompletionItemKind::TEXT),
+                ..Default::default()
+            };
+            CompletionItem::new(item, CompletionSource::Buffer)
+        })
+        .collect()
+}
+
+fn register_items(editor: &Editor) -> Vec<CompletionItem> {
+    let mut seen = HashSet::new();
+    let mut registers: Vec<_> = editor.registers.inner().values().collect();
+    registers.sort_by_key(|register| register.name());
+
+    registers
+        .into_iter()
+        .flat_map(|register| register.read().iter())
+        .filter(|value| !value.trim().is_empty() && seen.insert(value.as_str()))
+        .map(|value| {
+            // multi-line values are shown by their first line
+            let label = value.lines().next().unwrap_or_default().to_string();
+            let documentation =
+                (label != *value).then(|| lsp::Documentation::String(value.clone()));
+            let item = lsp::CompletionItem {
+                label,
+                kind: Some(lsp::CompletionItemKind::TEXT),
+                insert_text: Some(value.clone()),
+                documentation,
+                ..Default::default()
+            };
+            CompletionItem::new(item, CompletionSource::Register)
+        })
+        .collect()
+}
+
 /// Wraps a Menu.
 pub struct Completion {
     popup: Popup<Menu<CompletionItem>>,
+    /// The enabled completion sources, in order of their ranking.
+    sources: Vec<CompletionSource>,
     start_offset: usize,
     #[allow(dead_code)]
     trigger_offset: usize,
@@ -109,8 +305,9 @@ pub fn new(
         trigger_offset: usize,
     ) -> Self {
         let replace_mode = editor.config().completion_replace;
+        let sources = doc!(editor).completion_sources();
         // Sort completion items according to their preselect status (given by the LSP server)
-        items.sort_by_key(|item| !item.preselect.unwrap_or(false));
+        items.sort_by_key(|item| !item.item.preselect.unwrap_or(false));
 
         // Then create the menu
         let menu = Menu::new(items, (), move |editor: &mut Editor, item, event| {
@@ -127,8 +324,16 @@ fn item_to_transaction(
                 let selection = doc.selection(view_id);
                 let text = doc.text().slice(..);
                 let primary_cursor = selection.primary().cursor(text);
+                let item_edit_offset = item.edit_offset;
+                let item = &item.item;
 
-                let (edit_offset, new_text) = if let Some(edit) = &item.text_edit {
+                let (edit_offset, new_text) = if let Some(edit_offset) = item_edit_offset {
+                    let new_text = item
+                        .insert_text
+                        .clone()
+                        .unwrap_or_else(|| item.label.clone());
+                    (Some(edit_offset), new_text)
+                } else if let Some(edit) = &item.text_edit {
                     let edit = match edit {
                         lsp::CompletionTextEdit::Edit(edit) => edit.clone(),
                         lsp::CompletionTextEdit::InsertAndReplace(item) => {
@@ -260,11 +465,14 @@ fn completion_changes(transaction: &Transaction, trigger_offset: usize) -> Vec<C
                     });
 
                     // apply additional edits, mostly used to auto import unqualified types
-                    let resolved_item = if item
-                        .additional_text_edits
-                        .as_ref()
-                        .map(|edits| !edits.is_empty())
-                        .unwrap_or(false)
+                    let source = item.source;
+                    let item = &item.item;
+                    let resolved_item = if source != CompletionSource::LanguageServer
+                        || item
+                            .additional_text_edits
+                            .as_ref()
+                            .map(|edits| !edits.is_empty())
+                            .unwrap_or(false)
                     {
                         None
                     } else {
@@ -293,6 +501,7 @@ fn completion_changes(transaction: &Transaction, trigger_offset: usize) -> Vec<C
             .ignore_escape_key(true);
         let mut completion = Self {
             popup,
+            sources,
             start_offset,
             trigger_offset,
         };
@@ -306,7 +515,7 @@ fn completion_changes(transaction: &Transaction, trigger_offset: usize) -> Vec<C
     fn resolve_completion_item(
         doc: &Document,
         completion_item: lsp::CompletionItem,
-    ) -> Option<CompletionItem> {
+    ) -> Option<lsp::CompletionItem> {
         let language_server = doc.language_server()?;
 
         let future = language_server.resolve_completion_item(completion_item)?;
@@ -344,6 +553,11 @@ pub fn recompute_filter(&mut self, editor: &Editor) {
             let text = Cow::from(fragment);
             // TODO: logic is same as ui/picker
             menu.score(&text);
+            // rank by source first, keeping the fuzzy match order within a source
+            let sources = &self.sources;
+            menu.sort_matches_by_key(|item| {
+                sources.iter().position(|source| *source == item.source)
+            });
         } else {
             // we backspaced before the start offset, clear the menu
             // this will cause the editor to remove the completion popup
@@ -359,7 +573,7 @@ pub fn is_empty(&self) -> bool {
         self.popup.contents().is_empty()
     }
 
-    fn replace_item(&mut self, old_item: lsp::CompletionItem, new_item: lsp::CompletionItem) {
+    fn replace_item(&mut self, old_item: CompletionItem, new_item: CompletionItem) {
         self.popup.contents_mut().replace_option(old_item, new_item);
     }
 
@@ -375,7 +589,12 @@ pub fn ensure_item_resolved(&mut self, cx: &mut commands::Context) -> bool {
         // > The returned completion item should have the documentation property filled in.
         // https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_completion
         let current_item = match self.popup.contents().selection() {
-            Some(item) if item.documentation.is_none() => item.clone(),
+            Some(item)
+                if item.source == CompletionSource::LanguageServer
+                    && item.item.documentation.is_none() =>
+            {
+                item.clone()
+            }
             _ => return false,
         };
 
@@ -385,7 +604,7 @@ pub fn ensure_item_resolved(&mut self, cx: &mut commands::Context) -> bool {
         };
 
         // This method should not block the compositor so we handle the response asynchronously.
-        let future = match language_server.resolve_completion_item(current_item.clone()) {
+        let future = match language_server.resolve_completion_item(current_item.item.clone()) {
             Some(future) => future,
             None => return false,
         };
@@ -403,6 +622,10 @@ pub fn ensure_item_resolved(&mut self, cx: &mut commands::Context) -> bool {
                     .unwrap()
                     .completion
                 {
+                    let resolved_item = CompletionItem {
+                        item: resolved_item,
+                        ..current_item.clone()
+                    };
                     completion.replace_item(current_item, resolved_item);
                 }
             },
@@ -430,7 +653,7 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
 
         // if we have a selection, render a markdown popup on top/below with info
         let option = match self.popup.contents().selection() {
-            Some(option) => option,
+            Some(option) => &option.item,
             None => return,
         };
         // need to render:
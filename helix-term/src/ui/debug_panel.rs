@@ -0,0 +1,470 @@
+use std::path::PathBuf;
+
+use helix_core::Selection;
+use helix_lsp::block_on;
+use helix_view::{
+    align_view,
+    debug_panel::{NodeTree, Row, Section, VariableNode, Watch},
+    editor::{Action, PanelPosition},
+    graphics::Rect,
+    handlers::dap::{
+        breakpoints_changed, data_breakpoints_changed, jump_to_stack_frame, refresh_debug_panels,
+    },
+    input::Event,
+    theme::Style,
+    Align, Editor,
+};
+use tui::buffer::Buffer as Surface;
+
+use crate::{
+    commands::dap,
+    compositor::{self, Compositor, EventResult},
+    key,
+    ui::{self, Prompt, PromptEvent},
+};
+
+/// The narrowest the panels get before they are hidden.
+const MIN_PANEL_WIDTH: u16 = 10;
+
+/// Returns the width of the debugger panels beside the views, which is 0 when the panels are
+/// hidden or there is no room for them in `area`.
+pub fn panel_width(editor: &Editor, area: Rect) -> u16 {
+    if !editor.debug_panels.visible {
+        return 0;
+    }
+    let width = editor.config().debug_panel.width.min(area.width / 2);
+    if width < MIN_PANEL_WIDTH {
+        return 0;
+    }
+    width
+}
+
+/// The area of the panels in `area`, and the area left for the views.
+pub fn split_area(editor: &Editor, area: Rect) -> (Rect, Rect) {
+    let width = panel_width(editor, area);
+    match editor.config().debug_panel.position {
+        PanelPosition::Left => (area.with_width(width), area.clip_left(width)),
+        PanelPosition::Right => (area.clip_left(area.width - width), area.clip_right(width)),
+    }
+}
+
+fn rows(editor: &Editor) -> Vec<Row> {
+    let frames = editor
+        .debuggers
+        .active()
+        .and_then(|debugger| debugger.stack_frames.get(&debugger.thread_id?))
+        .map_or(&[][..], Vec::as_slice);
+    editor.debug_panels.rows(
+        frames,
+        &editor.breakpoints,
+        &editor.function_breakpoints,
+        &editor.data_breakpoints,
+    )
+}
+
+/// Renders the panels with a border towards the views, scrolled so that the selected row is
+/// visible.
+pub fn render_panel(editor: &Editor, area: Rect, surface: &mut Surface) {
+    let theme = &editor.theme;
+    let panels = &editor.debug_panels;
+    surface.clear_with(area, theme.get("ui.background"));
+
+    let border_x = match editor.config().debug_panel.position {
+        PanelPosition::Left => area.right().saturating_sub(1),
+        PanelPosition::Right => area.left(),
+    };
+    let border_style = theme.get("ui.window");
+    for y in area.top()..area.bottom() {
+        surface[(border_x, y)]
+            .set_symbol(tui::symbols::line::VERTICAL)
+            .set_style(border_style);
+    }
+    let area = match editor.config().debug_panel.position {
+        PanelPosition::Left => area.clip_right(1),
+        PanelPosition::Right => area.clip_left(1),
+    };
+
+    let rows = rows(editor);
+    let height = area.height as usize;
+    if height == 0 {
+        return;
+    }
+    let selected = panels.selected.min(rows.len().saturating_sub(1));
+    let offset = selected - selected % height;
+
+    let section_style = theme.get("ui.statusline");
+    let selected_style = theme.get("ui.cursorline.primary");
+    let scope_style = theme.get("ui.linenr.selected");
+    let type_style = theme.get("ui.text");
+    let text_style = theme.get("ui.text.focus");
+    let info_style = theme.get("ui.text.info");
+    let virtual_style = theme.get("ui.virtual");
+    let error_style = theme.get("error");
+
+    for (i, row) in rows.iter().enumerate().skip(offset).take(height) {
+        let y = area.y + (i - offset) as u16;
+        let line = Rect::new(area.x, y, area.width, 1);
+        let mut parts: Vec<(String, _)> = Vec::new();
+
+        match row {
+            Row::Section(section) => {
+                surface.set_style(line, section_style);
+                let marker = if panels.collapsed.contains(section) {
+                    "▸"
+                } else {
+                    "▾"
+                };
+                parts.push((format!("{} {}", marker, section.title()), section_style));
+            }
+            Row::Node { tree, path } => {
+                let node = match panels.node(*tree, path) {
+                    Some(node) => node,
+                    None => continue,
+                };
+                // scopes are the roots of the variables tree, watches have an empty root path
+                let depth = match tree {
+                    NodeTree::Variables => path.len(),
+                    NodeTree::Watch(_) => path.len() + 1,
+                };
+                let marker = match (node.has_children(), node.expanded) {
+                    (false, _) => " ",
+                    (true, false) => "▸",
+                    (true, true) => "▾",
+                };
+                parts.push((format!("{}{} ", "  ".repeat(depth), marker), type_style));
+                if *tree == NodeTree::Variables && path.len() == 1 {
+                    parts.push((node.name.clone(), scope_style));
+                } else {
+                    parts.extend(variable_parts(node, text_style, type_style));
+                }
+            }
+            Row::Watch(i) => {
+                let watch = &panels.watches[*i];
+                parts.push((format!("    {} = ", watch.expression), text_style));
+                match &watch.result {
+                    Some(Err(err)) => parts.push((err.clone(), error_style)),
+                    _ => parts.push(("not available".to_string(), virtual_style)),
+                }
+            }
+            Row::Frame(i) => {
+                let debugger = editor.debuggers.active();
+                let frame = debugger
+                    .and_then(|debugger| debugger.stack_frames.get(&debugger.thread_id?))
+                    .and_then(|frames| frames.get(*i));
+                let frame = match frame {
+                    Some(frame) => frame,
+                    None => continue,
+                };
+                let active = debugger.and_then(|debugger| debugger.active_frame) == Some(*i);
+                let marker = if active { "  → " } else { "    " };
+                parts.push((format!("{}{}", marker, frame.name), text_style));
+                if let Some(path) = frame
+                    .source
+                    .as_ref()
+                    .and_then(|source| source.path.as_ref())
+                {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    parts.push((format!(" {}:{}", name, frame.line), info_style));
+                }
+            }
+            Row::Breakpoint { path, index } => {
+                let breakpoint = &editor.breakpoints[path][*index];
+                let checkbox = if breakpoint.enabled { "[x]" } else { "[ ]" };
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                parts.push((format!("    {} ", checkbox), type_style));
+                parts.push((format!("{}:{}", name, breakpoint.line + 1), info_style));
+                if let Some(condition) = &breakpoint.condition {
+                    parts.push((format!(" if {}", condition), type_style));
+                }
+            }
+            Row::FunctionBreakpoint(i) => {
+                let breakpoint = &editor.function_breakpoints[*i];
+                parts.push((format!("    {}()", breakpoint.name), info_style));
+            }
+            Row::DataBreakpoint(i) => {
+                let breakpoint = &editor.data_breakpoints[*i];
+                parts.push((
+                    format!("    {} changed", breakpoint.description),
+                    info_style,
+                ));
+            }
+            Row::Empty(section) => {
+                let message = match section {
+                    Section::Variables | Section::CallStack => "not stopped",
+                    Section::Watches => "press a to add an expression",
+                    Section::Breakpoints => "no breakpoints",
+                };
+                parts.push((format!("    {}", message), virtual_style));
+            }
+        }
+
+        let mut x = area.x;
+        for (text, style) in parts {
+            let width = area.right().saturating_sub(x) as usize;
+            x = surface.set_stringn(x, y, text, width, style).0;
+        }
+        if panels.focused && i == selected {
+            surface.set_style(line, selected_style);
+        }
+    }
+}
+
+fn variable_parts(
+    node: &VariableNode,
+    text_style: Style,
+    type_style: Style,
+) -> Vec<(String, Style)> {
+    let mut parts = vec![(node.name.clone(), text_style)];
+    if let Some(ty) = &node.ty {
+        parts.push((": ".to_string(), type_style));
+        parts.push((ty.clone(), type_style));
+    }
+    parts.push((" = ".to_string(), type_style));
+    parts.push((node.value.clone(), text_style));
+    parts
+}
+
+/// Handles the keys for the panels while they are focused. Keys the panels don't use are left
+/// to the editor, so that commands can still be run.
+pub fn handle_event(event: &Event, cx: &mut compositor::Context) -> Option<EventResult> {
+    let panels = &cx.editor.debug_panels;
+    if !panels.visible || !panels.focused {
+        return None;
+    }
+    let key = match event {
+        Event::Key(key) => *key,
+        _ => return None,
+    };
+
+    let rows = rows(cx.editor);
+    let selected = panels.selected.min(rows.len().saturating_sub(1));
+    let row = rows.get(selected).cloned();
+
+    match key {
+        key!('j') | key!(Down) => {
+            cx.editor.debug_panels.selected = (selected + 1).min(rows.len().saturating_sub(1))
+        }
+        key!('k') | key!(Up) => cx.editor.debug_panels.selected = selected.saturating_sub(1),
+        key!('q') | key!(Esc) => cx.editor.debug_panels.focused = false,
+        key!(Enter) => return Some(activate(cx.editor, row?)),
+        key!('l') | key!(Right) => set_expanded(cx.editor, row?, true),
+        key!('h') | key!(Left) => set_expanded(cx.editor, row?, false),
+        key!(' ') => {
+            if let Some(Row::Breakpoint { path, index }) = row {
+                toggle_breakpoint(cx.editor, path, index);
+            }
+        }
+        key!('a') => return Some(EventResult::Consumed(Some(watch_prompt(None)))),
+        key!('d') | key!(Delete) => match row? {
+            Row::Watch(i)
+            | Row::Node {
+                tree: NodeTree::Watch(i),
+                ..
+            } => {
+                cx.editor.debug_panels.watches.remove(i);
+            }
+            Row::Breakpoint { path, index } => remove_breakpoint(cx.editor, path, index),
+            Row::FunctionBreakpoint(i) => {
+                let name = cx.editor.function_breakpoints[i].name.clone();
+                if let Err(err) = dap::toggle_function_breakpoint(cx.editor, &name) {
+                    cx.editor.set_error(err.to_string());
+                }
+            }
+            Row::DataBreakpoint(i) => {
+                let breakpoint = cx.editor.data_breakpoints.remove(i);
+                if let Some(debugger) = cx.editor.debuggers.get_mut(breakpoint.session) {
+                    if let Err(err) =
+                        data_breakpoints_changed(debugger, &cx.editor.data_breakpoints)
+                    {
+                        cx.editor.set_error(err.to_string());
+                    }
+                }
+            }
+            _ => {}
+        },
+        key!('w') => {
+            if let Some(Row::Node { tree, path }) = row {
+                toggle_data_breakpoint(cx.editor, tree, &path);
+            }
+        }
+        key!('m') => {
+            if let Some(Row::Node { tree, path }) = row {
+                open_memory(cx.editor, tree, &path);
+            }
+        }
+        _ => return None,
+    }
+    Some(EventResult::Consumed(None))
+}
+
+/// Toggles sections and nodes, jumps to stack frames and breakpoints, and edits watches.
+fn activate(editor: &mut Editor, row: Row) -> EventResult {
+    match row {
+        Row::Section(section) => {
+            let collapsed = &mut editor.debug_panels.collapsed;
+            if !collapsed.remove(&section) {
+                collapsed.insert(section);
+            }
+        }
+        Row::Node { tree, ref path } => {
+            let expanded = editor
+                .debug_panels
+                .node(tree, path)
+                .map_or(false, |node| node.expanded);
+            set_expanded(editor, row, !expanded);
+        }
+        Row::Watch(i) => return EventResult::Consumed(Some(watch_prompt(Some(i)))),
+        Row::Empty(Section::Watches) => return EventResult::Consumed(Some(watch_prompt(None))),
+        Row::Frame(i) => {
+            let debugger = match editor.debuggers.active_mut() {
+                Some(debugger) => debugger,
+                None => return EventResult::Consumed(None),
+            };
+            let frame = debugger
+                .thread_id
+                .and_then(|thread_id| debugger.stack_frames.get(&thread_id)?.get(i))
+                .cloned();
+            if let Some(frame) = frame {
+                debugger.active_frame = Some(i);
+                jump_to_stack_frame(editor, &frame);
+                block_on(refresh_debug_panels(editor));
+                block_on(editor.refresh_debug_values());
+                block_on(editor.refresh_disassembly());
+            }
+        }
+        Row::Breakpoint { path, index } => {
+            let line = editor.breakpoints[&path][index].line;
+            if let Err(err) = editor.open(&path, Action::Replace) {
+                editor.set_error(format!("Failed to open {}: {}", path.display(), err));
+                return EventResult::Consumed(None);
+            }
+            let (view, doc) = current!(editor);
+            let text = doc.text();
+            let pos = text.line_to_char(line.min(text.len_lines().saturating_sub(1)));
+            doc.set_selection(view.id, Selection::point(pos));
+            align_view(doc, view, Align::Center);
+        }
+        Row::FunctionBreakpoint(_) | Row::DataBreakpoint(_) | Row::Empty(_) => {}
+    }
+    EventResult::Consumed(None)
+}
+
+/// Expands or collapses a section or node, fetching the children of a node the first time it is
+/// expanded.
+fn set_expanded(editor: &mut Editor, row: Row, expanded: bool) {
+    let (tree, path) = match row {
+        Row::Section(section) => {
+            let collapsed = &mut editor.debug_panels.collapsed;
+            if expanded {
+                collapsed.remove(&section);
+            } else {
+                collapsed.insert(section);
+            }
+            return;
+        }
+        Row::Node { tree, path } => (tree, path),
+        _ => return,
+    };
+
+    let node = match editor.debug_panels.node_mut(tree, &path) {
+        Some(node) if node.has_children() => node,
+        _ => return,
+    };
+    if expanded && node.children.is_none() {
+        let debugger = match editor.debuggers.active() {
+            Some(debugger) => debugger,
+            None => return,
+        };
+        match block_on(debugger.variables(node.variables_reference)) {
+            Ok(variables) => {
+                node.children = Some(variables.into_iter().map(VariableNode::from).collect())
+            }
+            Err(err) => {
+                editor.set_error(format!("Failed to get variables: {}", err));
+                return;
+            }
+        }
+    }
+    node.expanded = expanded;
+}
+
+/// Toggles a data breakpoint on a variable, or on the expression of a watch.
+fn toggle_data_breakpoint(editor: &mut Editor, tree: NodeTree, path: &[usize]) {
+    let panels = &editor.debug_panels;
+    let (variables_reference, name) = match (tree, path.split_last()) {
+        (NodeTree::Watch(i), None) => (None, panels.watches[i].expression.clone()),
+        // scopes have no data
+        (NodeTree::Variables, None | Some((_, []))) => return,
+        (_, Some((_, parent))) => match panels.node(tree, parent).zip(panels.node(tree, path)) {
+            Some((container, node)) => (Some(container.variables_reference), node.name.clone()),
+            None => return,
+        },
+    };
+    if let Err(err) = dap::toggle_data_breakpoint(editor, variables_reference, name) {
+        editor.set_error(err.to_string());
+    }
+}
+
+/// Opens a hex dump of the memory holding the value of a variable or watch.
+fn open_memory(editor: &mut Editor, tree: NodeTree, path: &[usize]) {
+    let memory_reference = editor
+        .debug_panels
+        .node(tree, path)
+        .and_then(|node| node.memory_reference.clone());
+    match memory_reference {
+        Some(memory_reference) => dap::open_memory_view(editor, memory_reference),
+        None => editor.set_error("The value has no memory reference"),
+    }
+}
+
+fn toggle_breakpoint(editor: &mut Editor, path: PathBuf, index: usize) {
+    let breakpoints = match editor.breakpoints.get_mut(&path) {
+        Some(breakpoints) => breakpoints,
+        None => return,
+    };
+    breakpoints[index].enabled = !breakpoints[index].enabled;
+    if let Err(e) = breakpoints_changed(&mut editor.debuggers, path, breakpoints) {
+        editor.set_error(format!("Failed to set breakpoints: {}", e));
+    }
+}
+
+fn remove_breakpoint(editor: &mut Editor, path: PathBuf, index: usize) {
+    let breakpoints = match editor.breakpoints.get_mut(&path) {
+        Some(breakpoints) => breakpoints,
+        None => return,
+    };
+    breakpoints.remove(index);
+    if let Err(e) = breakpoints_changed(&mut editor.debuggers, path, breakpoints) {
+        editor.set_error(format!("Failed to set breakpoints: {}", e));
+    }
+}
+
+/// Opens a prompt to add a watch expression, or to edit the watch at `index`.
+fn watch_prompt(index: Option<usize>) -> compositor::Callback {
+    Box::new(
+        move |compositor: &mut Compositor, cx: &mut compositor::Context| {
+            let mut prompt = Prompt::new(
+                "watch:".into(),
+                None,
+                ui::completers::none,
+                move |cx, input: &str, event: PromptEvent| {
+                    if event != PromptEvent::Validate || input.is_empty() {
+                        return;
+                    }
+                    let watch = Watch::new(input.to_string());
+                    let watches = &mut cx.editor.debug_panels.watches;
+                    match index {
+                        Some(i) if i < watches.len() => watches[i] = watch,
+                        _ => watches.push(watch),
+                    }
+                    block_on(refresh_debug_panels(cx.editor));
+                },
+            );
+            let watches = &cx.editor.debug_panels.watches;
+            if let Some(expression) = index.and_then(|i| Some(watches.get(i)?.expression.clone())) {
+                prompt.insert_str(&expression, cx.editor);
+            }
+            compositor.push(Box::new(prompt));
+        },
+    )
+}
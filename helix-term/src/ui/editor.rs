@@ -5,8 +5,10 @@
     key,
     keymap::{KeymapResult, Keymaps},
     ui::{
+        self,
         document::{render_document, LinePos, TextRenderer, TranslatedPosition},
-        Completion, ProgressSpinners,
+        terminal::Terminals,
+        Completion, CompletionItem, ProgressSpinners,
     },
 };
 
@@ -22,18 +24,18 @@
     visual_offset_from_block, Position, Range, Selection, Transaction,
 };
 use helix_view::{
-    document::{Mode, SavePoint, SCRATCH_BUFFER_NAME},
-    editor::{CompleteAction, CursorShapeConfig},
+    document::{Mode, SavePoint},
+    editor::{Action, CloseError, CompleteAction, CursorShapeConfig},
     graphics::{Color, CursorKind, Modifier, Rect, Style},
     input::{KeyEvent, MouseButton, MouseEvent, MouseEventKind},
     keyboard::{KeyCode, KeyModifiers},
-    Document, Editor, Theme, View,
+    Document, DocumentId, Editor, Theme, View,
 };
-use std::{mem::take, num::NonZeroUsize, path::PathBuf, rc::Rc, sync::Arc};
+use std::{mem::take, num::NonZeroUsize, rc::Rc, sync::Arc};
 
 use tui::{buffer::Buffer as Surface, text::Span};
 
-use super::statusline;
+use super::{breadcrumbs, bufferline, statusline};
 use super::{document::LineDecoration, lsp::SignatureHelp};
 
 pub struct EditorView {
@@ -43,6 +45,10 @@ pub struct EditorView {
     pub(crate) last_insert: (commands::MappableCommand, Vec<InsertEvent>),
     pub(crate) completion: Option<Completion>,
     spinners: ProgressSpinners,
+    statusline_commands: statusline::StatusLineCommands,
+    pub(crate) terminals: Terminals,
+    /// Where each buffer was drawn in the bufferline, for mouse handling.
+    bufferline: Vec<(Rect, DocumentId)>,
 }
 
 #[derive(Debug, Clone)]
@@ -68,6 +74,9 @@ pub fn new(keymaps: Keymaps) -> Self {
             last_insert: (commands::MappableCommand::normal_mode, Vec::new()),
             completion: None,
             spinners: ProgressSpinners::default(),
+            statusline_commands: statusline::StatusLineCommands::default(),
+            terminals: Terminals::default(),
+            bufferline: Vec::new(),
         }
     }
 
@@ -85,7 +94,6 @@ pub fn render_view(
         is_focused: bool,
     ) {
         let inner = view.inner_area(doc);
-        let area = view.area;
         let theme = &editor.theme;
         let config = editor.config();
 
@@ -164,7 +172,7 @@ pub fn render_view(
             editor,
             doc,
             view,
-            view.area,
+            view.area.clip_top(view.header_height()),
             theme,
             is_focused,
             &mut line_decorations,
@@ -194,9 +202,37 @@ pub fn render_view(
             &mut translated_positions,
         );
         Self::render_rulers(editor, doc, view, inner, surface, theme);
+        if config.sticky_scroll {
+            breadcrumbs::render_sticky(editor, doc, view, surface);
+        }
+        breadcrumbs::render(editor, doc, view, surface);
+
+        Self::render_border(view, viewport, surface, theme);
+        Self::render_diagnostics(doc, view, inner, surface, theme);
+        self.render_statusline(editor, doc, view, surface, is_focused);
+    }
+
+    /// Renders a view whose terminal takes input in place of its document.
+    fn render_terminal_view(
+        &mut self,
+        editor: &Editor,
+        doc: &Document,
+        view: &View,
+        viewport: Rect,
+        surface: &mut Surface,
+        is_focused: bool,
+    ) {
+        if let Some(terminal) = self.terminals.get_mut(view) {
+            ui::terminal::render(terminal, view, is_focused, surface);
+        }
+        Self::render_border(view, viewport, surface, &editor.theme);
+        self.render_statusline(editor, doc, view, surface, is_focused);
+    }
 
-        // if we're not at the edge of the screen, draw a right border
+    /// Draws a right border, unless the view is at the edge of the screen.
+    fn render_border(view: &View, viewport: Rect, surface: &mut Surface, theme: &Theme) {
         if viewport.right() != view.area.right() {
+            let area = view.area;
             let x = area.right();
             let border_style = theme.get("ui.window");
             for y in area.top()..area.bottom() {
@@ -206,16 +242,29 @@ pub fn render_view(
                     .set_style(border_style);
             }
         }
+    }
 
-        Self::render_diagnostics(doc, view, inner, surface, theme);
-
+    fn render_statusline(
+        &self,
+        editor: &Editor,
+        doc: &Document,
+        view: &View,
+        surface: &mut Surface,
+        is_focused: bool,
+    ) {
         let statusline_area = view
             .area
             .clip_top(view.area.height.saturating_sub(1))
             .clip_bottom(1); // -1 from bottom to remove commandline
 
-        let mut context =
-            statusline::RenderContext::new(editor, doc, view, is_focused, &self.spinners);
+        let mut context = statusline::RenderContext::new(
+            editor,
+            doc,
+            view,
+            is_focused,
+            &self.spinners,
+            &self.statusline_commands,
+        );
 
         statusline::render(&mut context, statusline_area, surface);
     }
@@ -508,54 +557,58 @@ pub fn highlight_focused_view_elements(
         Vec::new()
     }
 
-    /// Render bufferline at the top
-    pub fn render_bufferline(editor: &Editor, viewport: Rect, surface: &mut Surface) {
-        let scratch = PathBuf::from(SCRATCH_BUFFER_NAME); // default filename to use for scratch buffer
+    /// Render the tab pages at the top, labelled with their name or their focused document
+    pub fn render_tabline(editor: &Editor, viewport: Rect, surface: &mut Surface) {
         surface.clear_with(
             viewport,
             editor
                 .theme
-                .try_get("ui.bufferline.background")
+                .try_get("ui.tabline.background")
+                .or_else(|| editor.theme.try_get("ui.bufferline.background"))
                 .unwrap_or_else(|| editor.theme.get("ui.statusline")),
         );
 
-        let bufferline_active = editor
+        let tabline_active = editor
             .theme
-            .try_get("ui.bufferline.active")
+            .try_get("ui.tabline.active")
+            .or_else(|| editor.theme.try_get("ui.bufferline.active"))
             .unwrap_or_else(|| editor.theme.get("ui.statusline.active"));
 
-        let bufferline_inactive = editor
+        let tabline_inactive = editor
             .theme
-            .try_get("ui.bufferline")
+            .try_get("ui.tabline")
+            .or_else(|| editor.theme.try_get("ui.bufferline"))
             .unwrap_or_else(|| editor.theme.get("ui.statusline.inactive"));
 
         let mut x = viewport.x;
-        let current_doc = view!(editor).doc;
-
-        for doc in editor.documents() {
-            let fname = doc
-                .path()
-                .unwrap_or(&scratch)
-                .file_name()
-                .unwrap_or_default()
-                .to_str()
-                .unwrap_or_default();
-
-            let style = if current_doc == doc.id() {
-                bufferline_active
+        let active = editor.tree.active_tab();
+
+        for (index, tab) in editor.tree.tabs().iter().enumerate() {
+            // unnamed tabs are labelled after their focused document
+            let name = match &tab.name {
+                Some(name) => name.as_str().into(),
+                None => editor
+                    .tree
+                    .try_get(editor.tree.tab_focus(index))
+                    .and_then(|view| editor.document(view.doc))
+                    .map(|doc| doc.display_name())
+                    .unwrap_or_default(),
+            };
+
+            let style = if index == active {
+                tabline_active
             } else {
-                bufferline_inactive
+                tabline_inactive
             };
 
-            let text = format!(" {}{} ", fname, if doc.is_modified() { "[+]" } else { "" });
-            let used_width = viewport.x.saturating_sub(x);
-            let rem_width = surface.area.width.saturating_sub(used_width);
+            let text = format!(" {} {} ", index + 1, name);
+            let rem_width = viewport.right().saturating_sub(x);
 
             x = surface
                 .set_stringn(x, viewport.y, text, rem_width as usize, style)
                 .0;
 
-            if x >= surface.area.right() {
+            if x >= viewport.right() {
                 break;
             }
         }
@@ -947,7 +1000,7 @@ pub fn set_completion(
         &mut self,
         editor: &mut Editor,
         savepoint: Arc<SavePoint>,
-        items: Vec<helix_lsp::lsp::CompletionItem>,
+        items: Vec<CompletionItem>,
         offset_encoding: helix_lsp::OffsetEncoding,
         start_offset: usize,
         trigger_offset: usize,
@@ -986,6 +1039,7 @@ pub fn clear_completion(&mut self, editor: &mut Editor) {
 
     pub fn handle_idle_timeout(&mut self, cx: &mut commands::Context) -> EventResult {
         commands::compute_inlay_hints_for_all_views(cx.editor, cx.jobs);
+        commands::pull_diagnostics_for_all_views(cx.editor, cx.jobs);
 
         if let Some(completion) = &mut self.completion {
             return if completion.ensure_item_resolved(cx) {
@@ -1025,7 +1079,7 @@ fn handle_mouse_event(
         } = *event;
 
         let pos_and_view = |editor: &Editor, row, column, ignore_virtual_text| {
-            editor.tree.views().find_map(|(view, _focus)| {
+            editor.tree.traverse().find_map(|(_, view)| {
                 view.pos_at_screen_coords(
                     &editor.documents[&view.doc],
                     row,
@@ -1037,16 +1091,39 @@ fn handle_mouse_event(
         };
 
         let gutter_coords_and_view = |editor: &Editor, row, column| {
-            editor.tree.views().find_map(|(view, _focus)| {
+            editor.tree.traverse().find_map(|(_, view)| {
                 view.gutter_coords_at_screen_coords(row, column)
                     .map(|coords| (coords, view.id))
             })
         };
 
+        let bufferline_doc = self
+            .bufferline
+            .iter()
+            .find(|(area, _)| area.top() == row && (area.left()..area.right()).contains(&column))
+            .map(|&(_, doc_id)| doc_id);
+
         match kind {
             MouseEventKind::Down(MouseButton::Left) => {
                 let editor = &mut cxt.editor;
 
+                if let Some(doc_id) = bufferline_doc {
+                    editor.switch(doc_id, Action::Replace);
+                    return EventResult::Consumed(None);
+                }
+
+                let breadcrumb = editor.tree.traverse().find_map(|(_, view)| {
+                    breadcrumbs::click_target(editor, view, row, column).map(|pos| (pos, view.id))
+                });
+                if let Some((pos, view_id)) = breadcrumb {
+                    editor.focus(view_id);
+                    let (view, doc) = current!(editor);
+                    view.jumps.push((doc.id(), doc.selection(view.id).clone()));
+                    doc.set_selection(view.id, Selection::point(pos));
+                    editor.ensure_cursor_in_view(view_id);
+                    return EventResult::Consumed(None);
+                }
+
                 if let Some((pos, view_id)) = pos_and_view(editor, row, column, true) {
                     let doc = doc_mut!(editor, &view!(editor, view_id).doc);
 
@@ -1171,6 +1248,17 @@ fn handle_mouse_event(
 
             MouseEventKind::Up(MouseButton::Middle) => {
                 let editor = &mut cxt.editor;
+
+                // middle-click closes buffers in the bufferline
+                if let Some(doc_id) = bufferline_doc {
+                    if let Err(CloseError::BufferModified(name)) =
+                        editor.close_document(doc_id, false)
+                    {
+                        editor.set_error(format!("{} has unsaved changes", name));
+                    }
+                    return EventResult::Consumed(None);
+                }
+
                 if !config.middle_click_paste {
                     return EventResult::Ignored(None);
                 }
@@ -1205,6 +1293,15 @@ fn handle_event(
         event: &Event,
         context: &mut crate::compositor::Context,
     ) -> EventResult {
+        if self.on_next_key.is_none() && self.keymaps.pending().is_empty() {
+            if let Some(result) = ui::debug_panel::handle_event(event, context) {
+                return result;
+            }
+            if let Some(result) = self.terminals.handle_event(event, context.editor) {
+                return result;
+            }
+        }
+
         let mut cx = commands::Context {
             editor: context.editor,
             count: None,
@@ -1338,6 +1435,7 @@ fn handle_event(
                         doc.append_changes_to_history(view);
                     }
                 }
+                cx.editor.sync_breakpoints();
 
                 EventResult::Consumed(callback)
             }
@@ -1369,22 +1467,67 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
             _ => false,
         };
 
-        // -1 for commandline and -1 for bufferline
+        let use_tabline = match config.tabline {
+            BufferLine::Always => true,
+            BufferLine::Multiple if cx.editor.tree.tabs().len() > 1 => true,
+            _ => false,
+        };
+
+        // -1 for commandline, -1 for tabline and -1 for bufferline
         let mut editor_area = area.clip_bottom(1);
+        if use_tabline {
+            editor_area = editor_area.clip_top(1);
+        }
         if use_bufferline {
             editor_area = editor_area.clip_top(1);
         }
 
+        // the quickfix panel sits between the views and the commandline
+        let quickfix_height = ui::quickfix::panel_height(cx.editor, editor_area);
+        editor_area = editor_area.clip_bottom(quickfix_height);
+
+        // the debugger panels are docked beside the views
+        let (debug_panel_area, views_area) = ui::debug_panel::split_area(cx.editor, editor_area);
+        editor_area = views_area;
+
         // if the terminal size suddenly changed, we need to trigger a resize
         cx.editor.resize(editor_area);
 
+        if use_tabline {
+            Self::render_tabline(cx.editor, area.with_height(1), surface);
+        }
         if use_bufferline {
-            Self::render_bufferline(cx.editor, area.with_height(1), surface);
+            let y = area.y + use_tabline as u16;
+            let viewport = Rect::new(area.x, y, area.width, 1);
+            self.bufferline = bufferline::render(cx.editor, viewport, surface);
+        } else {
+            self.bufferline.clear();
         }
 
-        for (view, is_focused) in cx.editor.tree.views() {
+        self.terminals.prune(cx.editor);
+        let focus = cx.editor.tree.focus;
+        for (view_id, view) in cx.editor.tree.traverse() {
+            let is_focused = view_id == focus;
             let doc = cx.editor.document(view.doc).unwrap();
-            self.render_view(cx.editor, doc, view, area, surface, is_focused);
+            if self
+                .terminals
+                .get(view)
+                .map_or(false, |terminal| terminal.takes_input())
+            {
+                self.render_terminal_view(cx.editor, doc, view, area, surface, is_focused);
+            } else {
+                self.render_view(cx.editor, doc, view, area, surface, is_focused);
+            }
+        }
+
+        if debug_panel_area.width > 0 {
+            ui::debug_panel::render_panel(cx.editor, debug_panel_area, surface);
+        }
+
+        if quickfix_height > 0 {
+            cx.editor.sync_quickfix();
+            let panel_area = Rect::new(area.x, editor_area.bottom(), area.width, quickfix_height);
+            ui::quickfix::render_panel(cx.editor, panel_area, surface);
         }
 
         if config.auto_info {
@@ -1459,6 +1602,16 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
     }
 
     fn cursor(&self, _area: Rect, editor: &Editor) -> (Option<Position>, CursorKind) {
+        // the cursor of a terminal is drawn with its screen
+        let view = editor.tree.get(editor.tree.focus);
+        if self
+            .terminals
+            .get(view)
+            .map_or(false, |terminal| terminal.takes_input())
+        {
+            return (None, CursorKind::Hidden);
+        }
+
         match editor.cursor() {
             // All block cursors are drawn manually
             (pos, CursorKind::Block) => (pos, CursorKind::Hidden),
@@ -115,6 +115,13 @@ pub fn score(&mut self, pattern: &str) {
         self.recalculate = true;
     }
 
+    /// Reorders the matches by a key. The sort is stable so matches with equal keys stay
+    /// ordered by their score.
+    pub fn sort_matches_by_key<K: Ord>(&mut self, mut f: impl FnMut(&T) -> K) {
+        let options = &self.options;
+        self.matches.sort_by_key(|(index, _)| f(&options[*index]));
+    }
+
     pub fn clear(&mut self) {
         self.matches.clear();
 
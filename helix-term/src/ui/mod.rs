@@ -1,4 +1,7 @@
+mod breadcrumbs;
+mod bufferline;
 mod completion;
+pub mod debug_panel;
 mod document;
 pub(crate) mod editor;
 mod fuzzy_match;
@@ -10,14 +13,16 @@
 mod picker;
 pub mod popup;
 mod prompt;
+pub mod quickfix;
 mod spinner;
 mod statusline;
+pub mod terminal;
 mod text;
 
 use crate::compositor::{Component, Compositor};
 use crate::filter_picker_entry;
 use crate::job::{self, Callback};
-pub use completion::Completion;
+pub use completion::{builtin_completion_items, Completion, CompletionItem};
 pub use editor::EditorView;
 pub use markdown::Markdown;
 pub use menu::Menu;
@@ -158,14 +163,18 @@ pub fn regex_prompt(
     cx.push_layer(Box::new(prompt));
 }
 
-pub fn file_picker(root: PathBuf, config: &helix_view::editor::Config) -> FilePicker<PathBuf> {
+pub fn file_picker(root: PathBuf, editor: &Editor) -> FilePicker<PathBuf> {
     use ignore::{types::TypesBuilder, WalkBuilder};
     use std::time::Instant;
 
     let now = Instant::now();
 
+    let config = editor.config();
+
     let dedup_symlinks = config.file_picker.deduplicate_links;
     let absolute_root = root.canonicalize().unwrap_or_else(|_| root.clone());
+    // frecency is recorded for canonical paths while the walked paths start with `root`
+    let frecency_root = (root.clone(), absolute_root.clone());
 
     let mut walk_builder = WalkBuilder::new(&root);
     walk_builder
@@ -232,10 +241,17 @@ pub fn file_picker(root: PathBuf, config: &helix_view::editor::Config) -> FilePi
         },
         |_editor, path| Some((path.clone().into(), None)),
     )
+    .with_boost({
+        let frecency = editor.workspace_state.frecency.clone();
+        move |path: &PathBuf| match path.strip_prefix(&frecency_root.0) {
+            Ok(relative) => frecency.score(&frecency_root.1.join(relative)),
+            Err(_) => frecency.score(path),
+        }
+    })
 }
 
 pub mod completers {
-    use crate::ui::prompt::Completion;
+    pub use crate::ui::prompt::Completion;
     use fuzzy_matcher::skim::SkimMatcherV2 as Matcher;
     use fuzzy_matcher::FuzzyMatcher;
     use helix_view::document::SCRATCH_BUFFER_NAME;
@@ -390,6 +406,25 @@ pub fn language(editor: &Editor, input: &str) -> Vec<Completion> {
             .collect()
     }
 
+    pub fn task(editor: &Editor, input: &str) -> Vec<Completion> {
+        let matcher = Matcher::default();
+
+        let mut matches: Vec<_> = editor
+            .tasks()
+            .into_keys()
+            .filter_map(|name| matcher.fuzzy_match(&name, input).map(|score| (name, score)))
+            .collect();
+
+        matches.sort_unstable_by(|(name1, score1), (name2, score2)| {
+            (Reverse(*score1), name1).cmp(&(Reverse(*score2), name2))
+        });
+
+        matches
+            .into_iter()
+            .map(|(name, _score)| ((0..), name.into()))
+            .collect()
+    }
+
     pub fn lsp_workspace_command(editor: &Editor, input: &str) -> Vec<Completion> {
         let matcher = Matcher::default();
 
@@ -1,6 +1,7 @@
 use helix_core::Position;
 use helix_view::{
     graphics::{CursorKind, Rect},
+    workspace::PickerState,
     Editor,
 };
 use tui::buffer::Buffer;
@@ -73,4 +74,8 @@ fn cursor(&self, area: Rect, ctx: &Editor) -> (Option<Position>, CursorKind) {
     fn id(&self) -> Option<&'static str> {
         self.content.id()
     }
+
+    fn restore_state(&mut self, state: &PickerState, editor: &Editor) {
+        self.content.restore_state(state, editor)
+    }
 }
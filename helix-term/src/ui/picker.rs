@@ -21,7 +21,11 @@
 use tui::widgets::Widget;
 
 use std::cmp::{self, Ordering};
-use std::{collections::HashMap, io::Read, path::PathBuf};
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::Read,
+    path::PathBuf,
+};
 
 use crate::ui::{Prompt, PromptEvent};
 use helix_core::{
@@ -31,8 +35,11 @@
 use helix_view::{
     editor::Action,
     graphics::{CursorKind, Margin, Modifier, Rect},
+    input::KeyEvent,
+    quickfix::QuickfixEntry,
     theme::Style,
     view::ViewPosition,
+    workspace::PickerState,
     Document, DocumentId, Editor,
 };
 
@@ -148,6 +155,62 @@ pub fn truncate_start(mut self, truncate_start: bool) -> Self {
         self
     }
 
+    pub fn with_bulk_action(
+        mut self,
+        key: KeyEvent,
+        callback_fn: impl Fn(&mut Context, &[&T]) + 'static,
+    ) -> Self {
+        self.picker = self.picker.with_bulk_action(key, callback_fn);
+        self
+    }
+
+    pub fn with_boost(mut self, boost_fn: impl Fn(&T) -> i64 + 'static) -> Self {
+        self.picker = self.picker.with_boost(boost_fn);
+        self
+    }
+
+    pub fn with_id(mut self, id: &'static str) -> Self {
+        self.picker = self.picker.with_id(id);
+        self
+    }
+
+    pub fn set_options(&mut self, new_options: Vec<T>) {
+        self.picker.set_options(new_options);
+    }
+
+    /// Replaces the quickfix list with the locations of the marked options, or of all
+    /// matching options if none are marked.
+    fn send_to_quickfix(&self, editor: &mut Editor) {
+        let entries: Vec<_> = self
+            .picker
+            .marked_or_matches()
+            .into_iter()
+            .filter_map(|option| {
+                let (path_or_id, lines) = (self.file_fn)(editor, option)?;
+                let path = match path_or_id {
+                    PathOrId::Path(path) => path,
+                    PathOrId::Id(id) => editor.document(id)?.path()?.clone(),
+                };
+                let text: Vec<_> = option
+                    .format(&self.picker.editor_data)
+                    .cell_text()
+                    .filter(|text| !text.is_empty())
+                    .collect();
+                let line = lines.map_or(0, |(start, _)| start);
+                Some(QuickfixEntry::new(path, line, 0, text.join(" ")))
+            })
+            .collect();
+
+        let mut title = self.picker.id.unwrap_or("picker").to_string();
+        if !self.picker.prompt.line().is_empty() {
+            title.push_str(&format!(" \"{}\"", self.picker.prompt.line()));
+        }
+        let len = entries.len();
+        editor.quickfix.set(title, entries);
+        editor.quickfix.visible = true;
+        editor.set_status(format!("{} entries in the quickfix list", len));
+    }
+
     fn current_file(&self, editor: &Editor) -> Option<FileLocation> {
         self.picker
             .selection()
@@ -356,6 +419,10 @@ fn handle_event(&mut self, event: &Event, ctx: &mut Context) -> EventResult {
         if let Event::IdleTimeout = event {
             return self.handle_idle_timeout(ctx);
         }
+        if let Event::Key(ctrl!('q')) = event {
+            self.send_to_quickfix(ctx.editor);
+            return self.picker.close(ctx.editor);
+        }
         // TODO: keybinds for scrolling preview
         self.picker.handle_event(event, ctx)
     }
@@ -373,6 +440,14 @@ fn required_size(&mut self, (width, height): (u16, u16)) -> Option<(u16, u16)> {
         self.picker.required_size((picker_width, height))?;
         Some((width, height))
     }
+
+    fn id(&self) -> Option<&'static str> {
+        self.picker.id
+    }
+
+    fn restore_state(&mut self, state: &PickerState, editor: &Editor) {
+        self.picker.restore_state(state, editor)
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -401,6 +476,8 @@ fn cmp(&self, other: &Self) -> Ordering {
 }
 
 type PickerCallback<T> = Box<dyn Fn(&mut Context, &T, Action)>;
+type PickerBulkCallback<T> = Box<dyn Fn(&mut Context, &[&T])>;
+type PickerBoost<T> = Box<dyn Fn(&T) -> i64>;
 
 pub struct Picker<T: Item> {
     options: Vec<T>,
@@ -413,6 +490,8 @@ pub struct Picker<T: Item> {
     completion_height: u16,
 
     cursor: usize,
+    /// Indices of the marked options
+    marked: BTreeSet<usize>,
     // pattern: String,
     prompt: Prompt,
     previous_pattern: (String, FuzzyQuery),
@@ -424,6 +503,16 @@ pub struct Picker<T: Item> {
     widths: Vec<Constraint>,
 
     callback_fn: PickerCallback<T>,
+    /// Picker specific actions run on all marked options at once
+    bulk_actions: Vec<(KeyEvent, PickerBulkCallback<T>)>,
+    /// Computes a value which is added to the match score of an option, used to rank
+    /// frequently opened files higher
+    boost_fn: Option<PickerBoost<T>>,
+    /// The boost of each option, computed once when the options are set
+    boosts: Vec<i64>,
+    /// The name of the command which opens this picker, used to restore the picker along
+    /// with its query in a later session
+    id: Option<&'static str>,
 }
 
 impl<T: Item> Picker<T> {
@@ -445,11 +534,16 @@ pub fn new(
             matcher: Box::default(),
             matches: Vec::new(),
             cursor: 0,
+            marked: BTreeSet::new(),
             prompt,
             previous_pattern: (String::new(), FuzzyQuery::default()),
             truncate_start: true,
             show_preview: true,
             callback_fn: Box::new(callback_fn),
+            bulk_actions: Vec::new(),
+            boost_fn: None,
+            boosts: Vec::new(),
+            id: None,
             completion_height: 0,
             widths: Vec::new(),
         };
@@ -472,8 +566,43 @@ pub fn new(
         picker
     }
 
+    /// Adds an action bound to `key` which is called with all marked options, or with the
+    /// selected option if none are marked. The picker is closed afterwards.
+    pub fn with_bulk_action(
+        mut self,
+        key: KeyEvent,
+        callback_fn: impl Fn(&mut Context, &[&T]) + 'static,
+    ) -> Self {
+        self.bulk_actions.push((key, Box::new(callback_fn)));
+        self
+    }
+
+    pub fn with_boost(mut self, boost_fn: impl Fn(&T) -> i64 + 'static) -> Self {
+        self.boost_fn = Some(Box::new(boost_fn));
+        self.calculate_boosts();
+        for pmatch in &mut self.matches {
+            pmatch.score += self.boosts[pmatch.index];
+        }
+        self.matches.sort_unstable();
+        self
+    }
+
+    pub fn with_id(mut self, id: &'static str) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    fn calculate_boosts(&mut self) {
+        self.boosts = match &self.boost_fn {
+            Some(boost_fn) => self.options.iter().map(boost_fn).collect(),
+            None => Vec::new(),
+        };
+    }
+
     pub fn set_options(&mut self, new_options: Vec<T>) {
         self.options = new_options;
+        self.marked.clear();
+        self.calculate_boosts();
         self.cursor = 0;
         self.force_score();
         self.calculate_column_widths();
@@ -524,10 +653,13 @@ pub fn score(&mut self) {
                     let text = option.filter_text(&self.editor_data);
                     PickerMatch {
                         index,
-                        score: 0,
+                        score: self.boosts.get(index).copied().unwrap_or_default(),
                         len: text.chars().count(),
                     }
                 }));
+            if !self.boosts.is_empty() {
+                self.matches.sort_unstable();
+            }
         } else if is_refined {
             // optimization: if the pattern is a more specific version of the previous one
             // then we can score the filtered set.
@@ -538,7 +670,8 @@ pub fn score(&mut self) {
                 match query.fuzzy_match(&text, &self.matcher) {
                     Some(s) => {
                         // Update the score
-                        pmatch.score = s;
+                        pmatch.score =
+                            s + self.boosts.get(pmatch.index).copied().unwrap_or_default();
                         true
                     }
                     None => false,
@@ -573,7 +706,7 @@ pub fn force_score(&mut self) {
                         .fuzzy_match(&text, &self.matcher)
                         .map(|score| PickerMatch {
                             index,
-                            score,
+                            score: score + self.boosts.get(index).copied().unwrap_or_default(),
                             len: text.chars().count(),
                         })
                 }),
@@ -627,10 +760,109 @@ pub fn selection(&self) -> Option<&T> {
             .map(|pmatch| &self.options[pmatch.index])
     }
 
+    /// Marks the selected option, or unmarks it if it was already marked.
+    pub fn toggle_mark(&mut self) {
+        if let Some(pmatch) = self.matches.get(self.cursor) {
+            if !self.marked.remove(&pmatch.index) {
+                self.marked.insert(pmatch.index);
+            }
+        }
+    }
+
+    /// Marks all matching options, or unmarks them if they are all marked already.
+    pub fn toggle_mark_all(&mut self) {
+        if self
+            .matches
+            .iter()
+            .all(|pmatch| self.marked.contains(&pmatch.index))
+        {
+            for pmatch in &self.matches {
+                self.marked.remove(&pmatch.index);
+            }
+        } else {
+            self.marked
+                .extend(self.matches.iter().map(|pmatch| pmatch.index));
+        }
+    }
+
+    /// Returns the marked options in their original order, or the selected option if
+    /// none are marked.
+    pub fn marked_or_selection(&self) -> Vec<&T> {
+        if self.marked.is_empty() {
+            return self.selection().into_iter().collect();
+        }
+        self.marked_options()
+    }
+
+    /// Returns the marked options in their original order, or all matching options if none
+    /// are marked.
+    pub fn marked_or_matches(&self) -> Vec<&T> {
+        if self.marked.is_empty() {
+            return self
+                .matches
+                .iter()
+                .map(|pmatch| &self.options[pmatch.index])
+                .collect();
+        }
+        self.marked_options()
+    }
+
+    fn marked_options(&self) -> Vec<&T> {
+        self.marked
+            .iter()
+            .map(|&index| &self.options[index])
+            .collect()
+    }
+
+    /// Drops every option which isn't marked, turning the picker into a list of the
+    /// marked options.
+    pub fn keep_marked(&mut self) {
+        if self.marked.is_empty() {
+            return;
+        }
+        let marked = std::mem::take(&mut self.marked);
+        let options = std::mem::take(&mut self.options)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, option)| marked.contains(&index).then_some(option))
+            .collect();
+        self.set_options(options);
+    }
+
+    /// Calls the callback with the marked options, or the selected option if none are
+    /// marked. When several options replace the current view, all but the last one are
+    /// only loaded so that the last one ends up focused.
+    fn call_marked(&self, cx: &mut Context, action: Action) {
+        let options = self.marked_or_selection();
+        let last = options.len().saturating_sub(1);
+        for (i, option) in options.into_iter().enumerate() {
+            let action = match action {
+                Action::Replace if i != last => Action::Load,
+                action => action,
+            };
+            (self.callback_fn)(cx, option, action);
+        }
+    }
+
     pub fn toggle_preview(&mut self) {
         self.show_preview = !self.show_preview;
     }
 
+    /// Remembers the picker so it can be reopened, either later in this session or in the
+    /// next one if it has an id.
+    fn close(&self, editor: &mut Editor) -> EventResult {
+        editor.workspace_state.last_picker = self.id.map(|id| PickerState {
+            command: id.to_string(),
+            query: self.prompt.line().clone(),
+            cursor: self.cursor,
+        });
+
+        EventResult::Consumed(Some(Box::new(|compositor: &mut Compositor, _cx| {
+            // remove the layer
+            compositor.last_picker = compositor.pop();
+        })))
+    }
+
     fn prompt_handle_event(&mut self, event: &Event, cx: &mut Context) -> EventResult {
         if let EventResult::Consumed(_) = self.prompt.handle_event(event, cx) {
             // TODO: recalculate only if pattern changed
@@ -659,14 +891,15 @@ fn handle_event(&mut self, event: &Event, cx: &mut Context) -> EventResult {
             _ => return EventResult::Ignored(None),
         };
 
-        let close_fn = EventResult::Consumed(Some(Box::new(|compositor: &mut Compositor, _cx| {
-            // remove the layer
-            compositor.last_picker = compositor.pop();
-        })));
-
         // So that idle timeout retriggers
         cx.editor.reset_idle_timer();
 
+        if let Some((_, callback_fn)) = self.bulk_actions.iter().find(|(key, _)| *key == key_event)
+        {
+            callback_fn(cx, &self.marked_or_selection());
+            return self.close(cx.editor);
+        }
+
         match key_event {
             shift!(Tab) | key!(Up) | ctrl!('p') => {
                 self.move_by(1, Direction::Backward);
@@ -687,30 +920,32 @@ fn handle_event(&mut self, event: &Event, cx: &mut Context) -> EventResult {
                 self.to_end();
             }
             key!(Esc) | ctrl!('c') => {
-                return close_fn;
+                return self.close(cx.editor);
             }
             alt!(Enter) => {
-                if let Some(option) = self.selection() {
-                    (self.callback_fn)(cx, option, Action::Load);
-                }
+                self.call_marked(cx, Action::Load);
             }
             key!(Enter) => {
-                if let Some(option) = self.selection() {
-                    (self.callback_fn)(cx, option, Action::Replace);
-                }
-                return close_fn;
+                self.call_marked(cx, Action::Replace);
+                return self.close(cx.editor);
             }
             ctrl!('s') => {
-                if let Some(option) = self.selection() {
-                    (self.callback_fn)(cx, option, Action::HorizontalSplit);
-                }
-                return close_fn;
+                self.call_marked(cx, Action::HorizontalSplit);
+                return self.close(cx.editor);
             }
             ctrl!('v') => {
-                if let Some(option) = self.selection() {
-                    (self.callback_fn)(cx, option, Action::VerticalSplit);
-                }
-                return close_fn;
+                self.call_marked(cx, Action::VerticalSplit);
+                return self.close(cx.editor);
+            }
+            ctrl!(' ') => {
+                self.toggle_mark();
+                self.move_by(1, Direction::Forward);
+            }
+            alt!('a') => {
+                self.toggle_mark_all();
+            }
+            alt!('q') => {
+                self.keep_marked();
             }
             ctrl!('t') => {
                 self.toggle_preview();
@@ -727,6 +962,11 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
         let text_style = cx.editor.theme.get("ui.text");
         let selected = cx.editor.theme.get("ui.text.focus");
         let highlight_style = cx.editor.theme.get("special").add_modifier(Modifier::BOLD);
+        let marked_style = cx
+            .editor
+            .theme
+            .try_get("ui.picker.marked")
+            .unwrap_or_else(|| cx.editor.theme.get("ui.selection"));
 
         // -- Render the frame:
         // clear area
@@ -745,7 +985,16 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
 
         let area = inner.clip_left(1).with_height(1);
 
-        let count = format!("{}/{}", self.matches.len(), self.options.len());
+        let count = if self.marked.is_empty() {
+            format!("{}/{}", self.matches.len(), self.options.len())
+        } else {
+            format!(
+                "({} marked) {}/{}",
+                self.marked.len(),
+                self.matches.len(),
+                self.options.len()
+            )
+        };
         surface.set_stringn(
             (area.x + area.width).saturating_sub(count.len() as u16 + 1),
             area.y,
@@ -778,8 +1027,14 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
             .iter()
             .skip(offset)
             .take(rows as usize)
-            .map(|pmatch| &self.options[pmatch.index])
-            .map(|option| option.format(&self.editor_data))
+            .map(|pmatch| {
+                let row = self.options[pmatch.index].format(&self.editor_data);
+                if self.marked.contains(&pmatch.index) {
+                    row.style(marked_style)
+                } else {
+                    row
+                }
+            })
             .map(|mut row| {
                 const TEMP_CELL_SEP: &str = " ";
 
@@ -899,6 +1154,13 @@ fn cursor(&self, area: Rect, editor: &Editor) -> (Option<Position>, CursorKind)
 
         self.prompt.cursor(area, editor)
     }
+
+    fn restore_state(&mut self, state: &PickerState, editor: &Editor) {
+        self.prompt.clear(editor);
+        self.prompt.insert_str(&state.query, editor);
+        self.score();
+        self.cursor = state.cursor.min(self.matches.len().saturating_sub(1));
+    }
 }
 
 /// Returns a new list of options to replace the contents of the picker
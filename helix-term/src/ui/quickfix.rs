@@ -0,0 +1,91 @@
+use helix_core::diagnostic::Severity;
+use helix_view::{graphics::Rect, Editor};
+use tui::buffer::Buffer as Surface;
+
+/// The most rows the quickfix panel takes up, including its title.
+const MAX_PANEL_HEIGHT: u16 = 10;
+
+/// Returns the height of the quickfix panel below the views, which is 0 when the panel is
+/// hidden or there is no room for it in `area`.
+pub fn panel_height(editor: &Editor, area: Rect) -> u16 {
+    let quickfix = &editor.quickfix;
+    if !quickfix.visible {
+        return 0;
+    }
+    let height = (quickfix.entries().len() as u16)
+        .saturating_add(1)
+        .min(MAX_PANEL_HEIGHT);
+    // leave room for at least a few lines of text and the statusline
+    if area.height < height + 4 {
+        return 0;
+    }
+    height
+}
+
+/// Renders the quickfix panel: a title line followed by the entries, scrolled so that the
+/// current entry is visible.
+pub fn render_panel(editor: &Editor, area: Rect, surface: &mut Surface) {
+    let quickfix = &editor.quickfix;
+    let theme = &editor.theme;
+
+    surface.clear_with(area, theme.get("ui.background"));
+
+    let title_style = theme.get("ui.statusline");
+    surface.set_style(area.with_height(1), title_style);
+    let title = if quickfix.is_empty() {
+        format!(" quickfix: {} (empty)", quickfix.title)
+    } else {
+        format!(
+            " quickfix: {} ({}/{})",
+            quickfix.title,
+            quickfix.index() + 1,
+            quickfix.entries().len()
+        )
+    };
+    surface.set_stringn(area.x, area.y, title, area.width as usize, title_style);
+
+    let rows = area.height.saturating_sub(1) as usize;
+    if rows == 0 {
+        return;
+    }
+    let offset = quickfix.index() - quickfix.index() % rows;
+
+    let text_style = theme.get("ui.text");
+    let location_style = theme.get("ui.text.info");
+    let current_style = theme.get("ui.cursorline.primary");
+
+    for (i, entry) in quickfix
+        .entries()
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(rows)
+    {
+        let y = area.y + 1 + (i - offset) as u16;
+        let row = Rect::new(area.x, y, area.width, 1);
+        if i == quickfix.index() {
+            surface.set_style(row, current_style);
+        }
+
+        let path = helix_core::path::get_relative_path(&entry.path);
+        let location = format!("{}:{}:{}", path.display(), entry.line + 1, entry.column + 1);
+        let (x, _) = surface.set_stringn(
+            area.x + 1,
+            y,
+            location,
+            area.width.saturating_sub(1) as usize,
+            location_style,
+        );
+
+        let style = match entry.severity {
+            Some(Severity::Error) => theme.get("error"),
+            Some(Severity::Warning) => theme.get("warning"),
+            Some(Severity::Info) => theme.get("info"),
+            Some(Severity::Hint) => theme.get("hint"),
+            None => text_style,
+        };
+        let text = entry.text.lines().next().unwrap_or_default();
+        let x = x + 2;
+        surface.set_stringn(x, y, text, area.right().saturating_sub(x) as usize, style);
+    }
+}
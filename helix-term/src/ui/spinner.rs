@@ -3,6 +3,8 @@
 #[derive(Default, Debug)]
 pub struct ProgressSpinners {
     inner: HashMap<usize, Spinner>,
+    /// The latest progress report of each language server, while it is progressing.
+    messages: HashMap<usize, String>,
 }
 
 impl ProgressSpinners {
@@ -10,6 +12,17 @@ pub fn get(&self, id: usize) -> Option<&Spinner> {
         self.inner.get(&id)
     }
 
+    pub fn message(&self, id: usize) -> Option<&str> {
+        self.messages.get(&id).map(String::as_str)
+    }
+
+    pub fn set_message(&mut self, id: usize, message: Option<String>) {
+        match message {
+            Some(message) => self.messages.insert(id, message),
+            None => self.messages.remove(&id),
+        };
+    }
+
     pub fn get_or_create(&mut self, id: usize) -> &mut Spinner {
         self.inner.entry(id).or_insert_with(Spinner::default)
     }
@@ -1,16 +1,21 @@
-use helix_core::{coords_at_pos, encoding, Position};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use helix_core::{coords_at_pos, encoding, unicode::width::UnicodeWidthChar, Position};
 use helix_lsp::lsp::DiagnosticSeverity;
 use helix_view::document::DEFAULT_LANGUAGE_NAME;
 use helix_view::{
     document::{Mode, SCRATCH_BUFFER_NAME},
     graphics::Rect,
+    statusline::{Placeholder, Segment, Truncate},
     theme::Style,
     Document, Editor, View,
 };
 
 use crate::ui::ProgressSpinners;
 
-use helix_view::editor::StatusLineElement as StatusLineElementID;
+use helix_view::editor::{StatusLineElement as StatusLineElementID, StatusLineItem};
 use tui::buffer::Buffer as Surface;
 use tui::text::{Span, Spans};
 
@@ -20,6 +25,7 @@ pub struct RenderContext<'a> {
     pub view: &'a View,
     pub focused: bool,
     pub spinners: &'a ProgressSpinners,
+    pub commands: &'a StatusLineCommands,
     pub parts: RenderBuffer<'a>,
 }
 
@@ -30,6 +36,7 @@ pub fn new(
         view: &'a View,
         focused: bool,
         spinners: &'a ProgressSpinners,
+        commands: &'a StatusLineCommands,
     ) -> Self {
         RenderContext {
             editor,
@@ -37,11 +44,90 @@ pub fn new(
             view,
             focused,
             spinners,
+            commands,
             parts: RenderBuffer::default(),
         }
     }
 }
 
+/// Output of the shell commands shown in the statusline, shared with the tasks running them.
+#[derive(Debug, Default, Clone)]
+pub struct StatusLineCommands {
+    outputs: Arc<Mutex<HashMap<String, CommandOutput>>>,
+}
+
+#[derive(Debug, Default)]
+struct CommandOutput {
+    text: String,
+    /// When the command last finished.
+    updated: Option<Instant>,
+    running: bool,
+}
+
+impl StatusLineCommands {
+    /// The latest output of `command`, running it again in the background once it is older than
+    /// `interval` seconds.
+    fn output(&self, editor: &Editor, command: &str, interval: u64) -> String {
+        let mut outputs = self.outputs.lock().unwrap();
+        let output = outputs.entry(command.to_string()).or_default();
+        let interval = Duration::from_secs(interval);
+
+        let stale = output
+            .updated
+            .map_or(true, |updated| updated.elapsed() >= interval);
+        if stale && !output.running {
+            output.running = true;
+
+            let outputs = self.outputs.clone();
+            let shell = editor.config().shell.clone();
+            let command = command.to_string();
+            let redraw_notify = editor.redraw_handle.0.clone();
+            tokio::spawn(async move {
+                // a command still running when its output is due again is given up on
+                let text = match tokio::time::timeout(interval, run_command(&shell, &command)).await
+                {
+                    Ok(Ok(text)) => text,
+                    Ok(Err(err)) => {
+                        log::warn!("statusline command '{}' failed: {}", command, err);
+                        String::new()
+                    }
+                    Err(_) => {
+                        log::warn!("statusline command '{}' timed out", command);
+                        String::new()
+                    }
+                };
+                if let Some(output) = outputs.lock().unwrap().get_mut(&command) {
+                    output.text = text;
+                    output.updated = Some(Instant::now());
+                    output.running = false;
+                }
+                redraw_notify.notify_one();
+
+                // render again once the output is stale so that the command is re-run
+                tokio::time::sleep(interval).await;
+                redraw_notify.notify_one();
+            });
+        }
+
+        output.text.clone()
+    }
+}
+
+async fn run_command(shell: &[String], command: &str) -> anyhow::Result<String> {
+    let (shell, args) = shell
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("no shell set"))?;
+    let output = tokio::process::Command::new(shell)
+        .args(args)
+        .arg(command)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().next().unwrap_or_default().trim().to_string())
+}
+
 #[derive(Default)]
 pub struct RenderBuffer<'a> {
     pub left: Spans<'a>,
@@ -72,11 +158,11 @@ pub fn render(context: &mut RenderContext, viewport: Rect, surface: &mut Surface
 
     let config = context.editor.config();
 
-    let element_ids = &config.statusline.left;
-    element_ids
+    config
+        .statusline
+        .left
         .iter()
-        .map(|element_id| get_render_function(*element_id))
-        .for_each(|render| render(context, write_left));
+        .for_each(|item| render_item(context, item, write_left));
 
     surface.set_spans(
         viewport.x,
@@ -87,11 +173,11 @@ pub fn render(context: &mut RenderContext, viewport: Rect, surface: &mut Surface
 
     // Right side of the status line.
 
-    let element_ids = &config.statusline.right;
-    element_ids
+    config
+        .statusline
+        .right
         .iter()
-        .map(|element_id| get_render_function(*element_id))
-        .for_each(|render| render(context, write_right));
+        .for_each(|item| render_item(context, item, write_right));
 
     surface.set_spans(
         viewport.x
@@ -105,11 +191,11 @@ pub fn render(context: &mut RenderContext, viewport: Rect, surface: &mut Surface
 
     // Center of the status line.
 
-    let element_ids = &config.statusline.center;
-    element_ids
+    config
+        .statusline
+        .center
         .iter()
-        .map(|element_id| get_render_function(*element_id))
-        .for_each(|render| render(context, write_center));
+        .for_each(|item| render_item(context, item, write_center));
 
     // Width of the empty space between the left and center area and between the center and right area.
     let spacing = 1u16;
@@ -133,6 +219,167 @@ fn append(buffer: &mut Spans, text: String, base_style: &Style, style: Option<St
     ));
 }
 
+fn render_item<F>(context: &mut RenderContext, item: &StatusLineItem, write: F)
+where
+    F: Fn(&mut RenderContext, String, Option<Style>) + Copy,
+{
+    match item {
+        StatusLineItem::Element(element_id) => get_render_function(*element_id)(context, write),
+        StatusLineItem::Format { format } => render_format(context, write, format.segments(), None),
+        StatusLineItem::Command { command, interval } => {
+            let output = context.commands.output(context.editor, command, *interval);
+            if !output.is_empty() {
+                write(context, format!(" {} ", output), None);
+            }
+        }
+    }
+}
+
+fn render_format<F>(
+    context: &mut RenderContext,
+    write: F,
+    segments: &[Segment],
+    style: Option<Style>,
+) where
+    F: Fn(&mut RenderContext, String, Option<Style>) + Copy,
+{
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => write(context, text.clone(), style),
+            Segment::Placeholder(placeholder, truncate) => {
+                let value = placeholder_value(context, *placeholder);
+                let value = match truncate {
+                    Some(truncate) => truncate_value(value, *truncate),
+                    None => value,
+                };
+                write(context, value, style)
+            }
+            Segment::Conditional {
+                placeholder,
+                negate,
+                body,
+            } => {
+                let value = placeholder_value(context, *placeholder);
+                let set = !value.is_empty() && value != "0";
+                if set != *negate {
+                    render_format(context, write, body, style);
+                }
+            }
+            Segment::Styled { scope, body } => {
+                let scope_style = context.editor.theme.get(scope);
+                let style = style.map_or(scope_style, |style| style.patch(scope_style));
+                render_format(context, write, body, Some(style));
+            }
+        }
+    }
+}
+
+/// Shortens `value` to the truncation width, marking the cut with an ellipsis.
+fn truncate_value(value: String, truncate: Truncate) -> String {
+    use helix_core::unicode::width::UnicodeWidthStr;
+
+    if value.width() <= truncate.width || truncate.width == 0 {
+        return value;
+    }
+
+    let mut width = 1;
+    let fits = |ch: &char| {
+        width += ch.width().unwrap_or(0);
+        width <= truncate.width
+    };
+    if truncate.keep_end {
+        let kept: Vec<_> = value.chars().rev().take_while(fits).collect();
+        std::iter::once('…').chain(kept.into_iter().rev()).collect()
+    } else {
+        let mut kept: String = value.chars().take_while(fits).collect();
+        kept.push('…');
+        kept
+    }
+}
+
+fn placeholder_value(context: &RenderContext, placeholder: Placeholder) -> String {
+    let doc = context.doc;
+    match placeholder {
+        Placeholder::Mode if context.focused => {
+            let modenames = &context.editor.config().statusline.mode;
+            match context.editor.mode() {
+                Mode::Insert => modenames.insert.clone(),
+                Mode::Select => modenames.select.clone(),
+                Mode::Normal => modenames.normal.clone(),
+            }
+        }
+        Placeholder::Mode => String::new(),
+        Placeholder::Spinner => doc
+            .language_server()
+            .and_then(|srv| context.spinners.get(srv.id()))
+            .and_then(|spinner| spinner.frame())
+            .unwrap_or_default()
+            .to_string(),
+        Placeholder::LspServers => doc
+            .language_server()
+            .map(|server| server.name().to_owned())
+            .unwrap_or_default(),
+        Placeholder::LspProgress => doc
+            .language_server()
+            .and_then(|srv| context.spinners.message(srv.id()))
+            .unwrap_or_default()
+            .to_string(),
+        Placeholder::FileName => doc
+            .relative_path()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_else(|| SCRATCH_BUFFER_NAME.to_string()),
+        Placeholder::FileBaseName => doc
+            .relative_path()
+            .and_then(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| SCRATCH_BUFFER_NAME.to_string()),
+        Placeholder::FileModified => if doc.is_modified() { "[+]" } else { "" }.to_string(),
+        Placeholder::FileEncoding => doc.encoding().name().to_string(),
+        Placeholder::FileLineEnding => line_ending_name(doc).to_string(),
+        Placeholder::FileType => doc
+            .language_name()
+            .unwrap_or(DEFAULT_LANGUAGE_NAME)
+            .to_string(),
+        Placeholder::IndentStyle => match doc.indent_style {
+            helix_core::indent::IndentStyle::Tabs => "tabs".to_string(),
+            helix_core::indent::IndentStyle::Spaces(1) => "1 space".to_string(),
+            helix_core::indent::IndentStyle::Spaces(n) => format!("{} spaces", n),
+        },
+        Placeholder::VersionControl => doc.version_control_head().unwrap_or_default().to_string(),
+        Placeholder::Selections => doc.selection(context.view.id).len().to_string(),
+        Placeholder::PrimarySelectionLength => {
+            doc.selection(context.view.id).primary().len().to_string()
+        }
+        Placeholder::Line => (get_position(context).row + 1).to_string(),
+        Placeholder::Column => (get_position(context).col + 1).to_string(),
+        Placeholder::PositionPercentage => {
+            let position = get_position(context);
+            ((position.row + 1) * 100 / doc.text().len_lines()).to_string()
+        }
+        Placeholder::TotalLines => doc.text().len_lines().to_string(),
+        Placeholder::Errors => diagnostic_counts(doc).1.to_string(),
+        Placeholder::Warnings => diagnostic_counts(doc).0.to_string(),
+        Placeholder::WorkspaceErrors => workspace_diagnostic_counts(context.editor).1.to_string(),
+        Placeholder::WorkspaceWarnings => workspace_diagnostic_counts(context.editor).0.to_string(),
+        Placeholder::Recording => context
+            .editor
+            .macro_recording
+            .as_ref()
+            .map(|(register, _)| register.to_string())
+            .unwrap_or_default(),
+        Placeholder::Tab => {
+            let tree = &context.editor.tree;
+            let active = tree.active_tab();
+            tree.tabs()[active]
+                .name
+                .clone()
+                .unwrap_or_else(|| (active + 1).to_string())
+        }
+    }
+}
+
 fn get_render_function<F>(element_id: StatusLineElementID) -> impl Fn(&mut RenderContext, F)
 where
     F: Fn(&mut RenderContext, String, Option<Style>) + Copy,
@@ -219,23 +466,40 @@ fn render_lsp_spinner<F>(context: &mut RenderContext, write: F)
     );
 }
 
-fn render_diagnostics<F>(context: &mut RenderContext, write: F)
-where
-    F: Fn(&mut RenderContext, String, Option<Style>) + Copy,
-{
-    let (warnings, errors) = context
-        .doc
-        .diagnostics()
-        .iter()
+/// Numbers of warnings and errors in the document.
+fn diagnostic_counts(doc: &Document) -> (usize, usize) {
+    doc.diagnostics().iter().fold((0, 0), |mut counts, diag| {
+        use helix_core::diagnostic::Severity;
+        match diag.severity {
+            Some(Severity::Warning) => counts.0 += 1,
+            Some(Severity::Error) | None => counts.1 += 1,
+            _ => {}
+        }
+        counts
+    })
+}
+
+/// Numbers of warnings and errors in the workspace.
+fn workspace_diagnostic_counts(editor: &Editor) -> (usize, usize) {
+    editor
+        .diagnostics
+        .values()
+        .flatten()
         .fold((0, 0), |mut counts, diag| {
-            use helix_core::diagnostic::Severity;
             match diag.severity {
-                Some(Severity::Warning) => counts.0 += 1,
-                Some(Severity::Error) | None => counts.1 += 1,
+                Some(DiagnosticSeverity::WARNING) => counts.0 += 1,
+                Some(DiagnosticSeverity::ERROR) | None => counts.1 += 1,
                 _ => {}
             }
             counts
-        });
+        })
+}
+
+fn render_diagnostics<F>(context: &mut RenderContext, write: F)
+where
+    F: Fn(&mut RenderContext, String, Option<Style>) + Copy,
+{
+    let (warnings, errors) = diagnostic_counts(context.doc);
 
     if warnings > 0 {
         write(
@@ -260,20 +524,7 @@ fn render_workspace_diagnostics<F>(context: &mut RenderContext, write: F)
 where
     F: Fn(&mut RenderContext, String, Option<Style>) + Copy,
 {
-    let (warnings, errors) =
-        context
-            .editor
-            .diagnostics
-            .values()
-            .flatten()
-            .fold((0, 0), |mut counts, diag| {
-                match diag.severity {
-                    Some(DiagnosticSeverity::WARNING) => counts.0 += 1,
-                    Some(DiagnosticSeverity::ERROR) | None => counts.1 += 1,
-                    _ => {}
-                }
-                counts
-            });
+    let (warnings, errors) = workspace_diagnostic_counts(context.editor);
 
     if warnings > 0 || errors > 0 {
         write(context, format!(" {} ", "W"), None);
@@ -382,8 +633,16 @@ fn render_file_line_ending<F>(context: &mut RenderContext, write: F)
 where
     F: Fn(&mut RenderContext, String, Option<Style>) + Copy,
 {
+    write(
+        context,
+        format!(" {} ", line_ending_name(context.doc)),
+        None,
+    );
+}
+
+fn line_ending_name(doc: &Document) -> &'static str {
     use helix_core::LineEnding::*;
-    let line_ending = match context.doc.line_ending {
+    match doc.line_ending {
         Crlf => "CRLF",
         LF => "LF",
         #[cfg(feature = "unicode-lines")]
@@ -398,9 +657,7 @@ fn render_file_line_ending<F>(context: &mut RenderContext, write: F)
         LS => "LS", // U+2028 -- Line Separator
         #[cfg(feature = "unicode-lines")]
         PS => "PS", // U+2029 -- ParagraphSeparator
-    };
-
-    write(context, format!(" {} ", line_ending), None);
+    }
 }
 
 fn render_file_type<F>(context: &mut RenderContext, write: F)
@@ -0,0 +1,503 @@
+//! A terminal emulator which runs a shell in a PTY and is shown in a split in place of a
+//! document.
+//!
+//! While a terminal takes input, keys are sent to the shell. `Ctrl-\ Ctrl-n` switches the
+//! view to normal mode, where a snapshot of the terminal contents including the scrollback
+//! is shown as a document which can be navigated, searched and yanked from like any other.
+//! `i` or `a` switches back to terminal input.
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    path::Path,
+};
+
+use anyhow::Context as _;
+use helix_core::{Position, Selection};
+use helix_view::{
+    document::Mode,
+    graphics::{Color, Modifier, Rect, Style, UnderlineStyle},
+    input::KeyEvent,
+    keyboard::{KeyCode, KeyModifiers},
+    DocumentId, Editor, View, ViewId,
+};
+use portable_pty::{CommandBuilder, MasterPty, PtySize};
+use tokio::sync::mpsc::UnboundedSender;
+use tui::buffer::Buffer as Surface;
+
+use crate::{
+    compositor::{Compositor, Event, EventResult},
+    job::Callback,
+    key,
+    ui::EditorView,
+};
+
+/// The number of lines kept after they scroll off the top of the terminal.
+const SCROLLBACK_LINES: usize = 10_000;
+
+pub struct Terminal {
+    parser: vt100::Parser,
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    child: Box<dyn portable_pty::Child + Send + Sync>,
+    /// The document holding the snapshot of the contents shown in normal mode.
+    doc: DocumentId,
+    /// Whether keys are sent to the terminal, rather than navigating the snapshot.
+    input: bool,
+    /// Set after `Ctrl-\` while waiting for the key which completes the escape sequence.
+    escape_pending: bool,
+}
+
+impl Terminal {
+    /// Starts `command`, or the user's shell if none is given, in a terminal of `size`
+    /// (columns, rows). The output is fed to the terminal of `view` through `callbacks`.
+    pub fn spawn(
+        command: Option<&str>,
+        shell: &[String],
+        cwd: &Path,
+        size: (u16, u16),
+        view: ViewId,
+        doc: DocumentId,
+        callbacks: UnboundedSender<Callback>,
+    ) -> anyhow::Result<Self> {
+        let (cols, rows) = (size.0.max(1), size.1.max(1));
+        let pty = portable_pty::native_pty_system().openpty(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })?;
+
+        let mut cmd = match command {
+            Some(command) => {
+                let (program, args) = shell.split_first().context("No shell set")?;
+                let mut cmd = CommandBuilder::new(program);
+                cmd.args(args);
+                cmd.arg(command);
+                cmd
+            }
+            None => CommandBuilder::new_default_prog(),
+        };
+        cmd.cwd(cwd);
+        cmd.env("TERM", "xterm-256color");
+
+        let child = pty
+            .slave
+            .spawn_command(cmd)
+            .context("failed to start the terminal process")?;
+        let mut reader = pty.master.try_clone_reader()?;
+        let writer = pty.master.take_writer()?;
+
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 8192];
+            loop {
+                let bytes = match reader.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => buf[..n].to_vec(),
+                };
+                let callback = move |_: &mut Editor, compositor: &mut Compositor| {
+                    if let Some(terminal) = terminal_mut(compositor, view) {
+                        terminal.parser.process(&bytes);
+                    }
+                };
+                if callbacks
+                    .send(Callback::EditorCompositor(Box::new(callback)))
+                    .is_err()
+                {
+                    return;
+                }
+            }
+            let _ = callbacks.send(Callback::EditorCompositor(Box::new(
+                move |editor, compositor| exited(editor, compositor, view),
+            )));
+        });
+
+        Ok(Self {
+            parser: vt100::Parser::new(rows, cols, SCROLLBACK_LINES),
+            master: pty.master,
+            writer,
+            child,
+            doc,
+            input: true,
+            escape_pending: false,
+        })
+    }
+
+    pub fn doc(&self) -> DocumentId {
+        self.doc
+    }
+
+    pub fn takes_input(&self) -> bool {
+        self.input
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        if let Err(err) = self
+            .writer
+            .write_all(bytes)
+            .and_then(|_| self.writer.flush())
+        {
+            log::error!("failed to write to terminal: {}", err);
+        }
+    }
+
+    fn resize(&mut self, cols: u16, rows: u16) {
+        let (cols, rows) = (cols.max(1), rows.max(1));
+        if self.parser.screen().size() == (rows, cols) {
+            return;
+        }
+        self.parser.set_size(rows, cols);
+        let size = PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        };
+        if let Err(err) = self.master.resize(size) {
+            log::error!("failed to resize terminal: {}", err);
+        }
+    }
+
+    /// Returns the lines of the scrollback followed by the lines of the screen.
+    fn lines(&mut self) -> Vec<String> {
+        let (rows, cols) = self.parser.screen().size();
+        self.parser.set_scrollback(usize::MAX);
+        let scrollback = self.parser.screen().scrollback();
+
+        // vt100 can't scroll back further than the height of the screen, so the screen is grown
+        // below its lines to fit the whole scrollback above them while it's read
+        self.parser
+            .set_size(rows.saturating_add(scrollback as u16), cols);
+        self.parser.set_scrollback(scrollback);
+        let lines = self.parser.screen().rows(0, cols).collect();
+        self.parser.set_scrollback(0);
+        self.parser.set_size(rows, cols);
+        lines
+    }
+
+    /// Switches from input to navigating a snapshot of the contents in normal mode, with
+    /// the cursor where the terminal cursor is.
+    fn enter_normal_mode(&mut self, editor: &mut Editor, view_id: ViewId) {
+        self.input = false;
+        self.escape_pending = false;
+
+        let mut lines = self.lines();
+        let scrollback = lines.len() - self.parser.screen().size().0 as usize;
+        let (row, col) = self.parser.screen().cursor_position();
+        let cursor_line = scrollback + row as usize;
+        // drop the empty lines below the cursor
+        while lines.len() > cursor_line + 1 && lines.last().map_or(false, |l| l.is_empty()) {
+            lines.pop();
+        }
+        let mut text = lines
+            .iter()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+        text.push('\n');
+
+        let len = match editor.documents.get(&self.doc) {
+            Some(doc) => doc.text().len_chars(),
+            None => return,
+        };
+        editor.write_output_buffer(self.doc, (0, len), &text);
+
+        let scrolloff = editor.config().scrolloff;
+        let view = editor.tree.get_mut(view_id);
+        let doc = doc_mut!(editor, &self.doc);
+        let pos = helix_core::pos_at_coords(
+            doc.text().slice(..),
+            Position::new(cursor_line, col as usize),
+            true,
+        );
+        doc.set_selection(view.id, Selection::point(pos));
+        view.ensure_cursor_in_view(doc, scrolloff);
+    }
+
+    /// Sends `key` to the terminal, except for `Ctrl-\ Ctrl-n` which switches to normal
+    /// mode.
+    fn handle_key(&mut self, key: KeyEvent, editor: &mut Editor, view: ViewId) {
+        if std::mem::take(&mut self.escape_pending) {
+            if key == ctrl_key('n') {
+                self.enter_normal_mode(editor, view);
+                return;
+            }
+            self.write(b"\x1c");
+        } else if key == ctrl_key('\\') || key == ctrl_key('4') {
+            // terminals send `Ctrl-\` as the same byte as `Ctrl-4`
+            self.escape_pending = true;
+            return;
+        }
+
+        let application_cursor = self.parser.screen().application_cursor();
+        if let Some(bytes) = key_to_bytes(key, application_cursor) {
+            self.write(&bytes);
+        }
+    }
+
+    fn paste(&mut self, contents: &str) {
+        if self.parser.screen().bracketed_paste() {
+            self.write(b"\x1b[200~");
+            self.write(contents.as_bytes());
+            self.write(b"\x1b[201~");
+        } else {
+            self.write(contents.as_bytes());
+        }
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+/// The terminals shown in views, keyed by their view.
+#[derive(Default)]
+pub struct Terminals {
+    terminals: HashMap<ViewId, Terminal>,
+}
+
+impl Terminals {
+    pub fn insert(&mut self, view: ViewId, terminal: Terminal) {
+        self.terminals.insert(view, terminal);
+    }
+
+    /// Returns the terminal shown in `view`, unless the view switched to another document.
+    pub fn get(&self, view: &View) -> Option<&Terminal> {
+        self.terminals
+            .get(&view.id)
+            .filter(|terminal| terminal.doc == view.doc)
+    }
+
+    pub fn get_mut(&mut self, view: &View) -> Option<&mut Terminal> {
+        self.terminals
+            .get_mut(&view.id)
+            .filter(|terminal| terminal.doc == view.doc)
+    }
+
+    /// Stops the terminals whose views were closed and closes their documents.
+    pub fn prune(&mut self, editor: &mut Editor) {
+        let closed: Vec<_> = self
+            .terminals
+            .keys()
+            .filter(|view| !editor.tree.contains(**view))
+            .copied()
+            .collect();
+        for view in closed {
+            if let Some(terminal) = self.terminals.remove(&view) {
+                let _ = editor.close_document(terminal.doc, true);
+            }
+        }
+    }
+
+    /// Handles the keys and pastes for the focused view if it's a terminal taking input,
+    /// or switches the terminal to input on `i` and `a` while it's navigated in normal
+    /// mode.
+    pub fn handle_event(&mut self, event: &Event, editor: &mut Editor) -> Option<EventResult> {
+        let view = editor.tree.get(editor.tree.focus);
+        let view_id = view.id;
+        let terminal = self.get_mut(view)?;
+
+        match event {
+            Event::Key(key) if terminal.input => {
+                editor.reset_idle_timer();
+                editor.status_msg = None;
+                terminal.handle_key(*key, editor, view_id);
+            }
+            Event::Key(key!('i') | key!('a')) if editor.mode() == Mode::Normal => {
+                terminal.input = true;
+            }
+            Event::Paste(contents) if terminal.input => terminal.paste(contents),
+            _ => return None,
+        }
+        Some(EventResult::Consumed(None))
+    }
+}
+
+fn terminal_mut(compositor: &mut Compositor, view: ViewId) -> Option<&mut Terminal> {
+    compositor
+        .find::<EditorView>()?
+        .terminals
+        .terminals
+        .get_mut(&view)
+}
+
+/// Leaves the view of a terminal whose process exited in normal mode, showing the last
+/// contents.
+fn exited(editor: &mut Editor, compositor: &mut Compositor, view: ViewId) {
+    let mut terminal = match compositor
+        .find::<EditorView>()
+        .and_then(|editor_view| editor_view.terminals.terminals.remove(&view))
+    {
+        Some(terminal) => terminal,
+        None => return,
+    };
+    if editor.tree.contains(view) {
+        terminal.enter_normal_mode(editor, view);
+    }
+    editor.set_status("Terminal process exited");
+}
+
+/// Renders the screen of `terminal` in the area of `view` above its statusline.
+pub fn render(terminal: &mut Terminal, view: &View, is_focused: bool, surface: &mut Surface) {
+    let area = view.area.clip_bottom(1);
+    terminal.resize(area.width, area.height);
+
+    let screen = terminal.parser.screen();
+    let (rows, cols) = screen.size();
+    for row in 0..rows.min(area.height) {
+        for col in 0..cols.min(area.width) {
+            let cell = match screen.cell(row, col) {
+                Some(cell) => cell,
+                None => continue,
+            };
+            if cell.is_wide_continuation() {
+                continue;
+            }
+
+            let mut style = Style::default()
+                .fg(convert_color(cell.fgcolor()))
+                .bg(convert_color(cell.bgcolor()));
+            if cell.bold() {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            if cell.italic() {
+                style = style.add_modifier(Modifier::ITALIC);
+            }
+            if cell.underline() {
+                style = style.underline_style(UnderlineStyle::Line);
+            }
+            if cell.inverse() {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+
+            let contents = cell.contents();
+            let symbol = if contents.is_empty() { " " } else { &contents };
+            surface[(area.x + col, area.y + row)]
+                .set_symbol(symbol)
+                .set_style(style);
+        }
+    }
+
+    if is_focused && terminal.input && !screen.hide_cursor() {
+        let (row, col) = screen.cursor_position();
+        if row < area.height && col < area.width {
+            let cursor = Rect::new(area.x + col, area.y + row, 1, 1);
+            surface.set_style(cursor, Style::default().add_modifier(Modifier::REVERSED));
+        }
+    }
+}
+
+fn convert_color(color: vt100::Color) -> Color {
+    match color {
+        vt100::Color::Default => Color::Reset,
+        vt100::Color::Rgb(r, g, b) => Color::Rgb(r, g, b),
+        vt100::Color::Idx(index) => match index {
+            0 => Color::Black,
+            1 => Color::Red,
+            2 => Color::Green,
+            3 => Color::Yellow,
+            4 => Color::Blue,
+            5 => Color::Magenta,
+            6 => Color::Cyan,
+            7 => Color::LightGray,
+            8 => Color::Gray,
+            9 => Color::LightRed,
+            10 => Color::LightGreen,
+            11 => Color::LightYellow,
+            12 => Color::LightBlue,
+            13 => Color::LightMagenta,
+            14 => Color::LightCyan,
+            15 => Color::White,
+            index => Color::Indexed(index),
+        },
+    }
+}
+
+fn ctrl_key(ch: char) -> KeyEvent {
+    KeyEvent {
+        code: KeyCode::Char(ch),
+        modifiers: KeyModifiers::CONTROL,
+    }
+}
+
+/// Encodes `key` as the bytes a terminal sends for it.
+fn key_to_bytes(key: KeyEvent, application_cursor: bool) -> Option<Vec<u8>> {
+    let cursor = |code: u8| {
+        let prefix: &[u8] = if application_cursor {
+            b"\x1bO"
+        } else {
+            b"\x1b["
+        };
+        [prefix, &[code]].concat()
+    };
+
+    let mut bytes = match key.code {
+        KeyCode::Char(ch) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            match ch.to_ascii_lowercase() {
+                ch @ 'a'..='z' => vec![ch as u8 - b'a' + 1],
+                '@' | ' ' => vec![0],
+                '[' => vec![0x1b],
+                '\\' => vec![0x1c],
+                ']' => vec![0x1d],
+                '^' => vec![0x1e],
+                '_' => vec![0x1f],
+                _ => return None,
+            }
+        }
+        KeyCode::Char(ch) => ch.to_string().into_bytes(),
+        KeyCode::Enter => b"\r".to_vec(),
+        KeyCode::Backspace => vec![0x7f],
+        KeyCode::Tab if key.modifiers.contains(KeyModifiers::SHIFT) => b"\x1b[Z".to_vec(),
+        KeyCode::Tab => b"\t".to_vec(),
+        KeyCode::Esc => vec![0x1b],
+        KeyCode::Up => cursor(b'A'),
+        KeyCode::Down => cursor(b'B'),
+        KeyCode::Right => cursor(b'C'),
+        KeyCode::Left => cursor(b'D'),
+        KeyCode::Home => cursor(b'H'),
+        KeyCode::End => cursor(b'F'),
+        KeyCode::Insert => b"\x1b[2~".to_vec(),
+        KeyCode::Delete => b"\x1b[3~".to_vec(),
+        KeyCode::PageUp => b"\x1b[5~".to_vec(),
+        KeyCode::PageDown => b"\x1b[6~".to_vec(),
+        KeyCode::F(n @ 1..=4) => vec![0x1b, b'O', b'P' + n - 1],
+        KeyCode::F(n @ 5..=12) => {
+            let code = [15, 17, 18, 19, 20, 21, 23, 24][n as usize - 5];
+            format!("\x1b[{}~", code).into_bytes()
+        }
+        _ => return None,
+    };
+
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        bytes.insert(0, 0x1b);
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use helix_view::input::parse_macro;
+
+    fn encode(keys: &str) -> Vec<u8> {
+        parse_macro(keys)
+            .unwrap()
+            .into_iter()
+            .flat_map(|key| key_to_bytes(key, false).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_key_to_bytes() {
+        assert_eq!(encode("ls<ret>"), b"ls\r");
+        assert_eq!(encode("<C-c><C-d>"), [0x03, 0x04]);
+        assert_eq!(encode("<A-b><backspace>"), b"\x1bb\x7f");
+        assert_eq!(encode("<up><F5>"), b"\x1b[A\x1b[15~");
+        assert_eq!(
+            key_to_bytes(parse_macro("<up>").unwrap()[0], true),
+            Some(b"\x1bOA".to_vec())
+        );
+    }
+}
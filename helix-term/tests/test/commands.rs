@@ -108,6 +108,33 @@ async fn test_selection_duplication() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_select_occurrences() -> anyhow::Result<()> {
+    test((
+        "#[foo|]# bar foo baz foo",
+        "<C-n>",
+        "#(foo|)# bar #[foo|]# baz foo",
+    ))
+    .await?;
+
+    test((
+        "#[foo|]# bar foo baz foo",
+        "<C-n><C-A-n>",
+        "#(foo|)# bar foo baz #[foo|]#",
+    ))
+    .await?;
+
+    // wraps around to the last occurrence
+    test((
+        "#[foo|]# bar foo baz foo",
+        "<C-p>",
+        "#(foo|)# bar foo baz #[foo|]#",
+    ))
+    .await?;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_goto_file_impl() -> anyhow::Result<()> {
     let file = tempfile::NamedTempFile::new()?;
@@ -385,3 +412,570 @@ async fn test_character_info() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_surround_tag() -> anyhow::Result<()> {
+    test(("#[hello|]#", "mstdiv<ret>", "#[<div>hello</div>|]#")).await?;
+    test((
+        "<div class=\"a\">h#[e|]#llo</div>",
+        "mrttp<ret>",
+        "<p class=\"a\">h#[e|]#llo</p>",
+    ))
+    .await?;
+    test(("(h#[e|]#llo)", "mr(tb<ret>", "<b>h#[e|]#llo</b>")).await?;
+    test(("<p><b>h#[e|]#llo</b></p>", "2mdt", "<b>h#[e|]#llo</b>")).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_toggle_comments() -> anyhow::Result<()> {
+    // languages without line comments use block comments
+    test_with_config(
+        AppBuilder::new().with_file("foo.html", None),
+        ("  <p>#[a|]#</p>", "<C-c>", "  <!-- <p>#[a|]#</p> -->"),
+    )
+    .await?;
+
+    test_with_config(
+        AppBuilder::new().with_file("foo.rs", None),
+        (
+            "let x = #[1 + 2|]#;",
+            "<space>C",
+            "let x = #[/* 1 + 2 */|]#;",
+        ),
+    )
+    .await?;
+    test_with_config(
+        AppBuilder::new().with_file("foo.rs", None),
+        (
+            "let x = #[/* 1 + 2 */|]#;",
+            "<space>C",
+            "let x = #[1 + 2|]#;",
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_buffer_word_completion() -> anyhow::Result<()> {
+    let mut app = helpers::AppBuilder::new()
+        .with_input_text("hello\n#[|\n]#")
+        .build()?;
+
+    // completions are shown asynchronously, so they're selected in a second step
+    test_key_sequences(
+        &mut app,
+        vec![
+            (Some("ihe<C-x>"), None),
+            (
+                Some("<tab><esc>"),
+                Some(&|app| {
+                    assert_eq!("hello\nhello\n", helix_view::doc!(app.editor).text());
+                }),
+            ),
+        ],
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_buffer_picker_close_marked() -> anyhow::Result<()> {
+    test_key_sequence(
+        &mut helpers::AppBuilder::new().build()?,
+        Some(":new<ret>:new<ret><space>b<C-space><C-space><C-x>"),
+        Some(&|app| {
+            assert_eq!(1, app.editor.documents().count());
+            assert!(!app.editor.is_err());
+        }),
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_restore_last_picker() -> anyhow::Result<()> {
+    use helix_view::workspace::PickerState;
+
+    let mut app = helpers::AppBuilder::new().build()?;
+    // the restored query selects the second buffer rather than the first one
+    app.editor.workspace_state.last_picker = Some(PickerState {
+        command: "buffer_picker".to_string(),
+        query: "2".to_string(),
+        cursor: 0,
+    });
+
+    // modified buffers aren't replaced by `:new`
+    test_key_sequence(
+        &mut app,
+        Some("ia<esc>:new<ret>ib<esc>:new<ret><space>'<ret>"),
+        Some(&|app| {
+            let second = app.editor.documents().nth(1).unwrap();
+            assert_eq!(second.id(), helix_view::doc!(app.editor).id());
+            assert_eq!(
+                Some("buffer_picker"),
+                app.editor
+                    .workspace_state
+                    .last_picker
+                    .as_ref()
+                    .map(|state| state.command.as_str())
+            );
+        }),
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quickfix_from_buffer() -> anyhow::Result<()> {
+    let file = helpers::temp_file_with_contents("one\ntwo\nthree\n")?;
+    let path = helix_core::path::get_canonicalized_path(file.path())?;
+    let output = format!(
+        "#[{0}:2:2: error: first\n{0}:3:1: warning: second|]#\n",
+        path.display()
+    );
+    let mut app = helpers::AppBuilder::new().with_input_text(output).build()?;
+
+    let cursor = |app: &Application| {
+        let (view, doc) = helix_view::current_ref!(app.editor);
+        doc.selection(view.id)
+            .primary()
+            .cursor(doc.text().slice(..))
+    };
+
+    test_key_sequences(
+        &mut app,
+        vec![
+            (
+                Some(":quickfix<minus>from<minus>buffer<ret>"),
+                Some(&|app| {
+                    assert_eq!(Some(&path), helix_view::doc!(app.editor).path());
+                    assert_eq!(2, app.editor.quickfix.entries().len());
+                    assert_eq!(5, cursor(app));
+                }),
+            ),
+            // the entries follow the edits to the document
+            (
+                Some("ggOzero<esc>]q"),
+                Some(&|app| {
+                    assert_eq!(
+                        "zero\none\ntwo\nthree\n",
+                        helix_view::doc!(app.editor).text()
+                    );
+                    assert_eq!(13, cursor(app));
+                }),
+            ),
+        ],
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_make() -> anyhow::Result<()> {
+    let file = helpers::temp_file_with_contents("one\ntwo\nthree\n")?;
+    let path = helix_core::path::get_canonicalized_path(file.path())?;
+
+    let mut config = helpers::test_config();
+    config.editor.tasks.insert(
+        "build".into(),
+        helix_core::syntax::TaskConfiguration {
+            command: format!("echo '{}:2:2: error: bad'; exit 1", path.display()),
+            error_formats: None,
+        },
+    );
+    let mut app = helpers::AppBuilder::new()
+        .with_config(config)
+        .with_file(&path, None)
+        .build()?;
+
+    test_key_sequences(
+        &mut app,
+        vec![
+            (
+                Some(":make<ret>"),
+                Some(&|app| {
+                    let output = app.editor.task_output.expect("task output buffer");
+                    let output = helix_view::doc!(app.editor, &output);
+                    // the output can't be undone line by line
+                    let history = output.history.take();
+                    assert_eq!(0, history.current_revision());
+                    output.history.set(history);
+                    let output = output.text().to_string();
+                    assert!(output.contains("error: bad"), "{}", output);
+                    assert!(output.contains("failed with exit code 1"), "{}", output);
+
+                    let doc = app.editor.document_by_path(&path).unwrap();
+                    assert_eq!(1, doc.diagnostics().len());
+                    assert_eq!(5, doc.diagnostics()[0].range.start);
+                    assert_eq!(1, app.editor.quickfix.entries().len());
+                }),
+            ),
+            // the output is shown in a split
+            (Some(":qa!<ret>"), None),
+        ],
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_terminal() -> anyhow::Result<()> {
+    let mut app = helpers::AppBuilder::new().build()?;
+
+    test_key_sequences(
+        &mut app,
+        vec![
+            (
+                Some(":terminal echo hello<ret>"),
+                Some(&|app| {
+                    // the process exited, leaving its output in the view in normal mode
+                    assert_eq!(2, app.editor.tree.views().count());
+                    let text = helix_view::doc!(app.editor).text().to_string();
+                    assert!(text.starts_with("hello\n"), "{:?}", text);
+                }),
+            ),
+            (
+                Some(":terminal seq 1 200<ret>"),
+                Some(&|app| {
+                    // the lines which scrolled off are kept once each, in order
+                    let doc = helix_view::doc!(app.editor);
+                    let lines: Vec<_> = doc.text().lines().map(|l| l.to_string()).collect();
+                    let expected: Vec<_> = (1..=200).map(|n| format!("{}\n", n)).collect();
+                    assert_eq!(expected, lines[..200]);
+                    let history = doc.history.take();
+                    assert_eq!(0, history.current_revision());
+                    doc.history.set(history);
+                }),
+            ),
+            (Some(":qa!<ret>"), None),
+        ],
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tab_pages() -> anyhow::Result<()> {
+    let file = helpers::temp_file_with_contents("one")?;
+    let mut app = helpers::AppBuilder::new()
+        .with_file(file.path(), None)
+        .build()?;
+
+    test_key_sequences(
+        &mut app,
+        vec![
+            (
+                Some(":vsplit<ret>:tab<minus>new<ret>:tab<minus>rename scratch<ret>"),
+                Some(&|app| {
+                    let tree = &app.editor.tree;
+                    assert_eq!(2, tree.tabs().len());
+                    assert_eq!(1, tree.active_tab());
+                    assert_eq!(Some("scratch"), tree.tabs()[1].name.as_deref());
+                    assert_eq!(1, tree.traverse().count());
+                    assert_eq!(None, helix_view::doc!(app.editor).path());
+                }),
+            ),
+            (
+                Some(":tab<minus>move 1<ret>:tab<minus>next<ret>"),
+                Some(&|app| {
+                    // the first tab page is back with both of its splits
+                    let tree = &app.editor.tree;
+                    assert_eq!(1, tree.active_tab());
+                    assert_eq!(None, tree.tabs()[1].name.as_deref());
+                    assert_eq!(2, tree.traverse().count());
+                    assert_eq!(3, tree.views().count());
+                }),
+            ),
+            (
+                Some(":tab<minus>previous<ret>:tab<minus>close<ret>"),
+                Some(&|app| {
+                    let tree = &app.editor.tree;
+                    assert_eq!(1, tree.tabs().len());
+                    assert_eq!(2, tree.traverse().count());
+                }),
+            ),
+            (
+                Some(":tab<minus>close<ret>"),
+                Some(&|app| {
+                    assert_eq!(1, app.editor.tree.tabs().len());
+                    assert_eq!(
+                        &helix_view::editor::Severity::Error,
+                        app.editor.get_status().unwrap().1
+                    );
+                }),
+            ),
+            (Some(":qa!<ret>"), None),
+        ],
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_buffer_pin_and_move() -> anyhow::Result<()> {
+    let one = helpers::temp_file_with_contents("one")?;
+    let two = helpers::temp_file_with_contents("two")?;
+    let three = helpers::temp_file_with_contents("three")?;
+    let mut app = helpers::AppBuilder::new()
+        .with_file(one.path(), None)
+        .with_file(two.path(), None)
+        .with_file(three.path(), None)
+        .build()?;
+
+    fn position(app: &Application) -> usize {
+        let doc_id = helix_view::doc!(app.editor).id();
+        app.editor.buffer_position(doc_id).unwrap()
+    }
+
+    test_key_sequences(
+        &mut app,
+        vec![
+            (
+                Some(":buffer<minus>pin<ret>"),
+                Some(&|app| {
+                    // pinned buffers come first
+                    assert_eq!(0, position(app));
+                    assert!(app.editor.is_pinned(helix_view::doc!(app.editor).id()));
+                }),
+            ),
+            (
+                Some(":bn<ret>:buffer<minus>move 1<ret>"),
+                Some(&|app| {
+                    // unpinned buffers stay after the pinned ones
+                    assert_eq!(1, position(app));
+                }),
+            ),
+            (
+                Some(":buffer<minus>move +5<ret>"),
+                Some(&|app| assert_eq!(2, position(app))),
+            ),
+            (
+                Some(":bn<ret>"),
+                Some(&|app| {
+                    // navigation follows the bufferline order
+                    assert_eq!(0, position(app));
+                }),
+            ),
+            (
+                Some(":bp<ret>:buffer<minus>close<minus>others<ret>"),
+                Some(&|app| {
+                    // the pinned buffer is kept
+                    assert_eq!(2, app.editor.buffers().count());
+                    assert_eq!(1, position(app));
+                }),
+            ),
+        ],
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_panels() -> anyhow::Result<()> {
+    let file = helpers::temp_file_with_contents("fn main() {}\n")?;
+    let mut app = helpers::AppBuilder::new()
+        .with_file(file.path(), None)
+        .build()?;
+
+    test_key_sequences(
+        &mut app,
+        vec![
+            (
+                Some("<space>gb<esc>:debug<minus>panels<ret>"),
+                Some(&|app| {
+                    let panels = &app.editor.debug_panels;
+                    assert!(panels.visible);
+                    assert!(panels.focused);
+                    let breakpoints: Vec<_> = app.editor.breakpoints.values().flatten().collect();
+                    assert_eq!(1, breakpoints.len());
+                    assert!(breakpoints[0].enabled);
+                }),
+            ),
+            (
+                Some("ax + 1<ret>"),
+                Some(&|app| {
+                    let watches = &app.editor.debug_panels.watches;
+                    assert_eq!(1, watches.len());
+                    assert_eq!("x + 1", watches[0].expression);
+                    assert!(watches[0].result.is_none());
+                }),
+            ),
+            // the rows are the headers of the variables, watches, call stack and breakpoints
+            // sections, each followed by its entries or a placeholder
+            (
+                Some("jjjd"),
+                Some(&|app| {
+                    assert_eq!(3, app.editor.debug_panels.selected);
+                    assert!(app.editor.debug_panels.watches.is_empty());
+                }),
+            ),
+            (
+                Some("jjjj<space>"),
+                Some(&|app| {
+                    let breakpoints: Vec<_> = app.editor.breakpoints.values().flatten().collect();
+                    assert!(!breakpoints[0].enabled);
+                }),
+            ),
+            (
+                Some("<esc>"),
+                Some(&|app| {
+                    assert!(app.editor.debug_panels.visible);
+                    assert!(!app.editor.debug_panels.focused);
+                }),
+            ),
+            (
+                Some(":debug<minus>panels<ret>"),
+                Some(&|app| {
+                    assert!(!app.editor.debug_panels.visible);
+                }),
+            ),
+        ],
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_breakpoints_follow_edits() -> anyhow::Result<()> {
+    let file = helpers::temp_file_with_contents("a\nb\nc\n")?;
+    let mut app = helpers::AppBuilder::new()
+        .with_file(file.path(), None)
+        .build()?;
+
+    let lines = |app: &Application| -> Vec<usize> {
+        let breakpoints = app.editor.breakpoints.values().flatten();
+        breakpoints.map(|breakpoint| breakpoint.line).collect()
+    };
+
+    test_key_sequences(
+        &mut app,
+        vec![
+            (
+                Some("jj<space>gb<esc>"),
+                Some(&|app| assert_eq!(vec![2], lines(app))),
+            ),
+            (
+                Some("ggOnew<esc>"),
+                Some(&|app| assert_eq!(vec![3], lines(app))),
+            ),
+            (Some("ggjxd"), Some(&|app| assert_eq!(vec![2], lines(app)))),
+        ],
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_function_breakpoint() -> anyhow::Result<()> {
+    test_key_sequences(
+        &mut helpers::AppBuilder::new().build()?,
+        vec![
+            (
+                Some(":debug<minus>function<minus>breakpoint main<ret>"),
+                Some(&|app| {
+                    let breakpoints = &app.editor.function_breakpoints;
+                    assert_eq!(1, breakpoints.len());
+                    assert_eq!("main", breakpoints[0].name);
+                }),
+            ),
+            (
+                Some(":dbf main<ret>"),
+                Some(&|app| {
+                    assert!(app.editor.function_breakpoints.is_empty());
+                }),
+            ),
+            (
+                Some(":dbd x<ret>"),
+                Some(&|app| {
+                    assert!(app.editor.data_breakpoints.is_empty());
+                    assert!(app.editor.is_err());
+                }),
+            ),
+        ],
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_console() -> anyhow::Result<()> {
+    let file = helpers::temp_file_with_contents("fn main() {}\n")?;
+    let mut app = helpers::AppBuilder::new()
+        .with_file(file.path(), None)
+        .build()?;
+
+    test_key_sequences(
+        &mut app,
+        vec![
+            (
+                Some(":debug<minus>console<ret>"),
+                Some(&|app| {
+                    let console = app.editor.debug_console_doc().expect("console buffer");
+                    assert!(app.editor.tree.views().any(|(view, _)| view.doc == console));
+                    // the focus stays on the file
+                    assert_ne!(console, app.editor.tree.get(app.editor.tree.focus).doc);
+                    assert_eq!("", app.editor.documents[&console].text());
+                }),
+            ),
+            (
+                Some("<space>gR1 + 1<ret>"),
+                Some(&|app| {
+                    let console = app.editor.debug_console_doc().unwrap();
+                    assert_eq!(
+                        "> 1 + 1\nNo debugger is running\n",
+                        app.editor.documents[&console].text()
+                    );
+                    assert!(!app.editor.documents[&console].is_modified());
+                    assert_eq!(
+                        Some("1 + 1"),
+                        app.editor.registers.last('>').map(|s| s.as_str())
+                    );
+                }),
+            ),
+            (
+                Some("<esc>:debug<minus>console stdout<ret>"),
+                Some(&|app| {
+                    assert_eq!(Some("stdout"), app.editor.debug_console.filter.as_deref());
+                    let console = app.editor.debug_console_doc().unwrap();
+                    assert_eq!(
+                        "> 1 + 1\nNo debugger is running\n",
+                        app.editor.documents[&console].text()
+                    );
+                }),
+            ),
+            // the console is shown in a split
+            (Some(":qa!<ret>"), None),
+        ],
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
@@ -86,10 +86,33 @@ pub fn encode(input: &[u8]) -> String {
     String::from_utf8(output).expect("Invalid UTF8")
 }
 
+/// Decodes standard base64, with or without padding. Returns `None` for invalid input.
+pub fn decode(input: &str) -> Option<Vec<u8>> {
+    let input = input.trim_end_matches(PAD_BYTE as char).as_bytes();
+    if input.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    for chunk in input.chunks(4) {
+        let mut chunk_int: u32 = 0;
+        for &byte in chunk {
+            let value = ENCODE_TABLE.iter().position(|&b| b == byte)? as u32;
+            chunk_int = chunk_int.shl(6_u8) | value;
+        }
+        // align the bits of a short last chunk as if it was complete
+        chunk_int = chunk_int.shl(6 * (4 - chunk.len() as u32));
+        let bytes = chunk_int.to_be_bytes();
+        output.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(output)
+}
+
 #[cfg(test)]
 mod tests {
     fn compare_encode(expected: &str, target: &[u8]) {
         assert_eq!(expected, super::encode(target));
+        assert_eq!(Some(target.to_vec()), super::decode(expected));
     }
 
     #[test]
@@ -160,4 +183,11 @@ fn encode_all_bytes() {
             &bytes,
         );
     }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(super::decode("Zm9v!"), None);
+        assert_eq!(super::decode("Zm9vY"), None);
+        assert_eq!(super::decode("Zm8"), Some(b"fo".to_vec()));
+    }
 }
@@ -0,0 +1,76 @@
+//! Keeps the breakpoints on their lines while the documents are edited.
+
+use helix_core::Assoc;
+
+use crate::{DocumentId, Editor};
+
+/// The char positions of the breakpoints of a file in its document, valid at `revision`.
+#[derive(Debug, Clone)]
+pub struct BreakpointAnchor {
+    doc: DocumentId,
+    revision: usize,
+    /// The lines of the breakpoints when the positions were taken. If the lines are changed
+    /// since, such as by the debugger, the positions are taken again.
+    lines: Vec<usize>,
+    positions: Vec<usize>,
+}
+
+impl Editor {
+    /// Moves the breakpoints with the changes made to their documents since the last sync, so
+    /// that they stay on the same lines of text.
+    pub fn sync_breakpoints(&mut self) {
+        for doc in self.documents.values_mut() {
+            let path = match doc.path() {
+                Some(path) => path.clone(),
+                None => continue,
+            };
+            let breakpoints = match self.breakpoints.get_mut(&path) {
+                Some(breakpoints) if !breakpoints.is_empty() => breakpoints,
+                _ => continue,
+            };
+
+            let doc_id = doc.id();
+            let revision = doc.get_current_revision();
+            match self.breakpoint_anchors.get(&path) {
+                Some(anchor)
+                    if anchor.doc == doc_id
+                        && breakpoints
+                            .iter()
+                            .map(|breakpoint| breakpoint.line)
+                            .eq(anchor.lines.iter().copied()) =>
+                {
+                    if anchor.revision == revision {
+                        continue;
+                    }
+                    if let Some(transaction) = doc.history.get_mut().changes_since(anchor.revision)
+                    {
+                        let text = doc.text().slice(..);
+                        for (breakpoint, &pos) in breakpoints.iter_mut().zip(&anchor.positions) {
+                            // text inserted at the start of the line pushes the breakpoint down
+                            let pos = transaction.changes().map_pos(pos, Assoc::After);
+                            breakpoint.line = text.char_to_line(pos.min(text.len_chars()));
+                        }
+                    }
+                }
+                // the breakpoints or the document are new since the last sync
+                _ => {}
+            }
+
+            let text = doc.text().slice(..);
+            let last_line = text.len_lines().saturating_sub(1);
+            let anchor = BreakpointAnchor {
+                doc: doc_id,
+                revision,
+                lines: breakpoints
+                    .iter()
+                    .map(|breakpoint| breakpoint.line)
+                    .collect(),
+                positions: breakpoints
+                    .iter()
+                    .map(|breakpoint| text.line_to_char(breakpoint.line.min(last_line)))
+                    .collect(),
+            };
+            self.breakpoint_anchors.insert(path, anchor);
+        }
+    }
+}
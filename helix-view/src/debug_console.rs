@@ -0,0 +1,122 @@
+//! The debug console: a buffer accumulating the output of the debuggee and the debug adapter,
+//! and the results of the expressions evaluated in its prompt.
+
+use helix_core::Selection;
+
+use crate::{DocumentId, Editor};
+
+/// The category of output without one, as defined by the debug adapter protocol.
+pub const DEFAULT_CATEGORY: &str = "console";
+/// The category of the expressions evaluated in the console and their results.
+pub const REPL_CATEGORY: &str = "repl";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputEntry {
+    /// The category of the output, such as `stdout`, `stderr` or `console`.
+    pub category: String,
+    pub text: String,
+}
+
+#[derive(Debug, Default)]
+pub struct DebugConsole {
+    /// The buffer showing the output, if open.
+    pub doc: Option<DocumentId>,
+    pub entries: Vec<OutputEntry>,
+    /// Only the output of this category is shown, along with the evaluated expressions.
+    pub filter: Option<String>,
+}
+
+impl DebugConsole {
+    pub fn shows(&self, entry: &OutputEntry) -> bool {
+        match &self.filter {
+            Some(filter) => entry.category == *filter || entry.category == REPL_CATEGORY,
+            None => true,
+        }
+    }
+
+    /// The text of the console buffer.
+    pub fn text(&self) -> String {
+        self.entries
+            .iter()
+            .filter(|entry| self.shows(entry))
+            .map(|entry| entry.text.as_str())
+            .collect()
+    }
+}
+
+impl Editor {
+    /// Adds output to the debug console, appending it to the console buffer if it is open.
+    /// The views showing the buffer follow the output, unless the user is in them.
+    pub fn debug_console_output(&mut self, category: Option<String>, text: String) {
+        let entry = OutputEntry {
+            category: category.unwrap_or_else(|| DEFAULT_CATEGORY.to_owned()),
+            text,
+        };
+        let shown = self.debug_console.shows(&entry);
+        let text = entry.text.clone();
+        self.debug_console.entries.push(entry);
+
+        let doc_id = match self.debug_console_doc() {
+            Some(doc_id) if shown => doc_id,
+            _ => return,
+        };
+        let len = doc!(self, &doc_id).text().len_chars();
+        self.write_output_buffer(doc_id, (len, len), &text);
+
+        let focus = self.tree.focus;
+        let scrolloff = self.config().scrolloff;
+        let doc = doc_mut!(self, &doc_id);
+        let end = doc.text().len_chars();
+        for (view, _) in self.tree.views_mut() {
+            if view.doc == doc_id && view.id != focus {
+                doc.set_selection(view.id, Selection::point(end));
+                view.ensure_cursor_in_view(doc, scrolloff);
+            }
+        }
+    }
+
+    /// The console buffer, unless it was closed.
+    pub fn debug_console_doc(&self) -> Option<DocumentId> {
+        self.debug_console
+            .doc
+            .filter(|id| self.documents.contains_key(id))
+    }
+
+    /// Replaces the text of the console buffer with the entries shown by the current filter.
+    pub fn rebuild_debug_console(&mut self) {
+        if let Some(doc_id) = self.debug_console_doc() {
+            let len = doc!(self, &doc_id).text().len_chars();
+            let text = self.debug_console.text();
+            self.write_output_buffer(doc_id, (0, len), &text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(category: &str, text: &str) -> OutputEntry {
+        OutputEntry {
+            category: category.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn text() {
+        let mut console = DebugConsole {
+            entries: vec![
+                entry("stdout", "hello\n"),
+                entry("stderr", "oops\n"),
+                entry(REPL_CATEGORY, "> 1 + 1\n2\n"),
+                entry("stdout", "world\n"),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(console.text(), "hello\noops\n> 1 + 1\n2\nworld\n");
+
+        console.filter = Some("stdout".to_string());
+        assert_eq!(console.text(), "hello\n> 1 + 1\n2\nworld\n");
+    }
+}
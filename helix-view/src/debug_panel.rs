@@ -0,0 +1,350 @@
+//! The debugger panels docked beside the views: the variables of the active stack frame, watch
+//! expressions, the call stack of the active thread and the breakpoints.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use helix_dap::{self as dap, StackFrame};
+
+use crate::editor::{Breakpoint, DataBreakpoint};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Section {
+    Variables,
+    Watches,
+    CallStack,
+    Breakpoints,
+}
+
+impl Section {
+    pub const ALL: [Section; 4] = [
+        Section::Variables,
+        Section::Watches,
+        Section::CallStack,
+        Section::Breakpoints,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Section::Variables => "Variables",
+            Section::Watches => "Watches",
+            Section::CallStack => "Call Stack",
+            Section::Breakpoints => "Breakpoints",
+        }
+    }
+}
+
+/// A scope or variable in the variables tree, or the result of a watch expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableNode {
+    pub name: String,
+    pub value: String,
+    pub ty: Option<String>,
+    /// Non-zero if the node has children, which are fetched with `Client::variables`.
+    pub variables_reference: usize,
+    pub expanded: bool,
+    /// `None` until the children are fetched.
+    pub children: Option<Vec<VariableNode>>,
+    /// The address of the value in the debuggee's memory, if the debug adapter knows it.
+    pub memory_reference: Option<String>,
+}
+
+impl VariableNode {
+    pub fn new(
+        name: String,
+        value: String,
+        ty: Option<String>,
+        variables_reference: usize,
+    ) -> Self {
+        Self {
+            name,
+            value,
+            ty,
+            variables_reference,
+            expanded: false,
+            children: None,
+            memory_reference: None,
+        }
+    }
+
+    pub fn has_children(&self) -> bool {
+        self.variables_reference != 0
+    }
+}
+
+impl From<dap::Variable> for VariableNode {
+    fn from(variable: dap::Variable) -> Self {
+        Self {
+            memory_reference: variable.memory_reference,
+            ..Self::new(
+                variable.name,
+                variable.value,
+                variable.ty,
+                variable.variables_reference,
+            )
+        }
+    }
+}
+
+impl From<dap::Scope> for VariableNode {
+    fn from(scope: dap::Scope) -> Self {
+        Self::new(scope.name, String::new(), None, scope.variables_reference)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Watch {
+    pub expression: String,
+    /// The result of the last evaluation, `None` until the debugger stops.
+    pub result: Option<Result<VariableNode, String>>,
+}
+
+impl Watch {
+    pub fn new(expression: String) -> Self {
+        Self {
+            expression,
+            result: None,
+        }
+    }
+}
+
+/// The tree a [VariableNode] belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeTree {
+    /// The scopes of the active stack frame and their variables.
+    Variables,
+    /// The result of the watch expression at this index.
+    Watch(usize),
+}
+
+/// A line of the panels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Row {
+    Section(Section),
+    /// A node of a tree, addressed by the indices of the children leading to it. The root of a
+    /// watch has an empty path.
+    Node {
+        tree: NodeTree,
+        path: Vec<usize>,
+    },
+    /// A watch expression without a value.
+    Watch(usize),
+    /// A stack frame of the active thread.
+    Frame(usize),
+    Breakpoint {
+        path: PathBuf,
+        index: usize,
+    },
+    FunctionBreakpoint(usize),
+    DataBreakpoint(usize),
+    /// A placeholder for a section without rows.
+    Empty(Section),
+}
+
+#[derive(Debug, Default)]
+pub struct DebugPanels {
+    pub visible: bool,
+    /// Whether keys go to the panels rather than the views.
+    pub focused: bool,
+    /// The index of the selected row.
+    pub selected: usize,
+    pub collapsed: HashSet<Section>,
+    pub scopes: Vec<VariableNode>,
+    pub watches: Vec<Watch>,
+}
+
+impl DebugPanels {
+    /// Forgets the values of the last debugging session, keeping the watch expressions.
+    pub fn clear_values(&mut self) {
+        self.scopes.clear();
+        for watch in &mut self.watches {
+            watch.result = None;
+        }
+    }
+
+    pub fn node(&self, tree: NodeTree, path: &[usize]) -> Option<&VariableNode> {
+        let (root, path) = match tree {
+            NodeTree::Variables => {
+                let (&first, rest) = path.split_first()?;
+                (self.scopes.get(first)?, rest)
+            }
+            NodeTree::Watch(i) => (self.watches.get(i)?.result.as_ref()?.as_ref().ok()?, path),
+        };
+        path.iter()
+            .try_fold(root, |node, &i| node.children.as_ref()?.get(i))
+    }
+
+    pub fn node_mut(&mut self, tree: NodeTree, path: &[usize]) -> Option<&mut VariableNode> {
+        let (root, path) = match tree {
+            NodeTree::Variables => {
+                let (&first, rest) = path.split_first()?;
+                (self.scopes.get_mut(first)?, rest)
+            }
+            NodeTree::Watch(i) => (
+                self.watches.get_mut(i)?.result.as_mut()?.as_mut().ok()?,
+                path,
+            ),
+        };
+        path.iter()
+            .try_fold(root, |node, &i| node.children.as_mut()?.get_mut(i))
+    }
+
+    /// The rows of the panels, given the stack frames of the active thread, the breakpoints of
+    /// each file and the function and data breakpoints.
+    pub fn rows(
+        &self,
+        frames: &[StackFrame],
+        breakpoints: &HashMap<PathBuf, Vec<Breakpoint>>,
+        function_breakpoints: &[dap::FunctionBreakpoint],
+        data_breakpoints: &[DataBreakpoint],
+    ) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for section in Section::ALL {
+            rows.push(Row::Section(section));
+            if self.collapsed.contains(&section) {
+                continue;
+            }
+            let start = rows.len();
+
+            match section {
+                Section::Variables => push_nodes(
+                    &mut rows,
+                    NodeTree::Variables,
+                    &self.scopes,
+                    &mut Vec::new(),
+                ),
+                Section::Watches => {
+                    for (i, watch) in self.watches.iter().enumerate() {
+                        match &watch.result {
+                            Some(Ok(node)) => {
+                                let tree = NodeTree::Watch(i);
+                                rows.push(Row::Node {
+                                    tree,
+                                    path: Vec::new(),
+                                });
+                                if let (true, Some(children)) = (node.expanded, &node.children) {
+                                    push_nodes(&mut rows, tree, children, &mut Vec::new());
+                                }
+                            }
+                            _ => rows.push(Row::Watch(i)),
+                        }
+                    }
+                }
+                Section::CallStack => rows.extend((0..frames.len()).map(Row::Frame)),
+                Section::Breakpoints => {
+                    let mut paths: Vec<_> = breakpoints.keys().collect();
+                    paths.sort();
+                    for path in paths {
+                        rows.extend((0..breakpoints[path].len()).map(|index| Row::Breakpoint {
+                            path: path.clone(),
+                            index,
+                        }));
+                    }
+                    rows.extend((0..function_breakpoints.len()).map(Row::FunctionBreakpoint));
+                    rows.extend((0..data_breakpoints.len()).map(Row::DataBreakpoint));
+                }
+            }
+
+            if rows.len() == start {
+                rows.push(Row::Empty(section));
+            }
+        }
+        rows
+    }
+}
+
+fn push_nodes(rows: &mut Vec<Row>, tree: NodeTree, nodes: &[VariableNode], path: &mut Vec<usize>) {
+    for (i, node) in nodes.iter().enumerate() {
+        path.push(i);
+        rows.push(Row::Node {
+            tree,
+            path: path.clone(),
+        });
+        if let (true, Some(children)) = (node.expanded, &node.children) {
+            push_nodes(rows, tree, children, path);
+        }
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn node(name: &str, children: Option<Vec<VariableNode>>) -> VariableNode {
+        VariableNode {
+            expanded: children.is_some(),
+            children,
+            ..VariableNode::new(name.to_string(), String::new(), None, 1)
+        }
+    }
+
+    #[test]
+    fn rows() {
+        let mut panels = DebugPanels {
+            scopes: vec![
+                node("Locals", Some(vec![node("a", None), node("b", None)])),
+                node("Globals", None),
+            ],
+            watches: vec![Watch::new("a + b".to_string())],
+            ..Default::default()
+        };
+        panels.collapsed.insert(Section::Breakpoints);
+
+        let node_row = |path: &[usize]| Row::Node {
+            tree: NodeTree::Variables,
+            path: path.to_vec(),
+        };
+        assert_eq!(
+            panels.rows(&[], &HashMap::new(), &[], &[]),
+            [
+                Row::Section(Section::Variables),
+                node_row(&[0]),
+                node_row(&[0, 0]),
+                node_row(&[0, 1]),
+                node_row(&[1]),
+                Row::Section(Section::Watches),
+                Row::Watch(0),
+                Row::Section(Section::CallStack),
+                Row::Empty(Section::CallStack),
+                Row::Section(Section::Breakpoints),
+            ]
+        );
+
+        assert_eq!(panels.node(NodeTree::Variables, &[0, 1]).unwrap().name, "b");
+        assert!(panels.node(NodeTree::Variables, &[1, 0]).is_none());
+
+        panels.watches[0].result = Some(Ok(node("a + b", Some(vec![node("x", None)]))));
+        let rows = panels.rows(&[], &HashMap::new(), &[], &[]);
+        assert_eq!(
+            rows[6..8],
+            [
+                Row::Node {
+                    tree: NodeTree::Watch(0),
+                    path: Vec::new()
+                },
+                Row::Node {
+                    tree: NodeTree::Watch(0),
+                    path: vec![0]
+                },
+            ]
+        );
+        assert_eq!(panels.node(NodeTree::Watch(0), &[0]).unwrap().name, "x");
+
+        panels.collapsed.clear();
+        let function_breakpoints = [dap::FunctionBreakpoint {
+            name: "main".to_string(),
+            ..Default::default()
+        }];
+        let rows = panels.rows(&[], &HashMap::new(), &function_breakpoints, &[]);
+        assert_eq!(
+            rows[rows.len() - 2..],
+            [
+                Row::Section(Section::Breakpoints),
+                Row::FunctionBreakpoint(0)
+            ]
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! The values of the variables referenced on the lines of the stopped stack frame's function,
+//! shown at the end of the lines while debugging.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use helix_core::{
+    line_ending::line_end_char_index,
+    syntax::RopeProvider,
+    text_annotations::InlineAnnotation,
+    tree_sitter::{Node, QueryCursor},
+    RopeSlice,
+};
+use helix_dap::Client;
+use log::warn;
+
+use crate::{handlers::dap::dap_pos_to_pos, Document, Editor};
+
+/// The most chars of a value shown, as the values of collections can be long.
+const MAX_VALUE_LEN: usize = 40;
+
+/// The lines of the function around `line`, or only `line` if the function isn't known.
+pub fn function_lines(doc: &Document, line: usize) -> Range<usize> {
+    let text = doc.text().slice(..);
+    let (syntax, query) = match (
+        doc.syntax(),
+        doc.language_config()
+            .and_then(|config| config.textobject_query()),
+    ) {
+        (Some(syntax), Some(query)) => (syntax, query),
+        _ => return line..line + 1,
+    };
+
+    let byte = text.char_to_byte(text.line_to_char(line));
+    let mut cursor = QueryCursor::new();
+    let function = query
+        .capture_nodes(
+            "function.around",
+            syntax.tree().root_node(),
+            text,
+            &mut cursor,
+        )
+        .and_then(|nodes| {
+            nodes
+                .filter(|node| node.byte_range().contains(&byte))
+                .min_by_key(|node| node.end_byte() - node.start_byte())
+        });
+    match function {
+        Some(function) => {
+            let start = text.byte_to_line(function.start_byte());
+            let end = text.byte_to_line(function.end_byte().min(text.len_bytes()));
+            start..end + 1
+        }
+        None => line..line + 1,
+    }
+}
+
+/// The identifiers the locals query finds on each of `lines`, in order and without duplicates.
+pub fn referenced_identifiers(doc: &Document, lines: Range<usize>) -> Vec<(usize, String)> {
+    let text = doc.text().slice(..);
+    let (syntax, query) = match (
+        doc.syntax(),
+        doc.language_config()
+            .and_then(|config| config.locals_query()),
+    ) {
+        (Some(syntax), Some(query)) => (syntax, query),
+        _ => return Vec::new(),
+    };
+
+    let start = text.line_to_byte(lines.start.min(text.len_lines()));
+    let end = text.line_to_byte(lines.end.min(text.len_lines()));
+    let mut cursor = QueryCursor::new();
+    cursor.set_byte_range(start..end);
+
+    let names = query.capture_names();
+    let mut identifiers: Vec<(usize, String)> = Vec::new();
+    for (query_match, index) in
+        cursor.captures(query, syntax.tree().root_node(), RopeProvider(text))
+    {
+        let capture = query_match.captures[index];
+        let name = &names[capture.index as usize];
+        if name != "local.reference" && !name.starts_with("local.definition") {
+            continue;
+        }
+        let identifier = node_text(capture.node, text);
+        let line = text.byte_to_line(capture.node.start_byte());
+        if !identifiers
+            .iter()
+            .any(|(l, i)| *l == line && *i == identifier)
+        {
+            identifiers.push((line, identifier));
+        }
+    }
+    identifiers.sort_by_key(|(line, _)| *line);
+    identifiers
+}
+
+fn node_text(node: Node, text: RopeSlice) -> String {
+    text.byte_slice(node.start_byte()..node.end_byte())
+        .to_string()
+}
+
+/// The annotations at the end of the lines listing the values of their identifiers, for the
+/// identifiers which have one.
+pub fn annotations(
+    text: RopeSlice,
+    identifiers: &[(usize, String)],
+    values: &HashMap<String, String>,
+) -> Vec<InlineAnnotation> {
+    let mut annotations: Vec<InlineAnnotation> = Vec::new();
+    let mut last_line = None;
+    for (line, identifier) in identifiers {
+        let value = match values.get(identifier) {
+            Some(value) => value,
+            None => continue,
+        };
+        let value = match value.char_indices().nth(MAX_VALUE_LEN) {
+            Some((end, _)) => format!("{}…", &value[..end]),
+            None => value.clone(),
+        };
+        if last_line == Some(*line) {
+            let annotation = annotations.last_mut().unwrap();
+            annotation.text = format!("{}, {} = {}", annotation.text, identifier, value).into();
+        } else {
+            let char_idx = line_end_char_index(&text, *line);
+            annotations.push(InlineAnnotation::new(
+                char_idx,
+                format!("  {} = {}", identifier, value),
+            ));
+            last_line = Some(*line);
+        }
+    }
+    annotations
+}
+
+/// The values of the variables of the scopes of a stack frame by name, with the innermost scopes
+/// first. The expensive scopes, such as of the global variables, are skipped.
+async fn fetch_values(debugger: &Client, frame_id: usize) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let scopes = match debugger.scopes(frame_id).await {
+        Ok(scopes) => scopes,
+        Err(err) => {
+            warn!("Failed to fetch scopes: {}", err);
+            return values;
+        }
+    };
+    for scope in scopes.iter().filter(|scope| !scope.expensive) {
+        if let Ok(variables) = debugger.variables(scope.variables_reference).await {
+            for variable in variables {
+                values.entry(variable.name).or_insert(variable.value);
+            }
+        }
+    }
+    values
+}
+
+impl Editor {
+    /// Shows the values of the variables of the current stack frame in its function.
+    pub async fn refresh_debug_values(&mut self) {
+        self.clear_debug_values();
+
+        let debugger = match self.debuggers.active() {
+            Some(debugger) => debugger,
+            None => return,
+        };
+        let frame = match debugger.current_stack_frame() {
+            Some(frame) => frame,
+            None => return,
+        };
+        let path = match frame
+            .source
+            .as_ref()
+            .and_then(|source| source.path.as_ref())
+        {
+            Some(path) => path,
+            None => return,
+        };
+        let doc = match self.document_by_path(path) {
+            Some(doc) => doc,
+            None => return,
+        };
+
+        let text = doc.text().slice(..);
+        let line = match dap_pos_to_pos(doc.text(), frame.line, 0) {
+            Some(pos) => text.char_to_line(pos.min(text.len_chars())),
+            None => return,
+        };
+        let identifiers = referenced_identifiers(doc, function_lines(doc, line));
+        if identifiers.is_empty() {
+            return;
+        }
+        let values = fetch_values(debugger, frame.id).await;
+
+        let annotations = annotations(text, &identifiers, &values);
+        let doc_id = doc.id();
+        doc_mut!(self, &doc_id).set_debug_values(annotations);
+    }
+
+    pub fn clear_debug_values(&mut self) {
+        for doc in self.documents_mut() {
+            if !doc.debug_values().is_empty() {
+                doc.set_debug_values(Vec::new());
+            }
+        }
+    }
+
+    /// Marks the debuggee as running after it was continued or stepped.
+    pub fn resume_debuggee(&mut self) {
+        if let Some(debugger) = self.debuggers.active_mut() {
+            debugger.resume_application();
+        }
+        self.clear_debug_values();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use helix_core::Rope;
+
+    #[test]
+    fn test_annotations() {
+        let text = Rope::from("let x = 1;\nlet y = x + z;\nprint(s);\n");
+        let identifiers = [
+            (0, "x".to_string()),
+            (1, "y".to_string()),
+            (1, "x".to_string()),
+            (1, "z".to_string()),
+            (2, "s".to_string()),
+        ];
+        let values = HashMap::from([
+            ("x".to_string(), "1".to_string()),
+            ("y".to_string(), "3".to_string()),
+            ("s".to_string(), "a".repeat(50)),
+        ]);
+
+        let annotations = annotations(text.slice(..), &identifiers, &values);
+        let annotations: Vec<_> = annotations
+            .iter()
+            .map(|annotation| (annotation.char_idx, annotation.text.as_str()))
+            .collect();
+        let long = format!("  s = {}…", "a".repeat(MAX_VALUE_LEN));
+        assert_eq!(
+            annotations,
+            vec![(10, "  x = 1"), (25, "  y = 3, x = 1"), (35, long.as_str())]
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! The disassembly of the code around the instruction pointer of the current stack frame, and
+//! hex dumps of the debuggee's memory, for debugging native code.
+
+use std::fmt::Write;
+
+use helix_core::Selection;
+use helix_dap::DisassembledInstruction;
+use log::warn;
+
+use crate::{align_view, Align, DocumentId, Editor};
+
+/// The number of instructions disassembled before and after the instruction pointer.
+pub const DISASSEMBLY_CONTEXT: usize = 32;
+/// The number of bytes read for the memory view.
+pub const MEMORY_VIEW_LEN: usize = 256;
+const BYTES_PER_LINE: usize = 16;
+
+#[derive(Debug, Default)]
+pub struct Disassembly {
+    /// The buffer showing the disassembly, if open.
+    pub doc: Option<DocumentId>,
+}
+
+/// Parses an address as the debug adapter protocol formats them, in hex with a `0x` prefix or
+/// in decimal.
+pub fn parse_address(address: &str) -> Option<u64> {
+    match address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => address.parse().ok(),
+    }
+}
+
+/// The text of the disassembly buffer, with a label before the instructions of each symbol, and
+/// the line of the instruction at `pointer`, which is marked.
+pub fn format_instructions(
+    instructions: &[DisassembledInstruction],
+    pointer: Option<u64>,
+) -> (String, Option<usize>) {
+    let bytes_width = instructions
+        .iter()
+        .filter_map(|instruction| instruction.instruction_bytes.as_ref())
+        .map(|bytes| bytes.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut text = String::new();
+    let mut line = 0;
+    let mut current = None;
+    let mut symbol = None;
+    for instruction in instructions {
+        if instruction.symbol.is_some() && instruction.symbol != symbol {
+            symbol = instruction.symbol.clone();
+            writeln!(text, "{}:", symbol.as_ref().unwrap()).unwrap();
+            line += 1;
+        }
+
+        let is_current = pointer.is_some() && parse_address(&instruction.address) == pointer;
+        if is_current {
+            current = Some(line);
+        }
+        writeln!(
+            text,
+            "{} {}  {:width$}  {}",
+            if is_current { '▶' } else { ' ' },
+            instruction.address,
+            instruction.instruction_bytes.as_deref().unwrap_or_default(),
+            instruction.instruction,
+            width = bytes_width,
+        )
+        .unwrap();
+        line += 1;
+    }
+    (text, current)
+}
+
+/// A hex dump of `bytes` read at `address`, followed by `unreadable` bytes shown as `??`.
+pub fn hex_dump(address: u64, bytes: &[u8], unreadable: usize) -> String {
+    let len = bytes.len() + unreadable;
+    let mut text = String::new();
+    for start in (0..len).step_by(BYTES_PER_LINE) {
+        write!(text, "0x{:016x} ", address.wrapping_add(start as u64)).unwrap();
+        let mut ascii = String::new();
+        for i in start..start + BYTES_PER_LINE {
+            if i % BYTES_PER_LINE == BYTES_PER_LINE / 2 {
+                text.push(' ');
+            }
+            match bytes.get(i) {
+                Some(&byte) => {
+                    write!(text, " {:02x}", byte).unwrap();
+                    ascii.push(if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
+                    });
+                }
+                None if i < len => {
+                    text.push_str(" ??");
+                    ascii.push('?');
+                }
+                None => text.push_str("   "),
+            }
+        }
+        writeln!(text, "  |{}|", ascii).unwrap();
+    }
+    text
+}
+
+impl Editor {
+    /// The disassembly buffer, unless it was closed.
+    pub fn disassembly_doc(&self) -> Option<DocumentId> {
+        self.disassembly
+            .doc
+            .filter(|id| self.documents.contains_key(id))
+    }
+
+    /// Disassembles the code around the instruction pointer of the current stack frame into the
+    /// disassembly buffer if it is open, moving the cursors of its views to the instruction.
+    pub async fn refresh_disassembly(&mut self) {
+        let doc_id = match self.disassembly_doc() {
+            Some(doc_id) => doc_id,
+            None => return,
+        };
+
+        let pointer = self
+            .current_stack_frame()
+            .and_then(|frame| frame.instruction_pointer_reference.clone());
+        let (text, current) = match (self.debuggers.active(), pointer) {
+            (Some(debugger), Some(pointer)) => {
+                let count = DISASSEMBLY_CONTEXT * 2 + 1;
+                match debugger
+                    .disassemble(pointer.clone(), -(DISASSEMBLY_CONTEXT as isize), count)
+                    .await
+                {
+                    Ok(instructions) => format_instructions(&instructions, parse_address(&pointer)),
+                    Err(err) => {
+                        warn!("Failed to disassemble: {}", err);
+                        (format!("Failed to disassemble: {}\n", err), None)
+                    }
+                }
+            }
+            (Some(_), None) => ("No instruction pointer\n".to_owned(), None),
+            (None, _) => ("No debugger is running\n".to_owned(), None),
+        };
+
+        let len = doc!(self, &doc_id).text().len_chars();
+        self.write_output_buffer(doc_id, (0, len), &text);
+
+        let line = match current {
+            Some(line) => line,
+            None => return,
+        };
+        let doc = doc_mut!(self, &doc_id);
+        let pos = doc.text().line_to_char(line);
+        for (view, _) in self.tree.views_mut() {
+            if view.doc == doc_id {
+                doc.set_selection(view.id, Selection::point(pos));
+                align_view(doc, view, Align::Center);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn instruction(
+        address: &str,
+        bytes: &str,
+        text: &str,
+        symbol: &str,
+    ) -> DisassembledInstruction {
+        DisassembledInstruction {
+            address: address.to_string(),
+            instruction_bytes: Some(bytes.to_string()),
+            instruction: text.to_string(),
+            symbol: Some(symbol.to_string()),
+            location: None,
+            line: None,
+            column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(parse_address("0x1f"), Some(31));
+        assert_eq!(parse_address("31"), Some(31));
+        assert_eq!(parse_address("main"), None);
+    }
+
+    #[test]
+    fn test_format_instructions() {
+        let instructions = [
+            instruction("0x10", "55", "push rbp", "main"),
+            instruction("0x11", "48 89 e5", "mov rbp, rsp", "main"),
+            instruction("0x20", "c3", "ret", "exit"),
+        ];
+        let (text, current) = format_instructions(&instructions, Some(0x11));
+        assert_eq!(
+            text,
+            "main:\n  \
+             0x10  55        push rbp\n\
+             ▶ 0x11  48 89 e5  mov rbp, rsp\n\
+             exit:\n  \
+             0x20  c3        ret\n"
+        );
+        assert_eq!(current, Some(2));
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        let bytes: Vec<u8> = (b'a'..b'a' + 18).collect();
+        assert_eq!(
+            hex_dump(0x1000, &bytes, 2),
+            "0x0000000000001000  61 62 63 64 65 66 67 68  69 6a 6b 6c 6d 6e 6f 70  |abcdefghijklmnop|\n\
+             0x0000000000001010  71 72 ?? ??                                       |qr??|\n"
+        );
+    }
+}
@@ -25,6 +25,8 @@
 use std::time::SystemTime;
 
 use helix_core::{
+    completion::{self, CompletionSource},
+    diagnostic::DiagnosticProvider,
     encoding,
     history::{History, State, UndoKind},
     indent::{auto_detect_indent_style, IndentStyle},
@@ -105,6 +107,9 @@ pub struct DocumentSavedEvent {
     pub text: Rope,
 }
 
+/// The words of a document with the number of times each occurs.
+type WordCounts = Arc<HashMap<String, usize>>;
+
 pub type DocumentSavedEventResult = Result<DocumentSavedEvent, anyhow::Error>;
 pub type DocumentSavedEventFuture = BoxFuture<'static, DocumentSavedEventResult>;
 
@@ -127,6 +132,12 @@ pub struct Document {
     /// Set to `true` when the document is updated, reset to `false` on the next inlay hints
     /// update from the LSP
     pub inlay_hints_oudated: bool,
+    /// Set to `true` when the document is opened or updated, reset to `false` when its
+    /// diagnostics are pulled from language servers supporting pull diagnostics
+    pub pulled_diagnostics_outdated: bool,
+    /// The values of the variables referenced on the lines of the stopped stack frame, shown at
+    /// the end of the lines while debugging.
+    debug_values: Rc<[InlineAnnotation]>,
 
     path: Option<PathBuf>,
     encoding: &'static encoding::Encoding,
@@ -169,6 +180,13 @@ pub struct Document {
 
     diff_handle: Option<DiffHandle>,
     version_control_head: Option<Arc<ArcSwap<Box<str>>>>,
+
+    /// Whether only the editor changes the text, for buffers it writes to such as the output of
+    /// a task. Edits are ignored, so that the history stays empty.
+    readonly: bool,
+    /// The words of the text for completion along with the version they were counted at, so
+    /// that they're only counted again once the text changed.
+    words: Mutex<Option<(i32, WordCounts)>>,
 }
 
 /// Inlay hints for a single `(Document, View)` combo.
@@ -246,6 +264,10 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("text", &self.text)
             .field("selections", &self.selections)
             .field("inlay_hints_oudated", &self.inlay_hints_oudated)
+            .field(
+                "pulled_diagnostics_outdated",
+                &self.pulled_diagnostics_outdated,
+            )
             .field("text_annotations", &self.inlay_hints)
             .field("path", &self.path)
             .field("encoding", &self.encoding)
@@ -478,6 +500,8 @@ pub fn from(
             selections: HashMap::default(),
             inlay_hints: HashMap::default(),
             inlay_hints_oudated: false,
+            pulled_diagnostics_outdated: true,
+            debug_values: Rc::new([]),
             indent_style: DEFAULT_INDENT,
             line_ending: DEFAULT_LINE_ENDING,
             restore_cursor: false,
@@ -496,6 +520,8 @@ pub fn from(
             diff_handle: None,
             config,
             version_control_head: None,
+            readonly: false,
+            words: Mutex::new(None),
         }
     }
     pub fn default(config: Arc<dyn DynAccess<Config>>) -> Self {
@@ -994,6 +1020,7 @@ fn apply_impl(&mut self, transaction: &Transaction, view_id: ViewId) -> bool {
             };
 
             self.inlay_hints_oudated = true;
+            self.pulled_diagnostics_outdated = true;
             for text_annotation in self.inlay_hints.values_mut() {
                 let DocumentInlayHints {
                     id: _,
@@ -1010,6 +1037,7 @@ fn apply_impl(&mut self, transaction: &Transaction, view_id: ViewId) -> bool {
                 apply_inlay_hint_changes(other_inlay_hints);
                 apply_inlay_hint_changes(padding_after_inlay_hints);
             }
+            apply_inlay_hint_changes(&mut self.debug_values);
 
             // emit lsp notification
             if let Some(language_server) = self.language_server() {
@@ -1030,6 +1058,10 @@ fn apply_impl(&mut self, transaction: &Transaction, view_id: ViewId) -> bool {
 
     /// Apply a [`Transaction`] to the [`Document`] to change its text.
     pub fn apply(&mut self, transaction: &Transaction, view_id: ViewId) -> bool {
+        if self.readonly && !transaction.changes().is_empty() {
+            return false;
+        }
+
         // store the state just before any changes are made. This allows us to undo to the
         // state just before a transaction was applied.
         if self.changes.is_empty() && !transaction.changes().is_empty() {
@@ -1050,6 +1082,23 @@ pub fn apply(&mut self, transaction: &Transaction, view_id: ViewId) -> bool {
         success
     }
 
+    /// Apply a [`Transaction`] of the editor to a read-only [`Document`]. It isn't recorded in
+    /// the history, which stays empty since the document can't be edited.
+    pub fn apply_without_history(&mut self, transaction: &Transaction, view: &mut View) -> bool {
+        debug_assert!(
+            self.readonly,
+            "only read-only documents can be changed without history"
+        );
+        let success = self.apply_impl(transaction, view.id);
+        if success {
+            // reset changeset to fix len
+            self.changes = ChangeSet::new(self.text());
+            // Update jumplist entries in the view.
+            view.apply(transaction, self);
+        }
+        success
+    }
+
     fn undo_redo_impl(&mut self, view: &mut View, undo: bool) -> bool {
         let mut history = self.history.take();
         let txn = if undo { history.undo() } else { history.redo() };
@@ -1173,6 +1222,17 @@ pub fn id(&self) -> DocumentId {
         self.id
     }
 
+    /// Whether only the editor can change the text.
+    pub fn readonly(&self) -> bool {
+        self.readonly
+    }
+
+    /// Makes the document read-only, for buffers the editor writes to. Only call this before
+    /// the document is edited, as its history is left as is.
+    pub fn set_readonly(&mut self, readonly: bool) {
+        self.readonly = readonly;
+    }
+
     /// If there are unsaved modifications.
     pub fn is_modified(&self) -> bool {
         let history = self.history.take();
@@ -1254,11 +1314,38 @@ pub fn language_config(&self) -> Option<&LanguageConfiguration> {
         self.language.as_deref()
     }
 
+    /// The language configuration at the char position `pos`. This is the configuration
+    /// of the injected language if `pos` is inside a language injection.
+    pub fn language_config_at(&self, pos: usize) -> Option<Arc<LanguageConfiguration>> {
+        let byte = self.text.char_to_byte(pos);
+        self.syntax
+            .as_ref()
+            .and_then(|syntax| syntax.language_config_for_byte_range(byte, byte))
+            .or_else(|| self.language.clone())
+    }
+
     /// Current document version, incremented at each change.
     pub fn version(&self) -> i32 {
         self.version
     }
 
+    /// The words of the document offered for completion, with the number of times each
+    /// occurs. They're counted again only if the document changed since the last call.
+    pub fn words(&self) -> Arc<HashMap<String, usize>> {
+        let mut words = self.words.lock();
+        match &*words {
+            Some((version, words)) if *version == self.version => words.clone(),
+            _ => {
+                let counts = Arc::new(completion::count_words(
+                    self.text.slice(..),
+                    completion::MIN_WORD_LEN,
+                ));
+                *words = Some((self.version, counts.clone()));
+                counts
+            }
+        }
+    }
+
     /// Language server if it has been initialized.
     pub fn language_server(&self) -> Option<&helix_lsp::Client> {
         let server = self.language_server.as_deref()?;
@@ -1311,6 +1398,14 @@ pub fn indent_width(&self) -> usize {
         self.indent_style.indent_width(self.tab_width())
     }
 
+    /// The completion sources for this document in order of their ranking, as configured
+    /// for its language or the editor.
+    pub fn completion_sources(&self) -> Vec<CompletionSource> {
+        self.language_config()
+            .and_then(|config| config.completion_sources.clone())
+            .unwrap_or_else(|| self.config.load().completion_sources.clone())
+    }
+
     pub fn changes(&self) -> &ChangeSet {
         &self.changes
     }
@@ -1391,6 +1486,20 @@ pub fn set_diagnostics(&mut self, diagnostics: Vec<Diagnostic>) {
             .sort_unstable_by_key(|diagnostic| diagnostic.range);
     }
 
+    /// Replaces the diagnostics of `provider` with `diagnostics`, keeping the ones from
+    /// other providers.
+    pub fn replace_diagnostics(
+        &mut self,
+        provider: &DiagnosticProvider,
+        diagnostics: Vec<Diagnostic>,
+    ) {
+        self.diagnostics
+            .retain(|diagnostic| &diagnostic.provider != provider);
+        self.diagnostics.extend(diagnostics);
+        self.diagnostics
+            .sort_unstable_by_key(|diagnostic| diagnostic.range);
+    }
+
     /// Get the document's auto pairs. If the document has a recognized
     /// language config with auto pairs configured, returns that;
     /// otherwise, falls back to the global auto pairs config. If the global
@@ -1477,8 +1586,24 @@ pub fn text_format(&self, mut viewport_width: u16, theme: Option<&Theme>) -> Tex
 
     /// Get the text annotations that apply to the whole document, those that do not apply to any
     /// specific view.
-    pub fn text_annotations(&self, _theme: Option<&Theme>) -> TextAnnotations {
-        TextAnnotations::default()
+    pub fn text_annotations(&self, theme: Option<&Theme>) -> TextAnnotations {
+        let mut text_annotations = TextAnnotations::default();
+        if !self.debug_values.is_empty() {
+            let style = theme
+                .and_then(|t| t.find_scope_index("ui.virtual.debug-value"))
+                .map(Highlight);
+            text_annotations.add_inline_annotations(Rc::clone(&self.debug_values), style);
+        }
+        text_annotations
+    }
+
+    /// Set the debug values shown at the end of the lines, sorted by position.
+    pub fn set_debug_values(&mut self, debug_values: Vec<InlineAnnotation>) {
+        self.debug_values = debug_values.into();
+    }
+
+    pub fn debug_values(&self) -> &[InlineAnnotation] {
+        &self.debug_values
     }
 
     /// Set the inlay hints for this document and `view_id`.
@@ -1693,6 +1818,24 @@ fn changeset_to_changes() {
         );
     }
 
+    #[test]
+    fn readonly_ignores_edits() {
+        let mut doc = Document::from(
+            Rope::from("hello"),
+            None,
+            Arc::new(ArcSwap::new(Arc::new(Config::default()))),
+        );
+        let view = ViewId::default();
+        doc.set_selection(view, Selection::single(5, 5));
+        doc.set_readonly(true);
+
+        let transaction = Transaction::insert(doc.text(), doc.selection(view), " world".into());
+        assert!(!doc.apply(&transaction, view));
+
+        assert_eq!(doc.text(), "hello");
+        assert!(!doc.is_modified());
+    }
+
     #[test]
     fn test_line_ending() {
         assert_eq!(
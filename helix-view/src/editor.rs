@@ -1,14 +1,21 @@
 use crate::{
     align_view,
+    breakpoint::BreakpointAnchor,
     clipboard::{get_clipboard_provider, ClipboardProvider},
+    debug_console::DebugConsole,
+    debug_panel::DebugPanels,
+    disassembly::Disassembly,
     document::{DocumentSavedEventFuture, DocumentSavedEventResult, Mode},
     graphics::{CursorKind, Rect},
     info::Info,
     input::KeyEvent,
+    quickfix::{Quickfix, QuickfixEntry},
+    statusline::StatusLineFormat,
     theme::{self, Theme},
     tree::{self, Tree},
     view::ViewPosition,
-    Align, Document, DocumentId, View, ViewId,
+    workspace::WorkspaceState,
+    Align, Document, DocumentId, TabId, View, ViewId,
 };
 use dap::StackFrame;
 use helix_vcs::DiffProviderRegistry;
@@ -21,7 +28,7 @@
 use std::{
     borrow::Cow,
     cell::Cell,
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     io::stdin,
     num::NonZeroUsize,
     path::{Path, PathBuf},
@@ -43,10 +50,12 @@
 pub use helix_core::register::Registers;
 use helix_core::{
     auto_pairs::AutoPairs,
-    syntax::{self, AutoPairConfig, SoftWrap},
+    completion::CompletionSource,
+    diagnostic::DiagnosticProvider,
+    syntax::{self, AutoPairConfig, SoftWrap, TaskConfiguration},
     Change,
 };
-use helix_core::{Position, Selection};
+use helix_core::{Position, Selection, Transaction};
 use helix_dap as dap;
 use helix_lsp::lsp;
 
@@ -255,6 +264,14 @@ pub struct Config {
     /// Whether to instruct the LSP to replace the entire word when applying a completion
     /// or to only insert new text
     pub completion_replace: bool,
+    /// Sources of completion items in order of their ranking. Defaults to the language
+    /// server, snippets, paths and words from open documents.
+    pub completion_sources: Vec<CompletionSource>,
+    /// Patterns used to parse tool output into quickfix entries, see
+    /// [helix_core::errorformat].
+    pub error_formats: Vec<String>,
+    /// Tasks like `build` or `test` run by `:make`, keyed by their name.
+    pub tasks: HashMap<String, TaskConfiguration>,
     /// Whether to display infoboxes. Defaults to true.
     pub auto_info: bool,
     pub file_picker: FilePickerConfig,
@@ -277,6 +294,15 @@ pub struct Config {
     pub whitespace: WhitespaceConfig,
     /// Persistently display open buffers along the top
     pub bufferline: BufferLine,
+    /// Display tab pages along the top. Defaults to showing them once there is more than one.
+    pub tabline: BufferLine,
+    /// Show the classes and functions enclosing the cursor above each view. Defaults to false.
+    pub breadcrumbs: bool,
+    /// Pin the first lines of the classes and functions enclosing the top of each view.
+    /// Defaults to false.
+    pub sticky_scroll: bool,
+    /// Placement of the debugger panels.
+    pub debug_panel: DebugPanelConfig,
     /// Vertical indent width guides.
     pub indent_guides: IndentGuidesConfig,
     /// Whether to color modes with different colors. Defaults to `false`.
@@ -381,9 +407,9 @@ pub struct SearchConfig {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct StatusLineConfig {
-    pub left: Vec<StatusLineElement>,
-    pub center: Vec<StatusLineElement>,
-    pub right: Vec<StatusLineElement>,
+    pub left: Vec<StatusLineItem>,
+    pub center: Vec<StatusLineItem>,
+    pub right: Vec<StatusLineItem>,
     pub separator: String,
     pub mode: ModeConfig,
 }
@@ -394,13 +420,18 @@ fn default() -> Self {
 
         Self {
             left: vec![
-                E::Mode,
-                E::Spinner,
-                E::FileName,
-                E::FileModificationIndicator,
+                E::Mode.into(),
+                E::Spinner.into(),
+                E::FileName.into(),
+                E::FileModificationIndicator.into(),
             ],
             center: vec![],
-            right: vec![E::Diagnostics, E::Selections, E::Position, E::FileEncoding],
+            right: vec![
+                E::Diagnostics.into(),
+                E::Selections.into(),
+                E::Position.into(),
+                E::FileEncoding.into(),
+            ],
             separator: String::from("│"),
             mode: ModeConfig::default(),
         }
@@ -483,6 +514,93 @@ pub enum StatusLineElement {
     VersionControl,
 }
 
+/// An entry of the statusline: a built-in element, a format string or the output of a shell
+/// command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum StatusLineItem {
+    Element(StatusLineElement),
+    Format {
+        format: StatusLineFormat,
+    },
+    /// The first line of the command's output, re-run every `interval` seconds.
+    Command {
+        command: String,
+        interval: u64,
+    },
+}
+
+impl From<StatusLineElement> for StatusLineItem {
+    fn from(element: StatusLineElement) -> Self {
+        Self::Element(element)
+    }
+}
+
+impl<'de> Deserialize<'de> for StatusLineItem {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Custom {
+            format: Option<StatusLineFormat>,
+            command: Option<String>,
+            interval: Option<u64>,
+        }
+
+        struct ItemVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ItemVisitor {
+            type Value = StatusLineItem;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(
+                    formatter,
+                    "a statusline element name or a table with a `format` or a `command`"
+                )
+            }
+
+            fn visit_str<E>(self, name: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                use serde::de::IntoDeserializer;
+                StatusLineElement::deserialize(name.into_deserializer())
+                    .map(StatusLineItem::Element)
+            }
+
+            fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+            where
+                M: serde::de::MapAccess<'de>,
+            {
+                let deserializer = serde::de::value::MapAccessDeserializer::new(map);
+                match Custom::deserialize(deserializer)? {
+                    Custom {
+                        format: Some(format),
+                        command: None,
+                        interval: None,
+                    } => Ok(StatusLineItem::Format { format }),
+                    Custom {
+                        format: None,
+                        command: Some(command),
+                        interval,
+                    } => Ok(StatusLineItem::Command {
+                        command,
+                        // seconds
+                        interval: interval.unwrap_or(5).max(1),
+                    }),
+                    _ => Err(serde::de::Error::custom(
+                        "expected either a `format` or a `command` (with an optional `interval`)",
+                    )),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(ItemVisitor)
+    }
+}
+
 // Cursor shape is read and used on every rendered frame and so needs
 // to be fast. Therefore we avoid a hashmap and use an enum indexed array.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -537,6 +655,32 @@ fn default() -> Self {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DebugPanelConfig {
+    /// Side of the views the debugger panels are docked to. Defaults to `right`.
+    pub position: PanelPosition,
+    /// Width of the debugger panels in columns. Defaults to 40.
+    pub width: u16,
+}
+
+impl Default for DebugPanelConfig {
+    fn default() -> Self {
+        Self {
+            position: PanelPosition::Right,
+            width: 40,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PanelPosition {
+    Left,
+    #[default]
+    Right,
+}
+
 /// bufferline render modes
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -747,6 +891,10 @@ fn default() -> Self {
             rulers: Vec::new(),
             whitespace: WhitespaceConfig::default(),
             bufferline: BufferLine::default(),
+            tabline: BufferLine::Multiple,
+            breadcrumbs: false,
+            sticky_scroll: false,
+            debug_panel: DebugPanelConfig::default(),
             indent_guides: IndentGuidesConfig::default(),
             color_modes: false,
             soft_wrap: SoftWrap {
@@ -755,6 +903,17 @@ fn default() -> Self {
             },
             text_width: 80,
             completion_replace: false,
+            completion_sources: vec![
+                CompletionSource::LanguageServer,
+                CompletionSource::Snippet,
+                CompletionSource::Path,
+                CompletionSource::Buffer,
+            ],
+            error_formats: helix_core::errorformat::DEFAULT_ERROR_FORMATS
+                .iter()
+                .map(|format| format.to_string())
+                .collect(),
+            tasks: HashMap::new(),
             workspace_lsp_roots: Vec::new(),
         }
     }
@@ -781,17 +940,70 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     }
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct Breakpoint {
+/// The state of a breakpoint in a debug session, given by its debug adapter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BreakpointState {
     pub id: Option<usize>,
     pub verified: bool,
     pub message: Option<String>,
+}
+
+/// A breakpoint on a line. Breakpoints are saved with the workspace, without the state given by
+/// the debuggers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Breakpoint {
+    /// The state of the breakpoint in each debug session it was sent to, by session id.
+    #[serde(skip)]
+    pub states: HashMap<usize, BreakpointState>,
 
     pub line: usize,
+    #[serde(skip)]
     pub column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub hit_condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub log_message: Option<String>,
+    /// Disabled breakpoints are kept but not sent to the debugger.
+    pub enabled: bool,
+    /// Temporary breakpoints are removed when the debugger stops, to run to the cursor.
+    #[serde(skip)]
+    pub temporary: bool,
+}
+
+impl Default for Breakpoint {
+    fn default() -> Self {
+        Self {
+            states: HashMap::new(),
+            line: 0,
+            column: None,
+            condition: None,
+            hit_condition: None,
+            log_message: None,
+            enabled: true,
+            temporary: false,
+        }
+    }
+}
+
+impl Breakpoint {
+    /// Whether the debug adapter of the session `id` could set the breakpoint.
+    pub fn verified(&self, id: usize) -> bool {
+        matches!(self.states.get(&id), Some(state) if state.verified)
+    }
+}
+
+/// A data breakpoint, which stops the debuggee when a variable or expression changes. The data
+/// ids are given by the debugger and only valid during its session.
+#[derive(Debug, Clone)]
+pub struct DataBreakpoint {
+    /// The id of the debug session which gave the data id.
+    pub session: usize,
+    pub data_id: String,
+    pub description: String,
+    pub access_type: Option<String>,
 }
 
 use futures_util::stream::{Flatten, Once};
@@ -802,6 +1014,9 @@ pub struct Editor {
     pub tree: Tree,
     pub next_document_id: DocumentId,
     pub documents: BTreeMap<DocumentId, Document>,
+    /// The order of buffers in the bufferline and for buffer navigation, pinned buffers first.
+    buffer_order: Vec<DocumentId>,
+    pinned_buffers: HashSet<DocumentId>,
 
     // We Flatten<> to resolve the inner DocumentSavedEventFuture. For that we need a stream of streams, hence the Once<>.
     // https://stackoverflow.com/a/66875668
@@ -818,9 +1033,16 @@ pub struct Editor {
     pub diagnostics: BTreeMap<lsp::Url, Vec<lsp::Diagnostic>>,
     pub diff_providers: DiffProviderRegistry,
 
-    pub debugger: Option<dap::Client>,
-    pub debugger_events: SelectAll<UnboundedReceiverStream<dap::Payload>>,
+    /// The debug sessions, one of which is active.
+    pub debuggers: dap::Registry,
     pub breakpoints: HashMap<PathBuf, Vec<Breakpoint>>,
+    /// The positions of the breakpoints in their documents when they were last synced with the
+    /// edits to the documents.
+    pub breakpoint_anchors: HashMap<PathBuf, BreakpointAnchor>,
+    pub function_breakpoints: Vec<dap::FunctionBreakpoint>,
+    pub data_breakpoints: Vec<DataBreakpoint>,
+    /// The exception filters of the debugger which are enabled, with their conditions.
+    pub exception_filters: Vec<dap::ExceptionFilterOptions>,
 
     pub clipboard_provider: Box<dyn ClipboardProvider>,
 
@@ -878,6 +1100,37 @@ pub struct Editor {
     /// field is set and any old requests are automatically
     /// canceled as a result
     pub completion_request_handle: Option<oneshot::Sender<()>>,
+
+    /// State of the workspace which is kept across restarts.
+    pub workspace_state: WorkspaceState,
+
+    /// The quickfix list of locations to step through.
+    pub quickfix: Quickfix,
+    pub debug_panels: DebugPanels,
+    pub debug_console: DebugConsole,
+    pub disassembly: Disassembly,
+    /// The scratch buffer which the output of the last task is written to.
+    pub task_output: Option<DocumentId>,
+    /// The running task is cancelled when this is dropped, like when starting another task.
+    pub task_handle: Option<oneshot::Sender<()>>,
+    /// The locations found in the output of each task by name, shown as diagnostics in
+    /// their documents.
+    pub task_diagnostics: HashMap<String, Vec<QuickfixEntry>>,
+}
+
+fn set_task_diagnostics(doc: &mut Document, task: &str, entries: &[QuickfixEntry]) {
+    let path = match doc.path() {
+        Some(path) => path.clone(),
+        None => return,
+    };
+    let provider = DiagnosticProvider::Task(task.to_string());
+    let text = doc.text().slice(..);
+    let diagnostics = entries
+        .iter()
+        .filter(|entry| entry.path == path)
+        .map(|entry| entry.to_diagnostic(text, provider.clone()))
+        .collect();
+    doc.replace_diagnostics(&provider, diagnostics);
 }
 
 pub type RedrawHandle = (Arc<Notify>, Arc<RwLock<()>>);
@@ -887,7 +1140,7 @@ pub enum EditorEvent {
     DocumentSaved(DocumentSavedEventResult),
     ConfigEvent(ConfigEvent),
     LanguageServerMessage((usize, Call)),
-    DebuggerEvent(dap::Payload),
+    DebuggerEvent((usize, dap::Payload)),
     IdleTimer,
 }
 
@@ -944,6 +1197,8 @@ pub fn new(
             tree: Tree::new(area),
             next_document_id: DocumentId::default(),
             documents: BTreeMap::new(),
+            buffer_order: Vec::new(),
+            pinned_buffers: HashSet::new(),
             saves: HashMap::new(),
             save_queue: SelectAll::new(),
             write_count: 0,
@@ -955,9 +1210,12 @@ pub fn new(
             language_servers: helix_lsp::Registry::new(),
             diagnostics: BTreeMap::new(),
             diff_providers: DiffProviderRegistry::default(),
-            debugger: None,
-            debugger_events: SelectAll::new(),
+            debuggers: dap::Registry::new(),
             breakpoints: HashMap::new(),
+            breakpoint_anchors: HashMap::new(),
+            function_breakpoints: Vec::new(),
+            data_breakpoints: Vec::new(),
+            exception_filters: Vec::new(),
             syn_loader,
             theme_loader,
             last_theme: None,
@@ -977,6 +1235,14 @@ pub fn new(
             needs_redraw: false,
             cursor_cache: Cell::new(None),
             completion_request_handle: None,
+            workspace_state: WorkspaceState::default(),
+            quickfix: Quickfix::default(),
+            debug_panels: DebugPanels::default(),
+            debug_console: DebugConsole::default(),
+            disassembly: Disassembly::default(),
+            task_output: None,
+            task_handle: None,
+            task_diagnostics: HashMap::new(),
         }
     }
 
@@ -1161,6 +1427,7 @@ fn _refresh(&mut self) {
             let doc = doc_mut!(self, &view.doc);
             view.sync_changes(doc);
             view.gutters = config.gutters.clone();
+            view.breadcrumbs = config.breadcrumbs;
             view.ensure_cursor_in_view(doc, config.scrolloff)
         }
     }
@@ -1185,6 +1452,10 @@ pub fn switch(&mut self, id: DocumentId, action: Action) {
             return;
         }
 
+        if let Some(path) = self.documents[&id].path() {
+            self.workspace_state.frecency.record(path);
+        }
+
         self.enter_normal_mode();
 
         match action {
@@ -1201,8 +1472,8 @@ pub fn switch(&mut self, id: DocumentId, action: Action) {
                     // Ensure the buffer is not displayed in any other splits.
                     && !self
                         .tree
-                        .traverse()
-                        .any(|(_, v)| v.doc == doc.id && v.id != view.id);
+                        .views()
+                        .any(|(v, _)| v.doc == doc.id && v.id != view.id);
 
                 let (view, doc) = current!(self);
                 let view_id = view.id;
@@ -1214,7 +1485,7 @@ pub fn switch(&mut self, id: DocumentId, action: Action) {
                     // Copy `doc.id` into a variable before calling `self.documents.remove`, which requires a mutable
                     // borrow, invalidating direct access to `doc.id`.
                     let id = doc.id;
-                    self.documents.remove(&id);
+                    self.remove_document(id);
 
                     // Remove the scratch buffer from any jumplists
                     for (view, _) in self.tree.views_mut() {
@@ -1278,6 +1549,7 @@ fn new_document(&mut self, mut doc: Document) -> DocumentId {
             DocumentId(unsafe { NonZeroUsize::new_unchecked(self.next_document_id.0.get() + 1) });
         doc.id = id;
         self.documents.insert(id, doc);
+        self.buffer_order.push(id);
 
         let (save_sender, save_receiver) = tokio::sync::mpsc::unbounded_channel();
         self.saves.insert(id, save_sender);
@@ -1326,6 +1598,10 @@ pub fn open(&mut self, path: &Path, action: Action) -> Result<DocumentId, Error>
             }
             doc.set_version_control_head(self.diff_providers.get_current_head_name(&path));
 
+            for (task, entries) in &self.task_diagnostics {
+                set_task_diagnostics(&mut doc, task, entries);
+            }
+
             let id = self.new_document(doc);
             let _ = self.launch_language_server(id);
 
@@ -1398,7 +1674,7 @@ enum Action {
             }
         }
 
-        self.documents.remove(&doc_id);
+        self.remove_document(doc_id);
 
         // If the document we removed was visible in all views, we will have no more views. We don't
         // want to close the editor just for a simple buffer close, so we need to create a new view
@@ -1447,6 +1723,57 @@ pub fn save<P: Into<PathBuf>>(
         Ok(())
     }
 
+    /// Returns the tasks which `:make` can run in the current document: the editor's tasks
+    /// along with the tasks of the document's language, which take precedence.
+    pub fn tasks(&self) -> BTreeMap<String, TaskConfiguration> {
+        let mut tasks: BTreeMap<_, _> = self
+            .config()
+            .tasks
+            .iter()
+            .map(|(name, task)| (name.clone(), task.clone()))
+            .collect();
+        if let Some(language) = doc!(self).language_config() {
+            tasks.extend(
+                language
+                    .tasks
+                    .iter()
+                    .map(|(name, task)| (name.clone(), task.clone())),
+            );
+        }
+        tasks
+    }
+
+    /// Replaces the diagnostics from the last run of `task` with `entries` in all documents.
+    pub fn set_task_diagnostics(&mut self, task: &str, entries: Vec<QuickfixEntry>) {
+        for doc in self.documents.values_mut() {
+            set_task_diagnostics(doc, task, &entries);
+        }
+        self.task_diagnostics.insert(task.to_string(), entries);
+    }
+
+    /// Replaces the chars in `range` of a buffer the editor writes to, such as the output of a
+    /// task or the debug console, with `text`. The buffer is made read-only, so that the output
+    /// isn't recorded in its history.
+    pub fn write_output_buffer(&mut self, doc_id: DocumentId, range: (usize, usize), text: &str) {
+        let view = self.tree.get_mut(self.tree.focus);
+        let doc = doc_mut!(self, &doc_id);
+        doc.ensure_view_init(view.id);
+        doc.set_readonly(true);
+
+        let transaction = Transaction::change(
+            doc.text(),
+            std::iter::once((range.0, range.1, Some(text.into()))),
+        );
+        doc.apply_without_history(&transaction, view);
+    }
+
+    /// Updates the positions of the quickfix entries with the edits to their documents.
+    pub fn sync_quickfix(&mut self) {
+        for doc in self.documents.values_mut() {
+            self.quickfix.sync(doc);
+        }
+    }
+
     pub fn resize(&mut self, area: Rect) {
         if self.tree.resize(area) {
             self._refresh();
@@ -1454,7 +1781,10 @@ pub fn resize(&mut self, area: Rect) {
     }
 
     pub fn focus(&mut self, view_id: ViewId) {
-        let prev_id = std::mem::replace(&mut self.tree.focus, view_id);
+        let prev_id = self.tree.focus;
+        // a view on another tab page brings its tab to the front
+        self.tree.focus_tab(self.tree.tab_of(view_id));
+        self.tree.focus = view_id;
 
         // if leaving the view: mode should reset and the cursor should be
         // within view
@@ -1470,6 +1800,27 @@ pub fn focus(&mut self, view_id: ViewId) {
         }
     }
 
+    /// Open the document in a new tab page placed after the current one.
+    pub fn new_tab(&mut self, id: DocumentId, name: Option<String>) -> TabId {
+        self.enter_normal_mode();
+        let tab = self.tree.new_tab(name);
+        self.switch(id, Action::VerticalSplit);
+        tab
+    }
+
+    /// Switch to the tab page at `index`, restoring the view that was focused in it.
+    pub fn focus_tab(&mut self, index: usize) {
+        self.focus(self.tree.tab_focus(index));
+    }
+
+    /// Close every view of the tab page at `index`, which closes the tab itself.
+    pub fn close_tab(&mut self, index: usize) {
+        let views: Vec<_> = self.tree.tab_views(index).map(|(id, _)| id).collect();
+        for view_id in views {
+            self.close(view_id);
+        }
+    }
+
     pub fn focus_next(&mut self) {
         self.focus(self.tree.next());
     }
@@ -1524,6 +1875,60 @@ pub fn documents_mut(&mut self) -> impl Iterator<Item = &mut Document> {
         self.documents.values_mut()
     }
 
+    fn remove_document(&mut self, id: DocumentId) {
+        self.documents.remove(&id);
+        self.buffer_order.retain(|&doc_id| doc_id != id);
+        self.pinned_buffers.remove(&id);
+    }
+
+    /// Documents in bufferline order: pinned buffers first, then the others as the user
+    /// arranged them.
+    pub fn buffers(&self) -> impl DoubleEndedIterator<Item = &Document> {
+        self.buffer_order.iter().map(|id| &self.documents[id])
+    }
+
+    pub fn is_pinned(&self, id: DocumentId) -> bool {
+        self.pinned_buffers.contains(&id)
+    }
+
+    /// Position of the buffer in [Self::buffers].
+    pub fn buffer_position(&self, id: DocumentId) -> Option<usize> {
+        self.buffer_order.iter().position(|&doc_id| doc_id == id)
+    }
+
+    /// Pin or unpin a buffer, moving it to the end of the pinned buffers or to the start of the
+    /// others. Returns whether the buffer is now pinned.
+    pub fn toggle_pin(&mut self, id: DocumentId) -> bool {
+        let pinned = self.pinned_buffers.insert(id);
+        if !pinned {
+            self.pinned_buffers.remove(&id);
+        }
+
+        if let Some(pos) = self.buffer_position(id) {
+            self.buffer_order.remove(pos);
+            let boundary = self.pinned_buffers.len() - pinned as usize;
+            self.buffer_order.insert(boundary, id);
+        }
+        pinned
+    }
+
+    /// Move a buffer to position `to` in [Self::buffers]. Pinned buffers stay before the others.
+    pub fn move_buffer(&mut self, id: DocumentId, to: usize) {
+        let pos = match self.buffer_position(id) {
+            Some(pos) => pos,
+            None => return,
+        };
+        let pinned = self.pinned_buffers.len();
+        let (first, last) = if self.is_pinned(id) {
+            (0, pinned - 1)
+        } else {
+            (pinned, self.buffer_order.len() - 1)
+        };
+
+        self.buffer_order.remove(pos);
+        self.buffer_order.insert(to.clamp(first, last), id);
+    }
+
     pub fn document_by_path<P: AsRef<Path>>(&self, path: P) -> Option<&Document> {
         self.documents()
             .find(|doc| doc.path().map(|p| p == path.as_ref()).unwrap_or(false))
@@ -1593,7 +1998,7 @@ pub async fn wait_event(&mut self) -> EditorEvent {
                 Some(message) = self.language_servers.incoming.next() => {
                     return EditorEvent::LanguageServerMessage(message)
                 }
-                Some(event) = self.debugger_events.next() => {
+                Some(event) = self.debuggers.incoming.next() => {
                     return EditorEvent::DebuggerEvent(event)
                 }
 
@@ -1664,8 +2069,8 @@ pub fn enter_normal_mode(&mut self) {
     }
 
     pub fn current_stack_frame(&self) -> Option<&StackFrame> {
-        self.debugger
-            .as_ref()
+        self.debuggers
+            .active()
             .and_then(|debugger| debugger.current_stack_frame())
     }
 }
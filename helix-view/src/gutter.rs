@@ -248,8 +248,10 @@ pub fn breakpoints<'doc>(
     let error = theme.get("error");
     let info = theme.get("info");
     let breakpoint_style = theme.get("ui.debug.breakpoint");
+    let disabled_style = theme.get("ui.virtual");
 
     let breakpoints = doc.path().and_then(|path| editor.breakpoints.get(path));
+    let session = editor.debuggers.active_id();
 
     let breakpoints = match breakpoints {
         Some(breakpoints) => breakpoints,
@@ -265,7 +267,9 @@ pub fn breakpoints<'doc>(
                 .iter()
                 .find(|breakpoint| breakpoint.line == line)?;
 
-            let style = if breakpoint.condition.is_some() && breakpoint.log_message.is_some() {
+            let style = if !breakpoint.enabled {
+                disabled_style
+            } else if breakpoint.condition.is_some() && breakpoint.log_message.is_some() {
                 error.underline_style(UnderlineStyle::Line)
             } else if breakpoint.condition.is_some() {
                 error
@@ -275,7 +279,8 @@ pub fn breakpoints<'doc>(
                 breakpoint_style
             };
 
-            let sym = if breakpoint.verified { "●" } else { "◯" };
+            let verified = matches!(session, Some(id) if breakpoint.verified(id));
+            let sym = if verified { "●" } else { "◯" };
             write!(out, "{}", sym).unwrap();
             Some(style)
         },
@@ -288,31 +293,47 @@ fn execution_pause_indicator<'doc>(
     theme: &Theme,
     is_focused: bool,
 ) -> GutterFn<'doc> {
-    let style = theme.get("ui.debug.active");
-    let current_stack_frame = editor.current_stack_frame();
-    let frame_line = current_stack_frame.map(|frame| frame.line - 1);
-    let frame_source_path = current_stack_frame.map(|frame| {
-        frame
+    let active_style = theme.get("ui.debug.active");
+    let inactive_style = theme.get("ui.virtual");
+    let active_id = editor.debuggers.active_id();
+    // with several sessions, the frames are marked with the number of the session they belong to
+    let numbered = editor.debuggers.len() > 1;
+
+    let mut frames: Vec<(usize, char, Style)> = Vec::new();
+    for (i, debugger) in editor.debuggers.iter().enumerate() {
+        let frame = match debugger.current_stack_frame() {
+            Some(frame) => frame,
+            None => continue,
+        };
+        let path = frame
             .source
             .as_ref()
-            .and_then(|source| source.path.as_ref())
-    });
-    let should_display_for_current_doc =
-        doc.path().is_some() && frame_source_path.unwrap_or(None) == doc.path();
+            .and_then(|source| source.path.as_ref());
+        if doc.path().is_none() || path != doc.path() {
+            continue;
+        }
+        let sym = match numbered {
+            true => char::from_digit(i as u32 + 1, 10).unwrap_or('▶'),
+            false => '▶',
+        };
+        let frame = (frame.line.saturating_sub(1), sym);
+        // the active session's frame takes precedence on a shared line
+        if Some(debugger.id()) == active_id {
+            frames.insert(0, (frame.0, frame.1, active_style));
+        } else {
+            frames.push((frame.0, frame.1, inactive_style));
+        }
+    }
 
     Box::new(
         move |line: usize, _selected: bool, first_visual_line: bool, out: &mut String| {
-            if !first_visual_line
-                || !is_focused
-                || line != frame_line?
-                || !should_display_for_current_doc
-            {
+            if !first_visual_line || !is_focused {
                 return None;
             }
 
-            let sym = "▶";
+            let (_, sym, style) = frames.iter().find(|(frame_line, ..)| *frame_line == line)?;
             write!(out, "{}", sym).unwrap();
-            Some(style)
+            Some(*style)
         },
     )
 }
@@ -1,17 +1,20 @@
-use crate::editor::{Action, Breakpoint};
+use crate::debug_panel::VariableNode;
+use crate::editor::{Action, Breakpoint, BreakpointState, DataBreakpoint};
 use crate::{align_view, Align, Editor};
 use dap::requests::DisconnectArguments;
+use futures_util::future::{BoxFuture, FutureExt};
 use helix_core::Selection;
 use helix_dap::{self as dap, Client, ConnectionType, Payload, Request, ThreadId};
 use helix_lsp::block_on;
 use log::warn;
+use std::collections::HashMap;
 use std::fmt::Write;
 use std::path::PathBuf;
 
 #[macro_export]
 macro_rules! debugger {
     ($editor:expr) => {{
-        match &mut $editor.debugger {
+        match $editor.debuggers.active_mut() {
             Some(debugger) => debugger,
             None => return,
         }
@@ -82,7 +85,115 @@ pub fn jump_to_stack_frame(editor: &mut Editor, frame: &helix_dap::StackFrame) {
     align_view(doc, view, Align::Center);
 }
 
+/// Fetches the variables of the active stack frame and evaluates the watch expressions for the
+/// debugger panels, keeping expanded nodes expanded.
+pub async fn refresh_debug_panels(editor: &mut Editor) {
+    let debugger = match editor.debuggers.active() {
+        Some(debugger) => debugger,
+        None => return,
+    };
+    let panels = &mut editor.debug_panels;
+    if !panels.visible {
+        return;
+    }
+    let frame_id = match debugger.current_stack_frame() {
+        Some(frame) => frame.id,
+        None => return,
+    };
+
+    let old_scopes = std::mem::take(&mut panels.scopes);
+    let mut scopes: Vec<VariableNode> = match debugger.scopes(frame_id).await {
+        Ok(scopes) => scopes.into_iter().map(VariableNode::from).collect(),
+        Err(err) => {
+            warn!("Failed to fetch scopes: {}", err);
+            Vec::new()
+        }
+    };
+    // show the innermost scope's variables in a new session
+    if old_scopes.is_empty() {
+        if let Some(scope) = scopes.first_mut() {
+            scope.expanded = true;
+        }
+    }
+    fetch_expanded(debugger, &mut scopes, &old_scopes).await;
+    panels.scopes = scopes;
+
+    for watch in &mut panels.watches {
+        let old: Vec<_> = watch
+            .result
+            .take()
+            .and_then(Result::ok)
+            .into_iter()
+            .collect();
+        let result = match debugger
+            .eval(watch.expression.clone(), Some(frame_id))
+            .await
+        {
+            Ok(response) => {
+                let mut node = VariableNode {
+                    memory_reference: response.memory_reference,
+                    ..VariableNode::new(
+                        watch.expression.clone(),
+                        response.result,
+                        response.ty,
+                        response.variables_reference,
+                    )
+                };
+                fetch_expanded(debugger, std::slice::from_mut(&mut node), &old).await;
+                Ok(node)
+            }
+            Err(err) => Err(err.to_string()),
+        };
+        watch.result = Some(result);
+    }
+}
+
+/// Fetches the children of the nodes that are expanded, or whose namesake in `old` was.
+fn fetch_expanded<'a>(
+    debugger: &'a Client,
+    nodes: &'a mut [VariableNode],
+    old: &'a [VariableNode],
+) -> BoxFuture<'a, ()> {
+    Box::pin(async move {
+        for node in nodes {
+            let old = old.iter().find(|old| old.name == node.name);
+            if let Some(old) = old {
+                node.expanded = old.expanded;
+            }
+            if !node.expanded || !node.has_children() {
+                continue;
+            }
+            if let Ok(variables) = debugger.variables(node.variables_reference).await {
+                let mut children: Vec<_> = variables.into_iter().map(VariableNode::from).collect();
+                let old_children = old
+                    .and_then(|old| old.children.as_deref())
+                    .unwrap_or_default();
+                fetch_expanded(debugger, &mut children, old_children).await;
+                node.children = Some(children);
+            }
+        }
+    })
+}
+
+/// Sends the breakpoints of `path` to every debug session.
 pub fn breakpoints_changed(
+    debuggers: &mut dap::Registry,
+    path: PathBuf,
+    breakpoints: &mut [Breakpoint],
+) -> Result<(), anyhow::Error> {
+    // a session failing shouldn't keep the others from getting the breakpoints
+    let errors: Vec<String> = debuggers
+        .iter_mut()
+        .filter_map(|debugger| send_breakpoints(debugger, path.clone(), breakpoints).err())
+        .map(|err| err.to_string())
+        .collect();
+    if !errors.is_empty() {
+        anyhow::bail!(errors.join("; "));
+    }
+    Ok(())
+}
+
+fn send_breakpoints(
     debugger: &mut dap::Client,
     path: PathBuf,
     breakpoints: &mut [Breakpoint],
@@ -112,8 +223,12 @@ pub fn breakpoints_changed(
     // }
     let source_breakpoints = breakpoints
         .iter()
+        .filter(|breakpoint| breakpoint.enabled)
         .map(|breakpoint| helix_dap::SourceBreakpoint {
             line: breakpoint.line + 1, // convert from 0-indexing to 1-indexing (TODO: could set debugger to 0-indexing on init)
+            condition: breakpoint.condition.clone(),
+            hit_condition: breakpoint.hit_condition.clone(),
+            log_message: breakpoint.log_message.clone(),
             ..Default::default()
         })
         .collect::<Vec<_>>();
@@ -121,10 +236,16 @@ pub fn breakpoints_changed(
     let request = debugger.set_breakpoints(path, source_breakpoints);
     match block_on(request) {
         Ok(Some(dap_breakpoints)) => {
-            for (breakpoint, dap_breakpoint) in breakpoints.iter_mut().zip(dap_breakpoints) {
-                breakpoint.id = dap_breakpoint.id;
-                breakpoint.verified = dap_breakpoint.verified;
-                breakpoint.message = dap_breakpoint.message;
+            let enabled = breakpoints
+                .iter_mut()
+                .filter(|breakpoint| breakpoint.enabled);
+            for (breakpoint, dap_breakpoint) in enabled.zip(dap_breakpoints) {
+                let state = BreakpointState {
+                    id: dap_breakpoint.id,
+                    verified: dap_breakpoint.verified,
+                    message: dap_breakpoint.message,
+                };
+                breakpoint.states.insert(debugger.id(), state);
                 // TODO: handle breakpoint.message
                 // TODO: verify source matches
                 breakpoint.line = dap_breakpoint.line.unwrap_or(0).saturating_sub(1); // convert to 0-indexing
@@ -139,12 +260,74 @@ pub fn breakpoints_changed(
     Ok(())
 }
 
+pub fn function_breakpoints_changed(
+    debugger: &mut dap::Client,
+    breakpoints: &[dap::FunctionBreakpoint],
+) -> Result<(), anyhow::Error> {
+    if debugger.capabilities().supports_function_breakpoints != Some(true) {
+        anyhow::bail!("Debugger does not support function breakpoints");
+    }
+    if let Err(e) = block_on(debugger.set_function_breakpoints(breakpoints.to_vec())) {
+        anyhow::bail!("Failed to set function breakpoints: {}", e);
+    }
+    Ok(())
+}
+
+pub fn data_breakpoints_changed(
+    debugger: &mut dap::Client,
+    breakpoints: &[DataBreakpoint],
+) -> Result<(), anyhow::Error> {
+    if debugger.capabilities().supports_data_breakpoints != Some(true) {
+        anyhow::bail!("Debugger does not support data breakpoints");
+    }
+    // the data ids of the other sessions mean nothing to this one
+    let breakpoints = breakpoints
+        .iter()
+        .filter(|breakpoint| breakpoint.session == debugger.id())
+        .map(|breakpoint| dap::DataBreakpoint {
+            data_id: breakpoint.data_id.clone(),
+            access_type: breakpoint.access_type.clone(),
+            ..Default::default()
+        })
+        .collect();
+    if let Err(e) = block_on(debugger.set_data_breakpoints(breakpoints)) {
+        anyhow::bail!("Failed to set data breakpoints: {}", e);
+    }
+    Ok(())
+}
+
+/// Sends the enabled exception filters. Their conditions are only sent if the debugger supports
+/// them.
+pub fn exception_filters_changed(
+    debugger: &mut dap::Client,
+    filters: &[dap::ExceptionFilterOptions],
+) -> Result<(), anyhow::Error> {
+    let result = if debugger.capabilities().supports_exception_filter_options == Some(true) {
+        block_on(debugger.set_exception_filter_options(filters.to_vec())).map(|_| ())
+    } else {
+        let filters = filters
+            .iter()
+            .map(|filter| filter.filter_id.clone())
+            .collect();
+        block_on(debugger.set_exception_breakpoints(filters)).map(|_| ())
+    };
+    if let Err(e) = result {
+        anyhow::bail!("Failed to set exception breakpoints: {}", e);
+    }
+    Ok(())
+}
+
 impl Editor {
-    pub async fn handle_debugger_message(&mut self, payload: helix_dap::Payload) -> bool {
-        use dap::requests::RunInTerminal;
+    /// Handles an event or reverse request of the debug session `id`.
+    pub async fn handle_debugger_message(
+        &mut self,
+        id: usize,
+        payload: helix_dap::Payload,
+    ) -> bool {
+        use dap::requests::{RunInTerminal, StartDebugging};
         use helix_dap::{events, Event};
 
-        let debugger = match self.debugger.as_mut() {
+        let debugger = match self.debuggers.get_mut(id) {
             Some(debugger) => debugger,
             None => return false,
         };
@@ -158,6 +341,19 @@ pub async fn handle_debugger_message(&mut self, payload: helix_dap::Payload) ->
                     all_threads_stopped,
                     ..
                 }) => {
+                    // the stopped session is the one to inspect
+                    self.debuggers.set_active(id);
+
+                    // the temporary breakpoints to run to the cursor are done
+                    for (path, breakpoints) in &mut self.breakpoints {
+                        if breakpoints.iter().any(|breakpoint| breakpoint.temporary) {
+                            breakpoints.retain(|breakpoint| !breakpoint.temporary);
+                            let _ =
+                                breakpoints_changed(&mut self.debuggers, path.clone(), breakpoints);
+                        }
+                    }
+                    let debugger = self.debuggers.active_mut().unwrap();
+
                     let all_threads_stopped = all_threads_stopped.unwrap_or_default();
 
                     if all_threads_stopped {
@@ -174,10 +370,15 @@ pub async fn handle_debugger_message(&mut self, payload: helix_dap::Payload) ->
                         select_thread_id(self, thread_id, false).await;
                     }
 
-                    let scope = match thread_id {
+                    let mut scope = match thread_id {
                         Some(id) => format!("Thread {}", id),
                         None => "Target".to_owned(),
                     };
+                    if self.debuggers.len() > 1 {
+                        if let Some(debugger) = self.debuggers.get(id) {
+                            scope = format!("{}: {}", debugger.name, scope);
+                        }
+                    }
 
                     let mut status = format!("{} stopped because of {}", scope, reason);
                     if let Some(desc) = description {
@@ -190,6 +391,9 @@ pub async fn handle_debugger_message(&mut self, payload: helix_dap::Payload) ->
                         status.push_str(" (all threads stopped)");
                     }
 
+                    refresh_debug_panels(self).await;
+                    self.refresh_debug_values().await;
+                    self.refresh_disassembly().await;
                     self.set_status(status);
                 }
                 Event::Continued(events::Continued { thread_id, .. }) => {
@@ -197,23 +401,30 @@ pub async fn handle_debugger_message(&mut self, payload: helix_dap::Payload) ->
                         .thread_states
                         .insert(thread_id, "running".to_owned());
                     if debugger.thread_id == Some(thread_id) {
-                        debugger.resume_application();
+                        self.resume_debuggee();
                     }
                 }
                 Event::Thread(_) => {
                     // TODO: update thread_states, make threads request
                 }
                 Event::Breakpoint(events::Breakpoint { reason, breakpoint }) => {
+                    // the breakpoint ids are given by the debug adapter of the session
+                    let is_event_breakpoint = |b: &Breakpoint| {
+                        b.states.get(&id).map(|state| state.id) == Some(breakpoint.id)
+                    };
                     match &reason[..] {
                         "new" => {
                             if let Some(source) = breakpoint.source {
+                                let state = BreakpointState {
+                                    id: breakpoint.id,
+                                    verified: breakpoint.verified,
+                                    message: breakpoint.message,
+                                };
                                 self.breakpoints
                                     .entry(source.path.unwrap()) // TODO: no unwraps
                                     .or_default()
                                     .push(Breakpoint {
-                                        id: breakpoint.id,
-                                        verified: breakpoint.verified,
-                                        message: breakpoint.message,
+                                        states: HashMap::from([(id, state)]),
                                         line: breakpoint.line.unwrap().saturating_sub(1), // TODO: no unwrap
                                         column: breakpoint.column,
                                         ..Default::default()
@@ -222,11 +433,10 @@ pub async fn handle_debugger_message(&mut self, payload: helix_dap::Payload) ->
                         }
                         "changed" => {
                             for breakpoints in self.breakpoints.values_mut() {
-                                if let Some(i) =
-                                    breakpoints.iter().position(|b| b.id == breakpoint.id)
-                                {
-                                    breakpoints[i].verified = breakpoint.verified;
-                                    breakpoints[i].message = breakpoint.message.clone();
+                                if let Some(i) = breakpoints.iter().position(is_event_breakpoint) {
+                                    let state = breakpoints[i].states.get_mut(&id).unwrap();
+                                    state.verified = breakpoint.verified;
+                                    state.message = breakpoint.message.clone();
                                     breakpoints[i].line =
                                         breakpoint.line.unwrap().saturating_sub(1); // TODO: no unwrap
                                     breakpoints[i].column = breakpoint.column;
@@ -235,9 +445,7 @@ pub async fn handle_debugger_message(&mut self, payload: helix_dap::Payload) ->
                         }
                         "removed" => {
                             for breakpoints in self.breakpoints.values_mut() {
-                                if let Some(i) =
-                                    breakpoints.iter().position(|b| b.id == breakpoint.id)
-                                {
+                                if let Some(i) = breakpoints.iter().position(is_event_breakpoint) {
                                     breakpoints.remove(i);
                                 }
                             }
@@ -250,24 +458,24 @@ pub async fn handle_debugger_message(&mut self, payload: helix_dap::Payload) ->
                 Event::Output(events::Output {
                     category, output, ..
                 }) => {
-                    let prefix = match category {
-                        Some(category) => {
-                            if &category == "telemetry" {
-                                return false;
-                            }
-                            format!("Debug ({}):", category)
-                        }
-                        None => "Debug:".to_owned(),
-                    };
+                    if category.as_deref() == Some("telemetry") {
+                        return false;
+                    }
 
                     log::info!("{}", output);
-                    self.set_status(format!("{} {}", prefix, output));
+                    self.debug_console_output(category, output);
                 }
                 Event::Initialized(_) => {
                     // send existing breakpoints
                     for (path, breakpoints) in &mut self.breakpoints {
                         // TODO: call futures in parallel, await all
-                        let _ = breakpoints_changed(debugger, path.clone(), breakpoints);
+                        let _ = send_breakpoints(debugger, path.clone(), breakpoints);
+                    }
+                    if !self.function_breakpoints.is_empty() {
+                        let _ = function_breakpoints_changed(debugger, &self.function_breakpoints);
+                    }
+                    if !self.exception_filters.is_empty() {
+                        let _ = exception_filters_changed(debugger, &self.exception_filters);
                     }
                     // TODO: fetch breakpoints (in case we're attaching)
 
@@ -321,7 +529,7 @@ pub async fn handle_debugger_message(&mut self, payload: helix_dap::Payload) ->
                             }
                         }
                         None => {
-                            self.debugger = None;
+                            self.remove_debug_session(id);
                             self.set_status(
                                 "Terminated debugging session and disconnected debugger.",
                             );
@@ -357,7 +565,7 @@ pub async fn handle_debugger_message(&mut self, payload: helix_dap::Payload) ->
                     };
 
                     // Re-borrowing debugger to avoid issues when loading config
-                    let debugger = match self.debugger.as_mut() {
+                    let debugger = match self.debuggers.get_mut(id) {
                         Some(debugger) => debugger,
                         None => return false,
                     };
@@ -388,9 +596,78 @@ pub async fn handle_debugger_message(&mut self, payload: helix_dap::Payload) ->
                         )
                         .await;
                 }
+                StartDebugging::COMMAND => {
+                    let result = match serde_json::from_value(request.arguments.unwrap_or_default())
+                    {
+                        Ok(arguments) => self
+                            .start_child_debug_session(id, arguments)
+                            .await
+                            .map(|_| serde_json::Value::Null),
+                        Err(err) => Err(err.into()),
+                    };
+                    if let Err(err) = &result {
+                        self.set_error(format!("Failed to start child debug session: {}", err));
+                    }
+                    if let Some(debugger) = self.debuggers.get(id) {
+                        let _ = debugger
+                            .reply(request.seq, StartDebugging::COMMAND, result)
+                            .await;
+                    }
+                }
                 _ => log::error!("DAP reverse request not implemented: {:?}", request),
             },
         }
         true
     }
+
+    /// Starts the session a debug adapter requested with `startDebugging`, such as for a child
+    /// process of the debuggee.
+    async fn start_child_debug_session(
+        &mut self,
+        parent_id: usize,
+        arguments: dap::requests::StartDebuggingArguments,
+    ) -> dap::Result<()> {
+        let id = self.debuggers.start_child(parent_id).await?;
+        let debugger = self.debuggers.get_mut(id).unwrap();
+        if let Some(name) = arguments
+            .configuration
+            .get("name")
+            .and_then(|name| name.as_str())
+        {
+            debugger.name = name.to_owned();
+        }
+        let args = serde_json::Value::Object(arguments.configuration);
+        let request = match arguments.request.as_str() {
+            "launch" => debugger.launch(args).boxed(),
+            "attach" => debugger.attach(args).boxed(),
+            request => {
+                self.debuggers.remove(id);
+                return Err(anyhow::anyhow!("Unsupported request '{}'", request).into());
+            }
+        };
+        // the response only comes once the session is configured, after its events are handled
+        tokio::spawn(async move {
+            if let Err(err) = request.await {
+                log::error!("Failed to start child debug session: {}", err);
+            }
+        });
+        Ok(())
+    }
+
+    /// Forgets the debug session `id` after it ended, along with its state of the breakpoints and
+    /// its data breakpoints. The debug panels and inline values are only cleared if they showed
+    /// this session.
+    pub fn remove_debug_session(&mut self, id: usize) {
+        let was_active = self.debuggers.active_id() == Some(id);
+        self.debuggers.remove(id);
+        for breakpoint in self.breakpoints.values_mut().flatten() {
+            breakpoint.states.remove(&id);
+        }
+        self.data_breakpoints
+            .retain(|breakpoint| breakpoint.session != id);
+        if was_active || self.debuggers.is_empty() {
+            self.debug_panels.clear_values();
+            self.clear_debug_values();
+        }
+    }
 }
@@ -1 +1,245 @@
+use crate::editor::Editor;
+use helix_core::diagnostic::{DiagnosticProvider, DiagnosticTag, NumberOrString};
+use helix_lsp::{lsp, util::lsp_pos_to_pos};
 
+impl Editor {
+    /// Replaces the diagnostics a language server reported for the document at `uri`, unless
+    /// they were reported for another version of the document. Returns whether the diagnostics
+    /// were applied.
+    pub fn handle_lsp_diagnostics(
+        &mut self,
+        uri: lsp::Url,
+        version: Option<i32>,
+        mut diagnostics: Vec<lsp::Diagnostic>,
+    ) -> bool {
+        let path = match uri.to_file_path() {
+            Ok(path) => path,
+            Err(_) => {
+                log::error!("Unsupported file URI: {}", uri);
+                return false;
+            }
+        };
+        let mut outdated = false;
+        let doc = self.document_by_path_mut(&path).filter(|doc| {
+            if let Some(version) = version {
+                if version != doc.version() {
+                    log::info!("Version ({version}) is out of date for {path:?} (expected ({}), dropping diagnostics", doc.version());
+                    outdated = true;
+                    return false;
+                }
+            }
+
+            true
+        });
+
+        if let Some(doc) = doc {
+            let lang_conf = doc.language_config();
+            let text = doc.text();
+
+            let diagnostics = diagnostics
+                .iter()
+                .filter_map(|diagnostic| {
+                    use helix_core::diagnostic::{Diagnostic, Range, Severity::*};
+                    use lsp::DiagnosticSeverity;
+
+                    let language_server = if let Some(language_server) = doc.language_server() {
+                        language_server
+                    } else {
+                        log::warn!("Discarding diagnostic because language server is not initialized: {:?}", diagnostic);
+                        return None;
+                    };
+
+                    // TODO: convert inside server
+                    let start = if let Some(start) = lsp_pos_to_pos(
+                        text,
+                        diagnostic.range.start,
+                        language_server.offset_encoding(),
+                    ) {
+                        start
+                    } else {
+                        log::warn!("lsp position out of bounds - {:?}", diagnostic);
+                        return None;
+                    };
+
+                    let end = if let Some(end) = lsp_pos_to_pos(
+                        text,
+                        diagnostic.range.end,
+                        language_server.offset_encoding(),
+                    ) {
+                        end
+                    } else {
+                        log::warn!("lsp position out of bounds - {:?}", diagnostic);
+                        return None;
+                    };
+
+                    let severity = diagnostic.severity.map(|severity| match severity {
+                        DiagnosticSeverity::ERROR => Error,
+                        DiagnosticSeverity::WARNING => Warning,
+                        DiagnosticSeverity::INFORMATION => Info,
+                        DiagnosticSeverity::HINT => Hint,
+                        severity => unreachable!("unrecognized diagnostic severity: {:?}", severity),
+                    });
+
+                    if let Some(lang_conf) = lang_conf {
+                        if let Some(severity) = severity {
+                            if severity < lang_conf.diagnostic_severity {
+                                return None;
+                            }
+                        }
+                    };
+
+                    let code = match diagnostic.code.clone() {
+                        Some(x) => match x {
+                            lsp::NumberOrString::Number(x) => Some(NumberOrString::Number(x)),
+                            lsp::NumberOrString::String(x) => Some(NumberOrString::String(x)),
+                        },
+                        None => None,
+                    };
+
+                    let tags = if let Some(ref tags) = diagnostic.tags {
+                        let new_tags = tags
+                            .iter()
+                            .filter_map(|tag| match *tag {
+                                lsp::DiagnosticTag::DEPRECATED => Some(DiagnosticTag::Deprecated),
+                                lsp::DiagnosticTag::UNNECESSARY => {
+                                    Some(DiagnosticTag::Unnecessary)
+                                }
+                                _ => None,
+                            })
+                            .collect();
+
+                        new_tags
+                    } else {
+                        Vec::new()
+                    };
+
+                    Some(Diagnostic {
+                        range: Range { start, end },
+                        line: diagnostic.range.start.line as usize,
+                        message: diagnostic.message.clone(),
+                        severity,
+                        code,
+                        tags,
+                        source: diagnostic.source.clone(),
+                        data: diagnostic.data.clone(),
+                        provider: DiagnosticProvider::Lsp,
+                    })
+                })
+                .collect();
+
+            doc.replace_diagnostics(&DiagnosticProvider::Lsp, diagnostics);
+        }
+
+        // Sort diagnostics first by severity and then by line numbers.
+        // Note: The `lsp::DiagnosticSeverity` enum is already defined in decreasing order
+        diagnostics.sort_unstable_by_key(|d| (d.severity, d.range.start));
+
+        // Insert the original lsp::Diagnostics here because we may have no open document
+        // for diagnosic message and so we can't calculate the exact position.
+        // When using them later in the diagnostics picker, we calculate them on-demand.
+        self.diagnostics.insert(uri, diagnostics);
+        !outdated
+    }
+
+    /// Applies the diagnostics pulled from the language server `server_id` for `version` of the
+    /// document at `uri`, and for the documents related to it. Unchanged reports keep the
+    /// diagnostics as they are. The result ids of the applied reports are recorded for the next
+    /// pull.
+    pub fn handle_lsp_document_diagnostic_report(
+        &mut self,
+        server_id: usize,
+        uri: lsp::Url,
+        version: i32,
+        report: lsp::DocumentDiagnosticReportResult,
+    ) {
+        use lsp::{DocumentDiagnosticReport, DocumentDiagnosticReportResult};
+
+        let related_documents = match report {
+            DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(report)) => {
+                let full = report.full_document_diagnostic_report;
+                if self.handle_lsp_diagnostics(uri.clone(), Some(version), full.items) {
+                    self.record_diagnostic_result_id(server_id, uri, full.result_id.as_ref());
+                }
+                report.related_documents
+            }
+            DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Unchanged(report)) => {
+                if self.is_current_version(&uri, Some(version)) {
+                    let result_id = &report.unchanged_document_diagnostic_report.result_id;
+                    self.record_diagnostic_result_id(server_id, uri, Some(result_id));
+                }
+                report.related_documents
+            }
+            DocumentDiagnosticReportResult::Partial(report) => report.related_documents,
+        };
+
+        for (uri, report) in related_documents.into_iter().flatten() {
+            match report {
+                lsp::DocumentDiagnosticReportKind::Full(report) => {
+                    if self.handle_lsp_diagnostics(uri.clone(), None, report.items) {
+                        let result_id = report.result_id.as_ref();
+                        self.record_diagnostic_result_id(server_id, uri, result_id);
+                    }
+                }
+                lsp::DocumentDiagnosticReportKind::Unchanged(report) => {
+                    self.record_diagnostic_result_id(server_id, uri, Some(&report.result_id));
+                }
+            }
+        }
+    }
+
+    /// Applies the diagnostics pulled from the language server `server_id` for the whole
+    /// workspace, recording the result ids of the applied reports.
+    pub fn handle_lsp_workspace_diagnostic_report(
+        &mut self,
+        server_id: usize,
+        report: lsp::WorkspaceDiagnosticReportResult,
+    ) {
+        let items = match report {
+            lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+            lsp::WorkspaceDiagnosticReportResult::Partial(report) => report.items,
+        };
+
+        for item in items {
+            match item {
+                lsp::WorkspaceDocumentDiagnosticReport::Full(report) => {
+                    let version = report.version.map(|version| version as i32);
+                    let full = report.full_document_diagnostic_report;
+                    if self.handle_lsp_diagnostics(report.uri.clone(), version, full.items) {
+                        let result_id = full.result_id.as_ref();
+                        self.record_diagnostic_result_id(server_id, report.uri, result_id);
+                    }
+                }
+                lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => {
+                    let version = report.version.map(|version| version as i32);
+                    if self.is_current_version(&report.uri, version) {
+                        let result_id = &report.unchanged_document_diagnostic_report.result_id;
+                        self.record_diagnostic_result_id(server_id, report.uri, Some(result_id));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether a report for `version` of the document at `uri` is about its current contents.
+    fn is_current_version(&self, uri: &lsp::Url, version: Option<i32>) -> bool {
+        let doc = uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| self.document_by_path(path));
+        match (doc, version) {
+            (Some(doc), Some(version)) => doc.version() == version,
+            _ => true,
+        }
+    }
+
+    fn record_diagnostic_result_id(
+        &self,
+        server_id: usize,
+        uri: lsp::Url,
+        result_id: Option<&String>,
+    ) {
+        if let Some(language_server) = self.language_servers.get_by_id(server_id) {
+            language_server.record_diagnostic_result_id(uri, result_id);
+        }
+    }
+}
@@ -1,7 +1,12 @@
 #[macro_use]
 pub mod macros;
 
+pub mod breakpoint;
 pub mod clipboard;
+pub mod debug_console;
+pub mod debug_panel;
+pub mod debug_values;
+pub mod disassembly;
 pub mod document;
 pub mod editor;
 pub mod env;
@@ -15,9 +20,12 @@ pub mod handlers {
 pub mod info;
 pub mod input;
 pub mod keyboard;
+pub mod quickfix;
+pub mod statusline;
 pub mod theme;
 pub mod tree;
 pub mod view;
+pub mod workspace;
 
 use std::num::NonZeroUsize;
 
@@ -38,6 +46,23 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     }
 }
 
+/// Identifies a tab page. Ids are never reused within an editor session, unlike tab positions.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct TabId(NonZeroUsize);
+
+impl Default for TabId {
+    fn default() -> TabId {
+        // Safety: 1 is non-zero
+        TabId(unsafe { NonZeroUsize::new_unchecked(1) })
+    }
+}
+
+impl std::fmt::Display for TabId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}", self.0))
+    }
+}
+
 slotmap::new_key_type! {
     pub struct ViewId;
 }
@@ -0,0 +1,274 @@
+//! An editor wide list of locations, such as compiler errors or search results, which
+//! outlives the picker or command that produced it and can be stepped through.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use helix_core::{
+    diagnostic::{Diagnostic, DiagnosticProvider, Range, Severity},
+    errorformat::{self, ErrorFormat},
+    Assoc, RopeSlice, Transaction,
+};
+
+use crate::{Document, DocumentId};
+
+#[derive(Debug, Clone)]
+pub struct QuickfixEntry {
+    pub path: PathBuf,
+    /// 0-based line, kept up to date with the edits to the document at `path`
+    pub line: usize,
+    /// 0-based column in chars
+    pub column: usize,
+    pub text: String,
+    pub severity: Option<Severity>,
+    anchor: Option<Anchor>,
+}
+
+/// The char position of an entry in an open document, valid at `revision`.
+#[derive(Debug, Clone, Copy)]
+struct Anchor {
+    doc: DocumentId,
+    revision: usize,
+    pos: usize,
+}
+
+impl QuickfixEntry {
+    pub fn new(path: PathBuf, line: usize, column: usize, text: String) -> Self {
+        let path = helix_core::path::get_canonicalized_path(&path).unwrap_or(path);
+        Self {
+            path,
+            line,
+            column,
+            text,
+            severity: None,
+            anchor: None,
+        }
+    }
+
+    pub fn with_severity(mut self, severity: Option<Severity>) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Returns the char position of the entry in `text`, clamped to the text.
+    pub fn char_pos(&self, text: RopeSlice) -> usize {
+        let line = self.line.min(text.len_lines().saturating_sub(1));
+        let line_start = text.line_to_char(line);
+        let line_len = helix_core::line_ending::line_end_char_index(&text, line) - line_start;
+        line_start + self.column.min(line_len)
+    }
+
+    /// Converts the entry into a diagnostic of `provider` spanning the char at the entry.
+    pub fn to_diagnostic(&self, text: RopeSlice, provider: DiagnosticProvider) -> Diagnostic {
+        let start = self.char_pos(text);
+        let line = text.char_to_line(start);
+        let end = (start + 1).min(helix_core::line_ending::line_end_char_index(&text, line));
+        Diagnostic {
+            range: Range { start, end },
+            line,
+            message: self.text.clone(),
+            severity: self.severity,
+            code: None,
+            tags: Vec::new(),
+            source: None,
+            data: None,
+            provider,
+        }
+    }
+}
+
+/// Parses the locations in tool `output` with the `errorformat` patterns in `formats`.
+/// Relative paths are resolved against `dir`.
+pub fn parse_output(
+    formats: &[String],
+    output: &str,
+    dir: &Path,
+) -> anyhow::Result<Vec<QuickfixEntry>> {
+    let formats = formats
+        .iter()
+        .map(|format| ErrorFormat::new(format))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(errorformat::parse(&formats, output)
+        .into_iter()
+        .map(|location| {
+            let path = helix_core::path::expand_tilde(Path::new(&location.path));
+            QuickfixEntry::new(
+                dir.join(path),
+                location.line,
+                location.column,
+                location.message,
+            )
+            .with_severity(location.severity)
+        })
+        .collect())
+}
+
+#[derive(Debug, Default)]
+pub struct Quickfix {
+    pub title: String,
+    entries: Vec<QuickfixEntry>,
+    /// The index of the current entry
+    index: usize,
+    /// Whether the panel listing the entries is shown
+    pub visible: bool,
+}
+
+impl Quickfix {
+    /// Replaces the list with `entries`, starting at the first one.
+    pub fn set(&mut self, title: String, entries: Vec<QuickfixEntry>) {
+        self.title = title;
+        self.entries = entries;
+        self.index = 0;
+    }
+
+    pub fn entries(&self) -> &[QuickfixEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn current(&self) -> Option<&QuickfixEntry> {
+        self.entries.get(self.index)
+    }
+
+    /// Makes the entry at `index` the current one, clamped to the last entry.
+    pub fn select(&mut self, index: usize) -> Option<&QuickfixEntry> {
+        self.index = index.min(self.entries.len().saturating_sub(1));
+        self.current()
+    }
+
+    /// Moves `count` entries forward, or returns `None` if the current entry is the last.
+    pub fn next(&mut self, count: usize) -> Option<&QuickfixEntry> {
+        if self.index + 1 >= self.entries.len() {
+            return None;
+        }
+        self.select(self.index + count)
+    }
+
+    /// Moves `count` entries backward, or returns `None` if the current entry is the first.
+    pub fn prev(&mut self, count: usize) -> Option<&QuickfixEntry> {
+        if self.index == 0 || self.entries.is_empty() {
+            return None;
+        }
+        self.select(self.index.saturating_sub(count))
+    }
+
+    /// Updates the positions of the entries in `doc` with the changes made since they were
+    /// last synced, so that entries keep pointing at the same text while it's edited.
+    pub fn sync(&mut self, doc: &mut Document) {
+        let path = match doc.path() {
+            Some(path) => path.clone(),
+            None => return,
+        };
+        if !self.entries.iter().any(|entry| entry.path == path) {
+            return;
+        }
+
+        let doc_id = doc.id();
+        let revision = doc.get_current_revision();
+        let mut transactions: HashMap<usize, Option<Transaction>> = HashMap::new();
+
+        for entry in self.entries.iter_mut().filter(|entry| entry.path == path) {
+            let pos = match entry.anchor {
+                Some(anchor) if anchor.doc == doc_id && anchor.revision == revision => continue,
+                Some(anchor) if anchor.doc == doc_id => {
+                    let transaction = transactions
+                        .entry(anchor.revision)
+                        .or_insert_with(|| doc.history.get_mut().changes_since(anchor.revision));
+                    match transaction {
+                        Some(transaction) => {
+                            transaction.changes().map_pos(anchor.pos, Assoc::Before)
+                        }
+                        None => anchor.pos,
+                    }
+                }
+                // the entry was created or its document closed since the last sync
+                _ => entry.char_pos(doc.text().slice(..)),
+            };
+
+            let text = doc.text().slice(..);
+            let pos = pos.min(text.len_chars());
+            entry.line = text.char_to_line(pos);
+            entry.column = pos - text.line_to_char(entry.line);
+            entry.anchor = Some(Anchor {
+                doc: doc_id,
+                revision,
+                pos,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::editor::Config;
+    use arc_swap::ArcSwap;
+    use helix_core::{Rope, Selection};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_sync_tracks_edits() {
+        let path = std::env::temp_dir().join("quickfix-test.txt");
+        let mut doc = Document::from(
+            Rope::from("one\ntwo\nthree\n"),
+            None,
+            Arc::new(ArcSwap::new(Arc::new(Config::default()))),
+        );
+        doc.set_path(Some(&path)).unwrap();
+        let view = crate::ViewId::default();
+        doc.ensure_view_init(view);
+
+        let mut quickfix = Quickfix::default();
+        quickfix.set(
+            "test".into(),
+            vec![
+                QuickfixEntry::new(path.clone(), 1, 1, "two".into()),
+                QuickfixEntry::new(path, 2, 0, "three".into()),
+            ],
+        );
+        quickfix.sync(&mut doc);
+
+        // insert a line at the start and commit it to the history
+        let transaction =
+            Transaction::change(doc.text(), std::iter::once((0, 0, Some("zero\n".into()))))
+                .with_selection(Selection::point(0));
+        doc.apply(&transaction, view);
+        doc.append_changes_to_history(&mut crate::View::new(doc.id(), Default::default()));
+        quickfix.sync(&mut doc);
+
+        let positions: Vec<_> = quickfix
+            .entries()
+            .iter()
+            .map(|entry| (entry.line, entry.column))
+            .collect();
+        assert_eq!(positions, [(2, 1), (3, 0)]);
+    }
+
+    #[test]
+    fn test_navigation() {
+        let mut quickfix = Quickfix::default();
+        assert!(quickfix.next(1).is_none());
+
+        let entries = (0..3)
+            .map(|line| QuickfixEntry::new("/foo".into(), line, 0, String::new()))
+            .collect();
+        quickfix.set("test".into(), entries);
+
+        assert_eq!(quickfix.next(1).map(|entry| entry.line), Some(1));
+        assert_eq!(quickfix.next(5).map(|entry| entry.line), Some(2));
+        assert!(quickfix.next(1).is_none());
+        assert_eq!(quickfix.prev(1).map(|entry| entry.line), Some(1));
+        assert_eq!(quickfix.prev(5).map(|entry| entry.line), Some(0));
+        assert!(quickfix.prev(1).is_none());
+    }
+}
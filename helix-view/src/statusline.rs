@@ -0,0 +1,306 @@
+//! Format strings for user-defined statusline elements.
+//!
+//! A format string is literal text with `{...}` expansions:
+//!
+//! - `{name}` inserts the value of a [Placeholder]. `{name:N}` truncates the value to `N`
+//!   columns keeping its start, `{name:-N}` keeps its end.
+//! - `{?name:text}` renders `text` only if the placeholder is set, meaning it is neither empty
+//!   nor `0`. `{!name:text}` renders `text` only if it is not set.
+//! - `{#scope:text}` renders `text` with the theme style `scope`.
+//!
+//! The `text` of conditionals and styles is itself a format string, closed by the first `}` that
+//! doesn't end a nested expansion. `{{` and `}}` produce literal braces outside of expansions.
+
+use std::{fmt, iter::Peekable, str::Chars, str::FromStr};
+
+use serde::{de::IntoDeserializer, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Editor state that can be inserted into a statusline format string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Placeholder {
+    /// The editor mode, named as configured in `statusline.mode`
+    Mode,
+    /// The LSP activity spinner, empty while the language server is idle
+    Spinner,
+    /// The name of the language server attached to the document
+    LspServers,
+    /// The latest progress report of the document's language server
+    LspProgress,
+    /// The relative file path
+    FileName,
+    /// The base file name
+    FileBaseName,
+    /// `[+]` if the document has unsaved changes
+    FileModified,
+    /// The file encoding
+    FileEncoding,
+    /// The file line endings (CRLF or LF)
+    FileLineEnding,
+    /// The file type (language ID or "text")
+    FileType,
+    /// "tabs" or the number of spaces used for indentation
+    IndentStyle,
+    /// Current version control information
+    VersionControl,
+    /// The number of selections (cursors)
+    Selections,
+    /// The number of characters in the primary selection
+    PrimarySelectionLength,
+    /// The line of the cursor, starting from 1
+    Line,
+    /// The column of the cursor, starting from 1
+    Column,
+    /// The cursor line as a percent of the total file
+    PositionPercentage,
+    /// The number of lines in the file
+    TotalLines,
+    /// The number of errors in the document
+    Errors,
+    /// The number of warnings in the document
+    Warnings,
+    /// The number of errors in the workspace
+    WorkspaceErrors,
+    /// The number of warnings in the workspace
+    WorkspaceWarnings,
+    /// The register a macro is being recorded into
+    Recording,
+    /// The name of the current tab page, or its position if it has none
+    Tab,
+}
+
+/// Limits a placeholder's value to `width` columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Truncate {
+    pub width: usize,
+    /// Keep the end of the value instead of its start, useful for paths.
+    pub keep_end: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Text(String),
+    Placeholder(Placeholder, Option<Truncate>),
+    Conditional {
+        placeholder: Placeholder,
+        negate: bool,
+        body: Vec<Segment>,
+    },
+    Styled {
+        scope: String,
+        body: Vec<Segment>,
+    },
+}
+
+/// A parsed statusline format string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusLineFormat {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl StatusLineFormat {
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+}
+
+impl FromStr for StatusLineFormat {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut chars = source.chars().peekable();
+        let segments = parse_segments(&mut chars, false)?;
+        Ok(Self {
+            source: source.to_string(),
+            segments,
+        })
+    }
+}
+
+impl fmt::Display for StatusLineFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Serialize for StatusLineFormat {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for StatusLineFormat {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let source = String::deserialize(deserializer)?;
+        source.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+fn parse_segments(chars: &mut Peekable<Chars>, nested: bool) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        match chars.next() {
+            None if nested => return Err("unclosed `{` in statusline format".to_string()),
+            None => break,
+            Some('{') if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            Some('{') => {
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(parse_expansion(chars)?);
+            }
+            Some('}') if nested => break,
+            Some('}') if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            Some('}') => {
+                return Err("unmatched `}` in statusline format, use `}}` for a brace".to_string())
+            }
+            Some(ch) => text.push(ch),
+        }
+    }
+
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    Ok(segments)
+}
+
+/// Parses an expansion after its opening `{`, up to and including the closing `}`.
+fn parse_expansion(chars: &mut Peekable<Chars>) -> Result<Segment, String> {
+    match chars.peek() {
+        Some(&kind @ ('?' | '!' | '#')) => {
+            chars.next();
+            let header =
+                take_until(chars, ':').ok_or_else(|| format!("expected `:` after `{{{}`", kind))?;
+            let body = parse_segments(chars, true)?;
+            Ok(match kind {
+                '#' => Segment::Styled {
+                    scope: header,
+                    body,
+                },
+                _ => Segment::Conditional {
+                    placeholder: parse_placeholder(&header)?,
+                    negate: kind == '!',
+                    body,
+                },
+            })
+        }
+        _ => {
+            let expansion = take_until(chars, '}').ok_or("unclosed `{` in statusline format")?;
+            let (name, truncate) = match expansion.split_once(':') {
+                Some((name, width)) => {
+                    let width: isize = width
+                        .parse()
+                        .map_err(|_| format!("invalid width `{}` for `{}`", width, name))?;
+                    let truncate = Truncate {
+                        width: width.unsigned_abs(),
+                        keep_end: width < 0,
+                    };
+                    (name, Some(truncate))
+                }
+                None => (expansion.as_str(), None),
+            };
+            Ok(Segment::Placeholder(parse_placeholder(name)?, truncate))
+        }
+    }
+}
+
+/// Consumes characters up to and including `end`, returning `None` if a brace or the end of the
+/// string comes first.
+fn take_until(chars: &mut Peekable<Chars>, end: char) -> Option<String> {
+    let mut taken = String::new();
+    loop {
+        match chars.next()? {
+            ch if ch == end => return Some(taken),
+            '{' | '}' => return None,
+            ch => taken.push(ch),
+        }
+    }
+}
+
+fn parse_placeholder(name: &str) -> Result<Placeholder, String> {
+    Placeholder::deserialize(name.trim().into_deserializer())
+        .map_err(|err: serde::de::value::Error| format!("invalid placeholder: {}", err))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(source: &str) -> Result<Vec<Segment>, String> {
+        source
+            .parse::<StatusLineFormat>()
+            .map(|format| format.segments)
+    }
+
+    #[test]
+    fn parse_format() {
+        assert_eq!(
+            parse("{{{mode}}} {file-name:-20}").unwrap(),
+            [
+                Segment::Text("{".to_string()),
+                Segment::Placeholder(Placeholder::Mode, None),
+                Segment::Text("} ".to_string()),
+                Segment::Placeholder(
+                    Placeholder::FileName,
+                    Some(Truncate {
+                        width: 20,
+                        keep_end: true
+                    })
+                ),
+            ]
+        );
+
+        assert_eq!(
+            parse("{?recording:{#warning:rec @{recording}}}{!errors:ok}").unwrap(),
+            [
+                Segment::Conditional {
+                    placeholder: Placeholder::Recording,
+                    negate: false,
+                    body: vec![Segment::Styled {
+                        scope: "warning".to_string(),
+                        body: vec![
+                            Segment::Text("rec @".to_string()),
+                            Segment::Placeholder(Placeholder::Recording, None),
+                        ],
+                    }],
+                },
+                Segment::Conditional {
+                    placeholder: Placeholder::Errors,
+                    negate: true,
+                    body: vec![Segment::Text("ok".to_string())],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_format_errors() {
+        assert!(parse("{mode").is_err());
+        assert!(parse("mode}").is_err());
+        assert!(parse("{?mode}").is_err());
+        assert!(parse("{?mode:text").is_err());
+        assert!(parse("{mode:wide}").is_err());
+        assert!(parse("{unknown}")
+            .unwrap_err()
+            .contains("unknown variant `unknown`"));
+    }
+}
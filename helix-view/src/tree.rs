@@ -1,5 +1,6 @@
-use crate::{graphics::Rect, View, ViewId};
+use crate::{graphics::Rect, TabId, View, ViewId};
 use slotmap::HopSlotMap;
+use std::num::NonZeroUsize;
 
 // the dimensions are recomputed on window resize/tree change.
 //
@@ -13,10 +14,25 @@ pub struct Tree {
 
     nodes: HopSlotMap<ViewId, Node>,
 
+    // tab pages share `nodes` but each has its own root container. `root` and `focus` above
+    // belong to the active tab, its entry in `tabs` only has an up to date focus while inactive.
+    tabs: Vec<Tab>,
+    active_tab: usize,
+    next_tab_id: TabId,
+
     // used for traversals
     stack: Vec<(ViewId, Rect)>,
 }
 
+/// A tab page: a separate layout of views with its own focus.
+#[derive(Debug)]
+pub struct Tab {
+    pub id: TabId,
+    pub name: Option<String>,
+    root: ViewId,
+    focus: ViewId,
+}
+
 #[derive(Debug)]
 pub struct Node {
     parent: ViewId,
@@ -93,12 +109,22 @@ pub fn new(area: Rect) -> Self {
         // root is it's own parent
         nodes[root].parent = root;
 
+        let id = TabId::default();
         Self {
             root,
             focus: root,
             // fullscreen: false,
             area,
             nodes,
+            tabs: vec![Tab {
+                id,
+                name: None,
+                root,
+                focus: root,
+            }],
+            active_tab: 0,
+            // Safety: adding 1 from 1 is fine
+            next_tab_id: TabId(unsafe { NonZeroUsize::new_unchecked(id.0.get() + 1) }),
             stack: Vec::new(),
         }
     }
@@ -217,7 +243,8 @@ pub fn split(&mut self, view: View, layout: Layout) -> ViewId {
     pub fn remove(&mut self, index: ViewId) {
         let mut stack = Vec::new();
 
-        if self.focus == index {
+        let tab = self.tab_of(index);
+        if tab == self.active_tab && self.focus == index {
             // focus on something else
             self.focus = self.prev();
         }
@@ -234,7 +261,7 @@ pub fn remove(&mut self, index: ViewId) {
                 if let Some(pos) = container.children.iter().position(|&child| child == index) {
                     container.children.remove(pos);
                     // TODO: if container now only has one child, remove it and place child in parent
-                    if container.children.is_empty() && parent_id != self.root {
+                    if container.children.is_empty() && self.nodes[parent_id].parent != parent_id {
                         // if container now empty, remove it
                         stack.push(parent_id);
                     }
@@ -243,9 +270,26 @@ pub fn remove(&mut self, index: ViewId) {
             self.nodes.remove(index);
         }
 
+        if tab != self.active_tab && self.tabs[tab].focus == index {
+            let root = self.tabs[tab].root;
+            self.tabs[tab].focus = Traverse::new(self, root).next().map_or(root, |(id, _)| id);
+        }
+
+        // an emptied tab page is closed, unless it's the last one
+        if self.tabs.len() > 1 && self.tab_is_empty(tab) {
+            let Tab { root, .. } = self.tabs.remove(tab);
+            self.nodes.remove(root);
+            if tab < self.active_tab {
+                self.active_tab -= 1;
+            } else if tab == self.active_tab {
+                self.load_tab(tab.min(self.tabs.len() - 1));
+            }
+        }
+
         self.recalculate()
     }
 
+    /// All views across every tab page. Use [Self::traverse] for the views on screen.
     pub fn views(&self) -> impl Iterator<Item = (&View, bool)> {
         let focus = self.focus;
         self.nodes.iter().filter_map(move |(key, node)| match node {
@@ -311,7 +355,11 @@ pub fn contains(&self, index: ViewId) -> bool {
     }
 
     pub fn is_empty(&self) -> bool {
-        match &self.nodes[self.root] {
+        self.tab_is_empty(self.active_tab)
+    }
+
+    fn tab_is_empty(&self, index: usize) -> bool {
+        match &self.nodes[self.tabs[index].root] {
             Node {
                 content: Content::Container(container),
                 ..
@@ -320,6 +368,107 @@ pub fn is_empty(&self) -> bool {
         }
     }
 
+    /// All tab pages in display order.
+    pub fn tabs(&self) -> &[Tab] {
+        &self.tabs
+    }
+
+    /// Position of the active tab page in [Self::tabs].
+    pub fn active_tab(&self) -> usize {
+        self.active_tab
+    }
+
+    /// The focused view of the tab page at `index`. Returns the tab's root container if the tab
+    /// has no views yet.
+    pub fn tab_focus(&self, index: usize) -> ViewId {
+        if index == self.active_tab {
+            self.focus
+        } else {
+            self.tabs[index].focus
+        }
+    }
+
+    /// Position of the tab page containing the node.
+    /// # Panics
+    ///
+    /// Panics if `index` is not in self.nodes.
+    pub fn tab_of(&self, mut index: ViewId) -> usize {
+        while self.nodes[index].parent != index {
+            index = self.nodes[index].parent;
+        }
+        self.tabs
+            .iter()
+            .position(|tab| tab.root == index)
+            .expect("every root belongs to a tab")
+    }
+
+    /// Views of the tab page at `index`, in layout order.
+    pub fn tab_views(&self, index: usize) -> Traverse {
+        Traverse::new(self, self.tabs[index].root)
+    }
+
+    /// Add an empty tab page after the active one and switch to it. The caller is expected to
+    /// insert a view into it.
+    pub fn new_tab(&mut self, name: Option<String>) -> TabId {
+        let root = self.nodes.insert(Node::container(Layout::Vertical));
+        // root is it's own parent
+        self.nodes[root].parent = root;
+
+        let id = self.next_tab_id;
+        // Safety: adding 1 to a non-zero id, probably impossible to reach usize max
+        self.next_tab_id = TabId(unsafe { NonZeroUsize::new_unchecked(id.0.get() + 1) });
+
+        let index = self.active_tab + 1;
+        self.tabs.insert(
+            index,
+            Tab {
+                id,
+                name,
+                root,
+                focus: root,
+            },
+        );
+        self.focus_tab(index);
+        id
+    }
+
+    /// Switch to the tab page at `index`.
+    pub fn focus_tab(&mut self, index: usize) {
+        if index == self.active_tab {
+            return;
+        }
+        self.tabs[self.active_tab].focus = self.focus;
+        self.load_tab(index);
+        self.recalculate();
+    }
+
+    fn load_tab(&mut self, index: usize) {
+        self.active_tab = index;
+        self.root = self.tabs[index].root;
+        self.focus = self.tabs[index].focus;
+    }
+
+    /// Move the tab page at `from` so it ends up at position `to`.
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        let to = to.min(self.tabs.len() - 1);
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+
+        self.active_tab = if self.active_tab == from {
+            to
+        } else if from < self.active_tab && self.active_tab <= to {
+            self.active_tab - 1
+        } else if to <= self.active_tab && self.active_tab < from {
+            self.active_tab + 1
+        } else {
+            self.active_tab
+        };
+    }
+
+    pub fn set_tab_name(&mut self, index: usize, name: Option<String>) {
+        self.tabs[index].name = name;
+    }
+
     pub fn resize(&mut self, area: Rect) -> bool {
         if self.area != area {
             self.area = area;
@@ -416,8 +565,9 @@ pub fn recalculate(&mut self) {
         }
     }
 
+    /// Views of the active tab page, in layout order.
     pub fn traverse(&self) -> Traverse {
-        Traverse::new(self)
+        Traverse::new(self, self.root)
     }
 
     // Finds the split in the given direction if it exists
@@ -653,10 +803,10 @@ pub struct Traverse<'a> {
 }
 
 impl<'a> Traverse<'a> {
-    fn new(tree: &'a Tree) -> Self {
+    fn new(tree: &'a Tree, root: ViewId) -> Self {
         Self {
             tree,
-            stack: vec![tree.root],
+            stack: vec![root],
         }
     }
 }
@@ -874,4 +1024,59 @@ fn doc_id(tree: &Tree, view_id: ViewId) -> Option<DocumentId> {
         assert_eq!(doc_id(&tree, l2), Some(doc_r0));
         assert_eq!(doc_id(&tree, r0), Some(doc_l0));
     }
+
+    #[test]
+    fn tabs() {
+        let mut tree = Tree::new(Rect::new(0, 0, 180, 80));
+        let view = View::new(DocumentId::default(), GutterConfig::default());
+        let t0_l0 = tree.insert(view);
+        let view = View::new(DocumentId::default(), GutterConfig::default());
+        let t0_l1 = tree.split(view, Layout::Vertical);
+        tree.focus = t0_l0;
+        let first = tree.tabs()[0].id;
+
+        let second = tree.new_tab(Some("second".to_string()));
+        assert_eq!(tree.active_tab(), 1);
+        assert!(tree.is_empty());
+        let view = View::new(DocumentId::default(), GutterConfig::default());
+        let t1_l0 = tree.insert(view);
+        assert_eq!(
+            tree.traverse().map(|(id, _)| id).collect::<Vec<_>>(),
+            [t1_l0]
+        );
+        // views of all tab pages are still reachable
+        assert_eq!(tree.views().count(), 3);
+        assert_eq!(tree.tab_of(t0_l1), 0);
+        assert_eq!(tree.tab_of(t1_l0), 1);
+
+        // the focus of each tab page is restored
+        tree.focus_tab(0);
+        assert_eq!(tree.focus, t0_l0);
+        assert_eq!(tree.tab_focus(1), t1_l0);
+        assert_eq!(tree.traverse().count(), 2);
+
+        // removing the focused view of an inactive tab page moves its focus
+        tree.focus_tab(1);
+        tree.remove(t0_l0);
+        assert_eq!(tree.tab_focus(0), t0_l1);
+
+        tree.move_tab(1, 0);
+        assert_eq!(tree.active_tab(), 0);
+        assert_eq!(
+            tree.tabs().iter().map(|tab| tab.id).collect::<Vec<_>>(),
+            [second, first]
+        );
+
+        // closing the last view of a tab page closes the tab page
+        tree.remove(t1_l0);
+        assert_eq!(tree.tabs().len(), 1);
+        assert_eq!(tree.active_tab(), 0);
+        assert_eq!(tree.focus, t0_l1);
+        assert!(!tree.is_empty());
+
+        // but the last tab page is kept
+        tree.remove(t0_l1);
+        assert_eq!(tree.tabs().len(), 1);
+        assert!(tree.is_empty());
+    }
 }
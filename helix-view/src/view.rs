@@ -126,6 +126,8 @@ pub struct View {
     pub object_selections: Vec<Selection>,
     /// all gutter-related configuration settings, used primarily for gutter rendering
     pub gutters: GutterConfig,
+    /// Whether a breadcrumbs header is rendered above the document.
+    pub breadcrumbs: bool,
     /// A mapping between documents and the last history revision the view was updated at.
     /// Changes between documents and views are synced lazily when switching windows. This
     /// mapping keeps track of the last applied history revision so that only new changes
@@ -159,6 +161,7 @@ pub fn new(doc: DocumentId, gutters: GutterConfig) -> Self {
             last_modified_docs: [None, None],
             object_selections: Vec::new(),
             gutters,
+            breadcrumbs: false,
             doc_revisions: HashMap::new(),
         }
     }
@@ -171,11 +174,23 @@ pub fn add_to_history(&mut self, id: DocumentId) {
     }
 
     pub fn inner_area(&self, doc: &Document) -> Rect {
-        self.area.clip_left(self.gutter_offset(doc)).clip_bottom(1) // -1 for statusline
+        self.area
+            .clip_left(self.gutter_offset(doc))
+            .clip_top(self.header_height())
+            .clip_bottom(1) // -1 for statusline
     }
 
     pub fn inner_height(&self) -> usize {
-        self.area.clip_bottom(1).height.into() // -1 for statusline
+        self.area
+            .clip_top(self.header_height())
+            .clip_bottom(1) // -1 for statusline
+            .height
+            .into()
+    }
+
+    /// The number of rows above the document, taken by the breadcrumbs header.
+    pub fn header_height(&self) -> u16 {
+        u16::from(self.breadcrumbs).min(self.area.height.saturating_sub(1))
     }
 
     pub fn inner_width(&self, doc: &Document) -> u16 {
@@ -547,18 +562,19 @@ pub fn pos_at_visual_coords(
     /// Returns a tuple of usize typed line and column numbers starting with 0.
     /// Returns None if coordinates are not on the gutter.
     pub fn gutter_coords_at_screen_coords(&self, row: u16, column: u16) -> Option<Position> {
+        let area = self.area.clip_top(self.header_height());
         // 1 for status
-        if row < self.area.top() || row >= self.area.bottom() {
+        if row < area.top() || row >= area.bottom() {
             return None;
         }
 
-        if column < self.area.left() || column > self.area.right() {
+        if column < area.left() || column > area.right() {
             return None;
         }
 
         Some(Position::new(
-            (row - self.area.top()) as usize,
-            (column - self.area.left()) as usize,
+            (row - area.top()) as usize,
+            (column - area.left()) as usize,
         ))
     }
 
@@ -1016,4 +1032,45 @@ fn test_text_pos_at_screen_coords_graphemes() {
             Some(7)
         );
     }
+
+    #[test]
+    fn test_text_pos_at_screen_coords_breadcrumbs() {
+        let mut view = View::new(DocumentId::default(), GutterConfig::default());
+        view.area = Rect::new(40, 40, 40, 40);
+        view.breadcrumbs = true;
+        let rope = Rope::from_str("abc\ndef");
+        let doc = Document::from(
+            rope,
+            None,
+            Arc::new(ArcSwap::new(Arc::new(Config::default()))),
+        );
+
+        assert_eq!(view.inner_height(), 38);
+        assert_eq!(view.inner_area(&doc).y, 41);
+
+        // the header row is not part of the document
+        assert_eq!(
+            view.text_pos_at_screen_coords(
+                &doc,
+                40,
+                40 + DEFAULT_GUTTER_OFFSET + 1,
+                TextFormat::default(),
+                &TextAnnotations::default(),
+                true
+            ),
+            None
+        );
+
+        assert_eq!(
+            view.text_pos_at_screen_coords(
+                &doc,
+                42,
+                40 + DEFAULT_GUTTER_OFFSET + 1,
+                TextFormat::default(),
+                &TextAnnotations::default(),
+                true
+            ),
+            Some(5)
+        );
+    }
 }
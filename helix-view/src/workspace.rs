@@ -0,0 +1,260 @@
+//! State which is kept per workspace across restarts, such as how often and how recently
+//! files were opened, the last picker that was used and the breakpoints.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::editor::Breakpoint;
+
+/// The number of files remembered per workspace. The files with the lowest frecency are
+/// forgotten first.
+const MAX_FRECENCY_ENTRIES: usize = 1000;
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceState {
+    pub frecency: Frecency,
+    pub last_picker: Option<PickerState>,
+    pub breakpoints: HashMap<PathBuf, Vec<Breakpoint>>,
+    /// The file the state is loaded from and saved to, unset if the state isn't persisted.
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl WorkspaceState {
+    /// Loads the state of the workspace at `root`, or starts with an empty state if there
+    /// is none yet. Changes are written back by [WorkspaceState::save].
+    pub fn load(root: &Path) -> Self {
+        let path = Self::state_file(root);
+        let mut state = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| match serde_json::from_slice::<Self>(&bytes) {
+                Ok(state) => Some(state),
+                Err(err) => {
+                    log::warn!("failed to parse {}: {}", path.display(), err);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        state.path = Some(path);
+        state
+    }
+
+    /// Keeps the breakpoints to save, leaving out the temporary ones.
+    pub fn set_breakpoints(&mut self, breakpoints: &HashMap<PathBuf, Vec<Breakpoint>>) {
+        self.breakpoints = breakpoints
+            .iter()
+            .map(|(path, breakpoints)| {
+                let breakpoints: Vec<_> = breakpoints
+                    .iter()
+                    .filter(|breakpoint| !breakpoint.temporary)
+                    .cloned()
+                    .collect();
+                (path.clone(), breakpoints)
+            })
+            .filter(|(_, breakpoints)| !breakpoints.is_empty())
+            .collect();
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let bytes = serde_json::to_vec(self)?;
+        std::fs::write(path, bytes).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// The state of each workspace lives in its own file in the cache directory, named
+    /// after the workspace root with separators escaped.
+    fn state_file(root: &Path) -> PathBuf {
+        let name: String = root
+            .to_string_lossy()
+            .chars()
+            .map(|ch| {
+                if std::path::is_separator(ch) || ch == ':' {
+                    '%'
+                } else {
+                    ch
+                }
+            })
+            .collect();
+        helix_loader::cache_dir()
+            .join("workspaces")
+            .join(format!("{}.json", name))
+    }
+}
+
+/// The query and cursor of a picker, along with the command which opens it again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PickerState {
+    pub command: String,
+    pub query: String,
+    pub cursor: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct FrecencyEntry {
+    count: u32,
+    /// Seconds since the unix epoch
+    last_access: u64,
+}
+
+/// Tracks how often and how recently files were opened.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Frecency {
+    entries: HashMap<PathBuf, FrecencyEntry>,
+}
+
+impl Frecency {
+    pub fn record(&mut self, path: &Path) {
+        self.record_at(path, now());
+    }
+
+    fn record_at(&mut self, path: &Path, now: u64) {
+        let entry = self
+            .entries
+            .entry(path.to_path_buf())
+            .or_insert(FrecencyEntry {
+                count: 0,
+                last_access: now,
+            });
+        entry.count = entry.count.saturating_add(1);
+        entry.last_access = now;
+
+        if self.entries.len() > MAX_FRECENCY_ENTRIES {
+            let least = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| Self::entry_score(entry, now))
+                .map(|(path, _)| path.clone());
+            if let Some(least) = least {
+                self.entries.remove(&least);
+            }
+        }
+    }
+
+    /// Returns a score for the file at `path` which grows with the number of times it was
+    /// opened and shrinks as the last access gets older. Scores are kept in the range of
+    /// fuzzy match scores so they can be added to them without drowning the match quality.
+    pub fn score(&self, path: &Path) -> i64 {
+        self.score_at(path, now())
+    }
+
+    fn score_at(&self, path: &Path, now: u64) -> i64 {
+        self.entries
+            .get(path)
+            .map_or(0, |entry| Self::entry_score(entry, now))
+    }
+
+    fn entry_score(entry: &FrecencyEntry, now: u64) -> i64 {
+        const DAY: u64 = 24 * 60 * 60;
+
+        let weight = match now.saturating_sub(entry.last_access) {
+            age if age < 4 * DAY => 100,
+            age if age < 14 * DAY => 70,
+            age if age < 31 * DAY => 50,
+            age if age < 90 * DAY => 30,
+            _ => 10,
+        };
+        // roughly log2 of the count so frequently used files don't dominate
+        let frequency = (u32::BITS - entry.count.leading_zeros()) as i64;
+        weight * frequency / 4
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::editor::BreakpointState;
+
+    #[test]
+    fn test_frecency() {
+        const DAY: u64 = 24 * 60 * 60;
+        let now = 100 * DAY;
+        let mut frecency = Frecency::default();
+
+        frecency.record_at(Path::new("/often"), now - 50 * DAY);
+        frecency.record_at(Path::new("/often"), now - 50 * DAY);
+        frecency.record_at(Path::new("/often"), now - 50 * DAY);
+        frecency.record_at(Path::new("/recent"), now - DAY);
+        frecency.record_at(Path::new("/old"), now - 95 * DAY);
+
+        let score = |path| frecency.score_at(Path::new(path), now);
+        assert!(score("/recent") > score("/often"));
+        assert!(score("/often") > score("/old"));
+        assert!(score("/old") > score("/never"));
+        assert_eq!(score("/never"), 0);
+    }
+
+    #[test]
+    fn test_workspace_state_roundtrip() {
+        let mut state = WorkspaceState::default();
+        state.frecency.record_at(Path::new("/foo"), 42);
+        state.last_picker = Some(PickerState {
+            command: "file_picker".into(),
+            query: "foo".into(),
+            cursor: 2,
+        });
+
+        let mut breakpoints = HashMap::new();
+        breakpoints.insert(
+            PathBuf::from("/foo"),
+            vec![
+                Breakpoint {
+                    line: 3,
+                    condition: Some("x > 1".into()),
+                    states: HashMap::from([(
+                        1,
+                        BreakpointState {
+                            verified: true,
+                            ..Default::default()
+                        },
+                    )]),
+                    ..Default::default()
+                },
+                Breakpoint {
+                    line: 5,
+                    temporary: true,
+                    ..Default::default()
+                },
+            ],
+        );
+        breakpoints.insert(
+            PathBuf::from("/bar"),
+            vec![Breakpoint {
+                temporary: true,
+                ..Default::default()
+            }],
+        );
+        state.set_breakpoints(&breakpoints);
+
+        let json = serde_json::to_string(&state).unwrap();
+        let state: WorkspaceState = serde_json::from_str(&json).unwrap();
+        assert_eq!(state.frecency.score_at(Path::new("/foo"), 42), 25);
+        assert_eq!(state.last_picker.unwrap().query, "foo");
+        assert_eq!(state.breakpoints.len(), 1);
+        let breakpoints = &state.breakpoints[Path::new("/foo")];
+        assert_eq!(breakpoints.len(), 1);
+        assert_eq!(breakpoints[0].line, 3);
+        assert_eq!(breakpoints[0].condition.as_deref(), Some("x > 1"));
+        assert!(breakpoints[0].enabled);
+        assert!(!breakpoints[0].verified(1));
+    }
+}
@@ -34,3 +34,6 @@
 (comment) @comment.inside
 
 (comment)+ @comment.around
+
+(pair
+  value: (_) @entry.inside) @entry.around
@@ -0,0 +1,5 @@
+(pair
+  value: (_) @entry.inside) @entry.around
+
+(array
+  ((_) @entry.inside . ","? @entry.around) @entry.around)
@@ -21,3 +21,9 @@
    name: (identifier) @_name
    body: (block)? @test.inside) @test.around
  (#match? @_name "^test_"))
+
+(pair
+  value: (_) @entry.inside) @entry.around
+
+(keyword_argument
+  value: (_) @entry.inside) @entry.around
@@ -53,3 +53,9 @@
  (function_item
    body: (_) @test.inside) @test.around
  (#eq? @_test_attribute "test"))
+
+(field_initializer
+  value: (_) @entry.inside) @entry.around
+
+(field_declaration
+  type: (_) @entry.inside) @entry.around
@@ -0,0 +1,9 @@
+(pair
+  (_) @entry.inside .) @entry.around
+
+(array
+  ((_) @entry.inside . ","? @entry.around) @entry.around)
+
+(comment) @comment.inside
+
+(comment)+ @comment.around
@@ -0,0 +1,12 @@
+(block_mapping_pair
+  value: (_) @entry.inside) @entry.around
+
+(flow_pair
+  value: (_) @entry.inside) @entry.around
+
+(block_sequence_item
+  (_) @entry.inside) @entry.around
+
+(comment) @comment.inside
+
+(comment)+ @comment.around